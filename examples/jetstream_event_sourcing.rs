@@ -70,8 +70,13 @@ async fn main() -> Result<(), Box<dyn Error>> {
             name: "Example Author".to_string(),
             email: "author@example.com".to_string(),
         },
+        raw_author: None,
         message: "Initial commit".to_string(),
+        parsed_message: Default::default(),
+        signature: None,
         files_changed: vec![],
+        parent_diffs: vec![],
+        combined_files: vec![],
         commit_timestamp: Utc::now(),
         timestamp: Utc::now(),
     });
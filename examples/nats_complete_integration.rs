@@ -267,8 +267,13 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 name: format!("Author {}", i),
                 email: format!("author{}@example.com", i),
             },
+            raw_author: None,
             message: format!("Commit message {}", i),
+            parsed_message: Default::default(),
+            signature: None,
             files_changed: vec![],
+            parent_diffs: vec![],
+            combined_files: vec![],
             commit_timestamp: Utc::now(),
             timestamp: Utc::now(),
         });
@@ -186,13 +186,20 @@ async fn publish_example_events(
             name: "Example Author".to_string(),
             email: "author@example.com".to_string(),
         },
+        raw_author: None,
         message: "Initial commit".to_string(),
+        parsed_message: Default::default(),
+        signature: None,
         files_changed: vec![FileChangeInfo {
             path: FilePath::new("README.md")?,
             change_type: FileChangeType::Added,
             additions: 10,
             deletions: 0,
+            old_path: None,
+            binary: false,
         }],
+        parent_diffs: vec![],
+        combined_files: vec![],
         commit_timestamp: Utc::now(),
         timestamp: Utc::now(),
     });
@@ -5,15 +5,14 @@
 //! This module contains the aggregate roots that maintain consistency
 //! boundaries for Git-related operations.
 
-use crate::events::{GitDomainEvent, RepositoryCloned};
-use crate::value_objects::{AuthorInfo, BranchName, CommitHash, RemoteUrl};
+use crate::events::{BranchCreated, BranchDeleted, GitDomainEvent, RepositoryCloned, TagCreated};
+use crate::value_objects::{AuthorInfo, BranchName, CommitHash, RemoteUrl, TagName};
 use crate::GitDomainError;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
-
 /// Unique identifier for a repository
 ///
 /// # Examples
@@ -141,6 +140,18 @@ pub struct Repository {
     /// Branches in the repository
     pub branches: HashMap<BranchName, CommitHash>,
 
+    /// Branch HEAD points to, if known and not detached
+    #[serde(default)]
+    pub current_branch: Option<BranchName>,
+
+    /// Tags in the repository
+    #[serde(default)]
+    pub tags: HashMap<TagName, CommitHash>,
+
+    /// Last analyzed tip of each ref the history was walked from
+    #[serde(default)]
+    pub analyzed_tips: HashMap<String, CommitHash>,
+
     /// Repository metadata
     pub metadata: RepositoryMetadata,
 
@@ -187,6 +198,9 @@ impl Repository {
             local_path: None,
             head: None,
             branches: HashMap::new(),
+            current_branch: None,
+            tags: HashMap::new(),
+            analyzed_tips: HashMap::new(),
             metadata: RepositoryMetadata {
                 name,
                 description: None,
@@ -208,9 +222,7 @@ impl Repository {
         local_path: String,
     ) -> Result<Vec<GitDomainEvent>, GitDomainError> {
         if self.local_path.is_some() {
-            return Err(GitDomainError::GitOperationFailed(
-                "Repository already cloned".to_string(),
-            ));
+            return Err(GitDomainError::RepositoryAlreadyCloned(self.id));
         }
 
         let event = RepositoryCloned {
@@ -225,6 +237,99 @@ impl Repository {
         Ok(vec![GitDomainEvent::RepositoryCloned(event)])
     }
 
+    /// Handle a create branch command
+    ///
+    /// Fails if the branch already exists. With `checkout` the new branch
+    /// becomes the current branch.
+    pub fn create_branch(
+        &mut self,
+        branch_name: BranchName,
+        commit_hash: CommitHash,
+        source_branch: Option<BranchName>,
+        checkout: bool,
+    ) -> Result<Vec<GitDomainEvent>, GitDomainError> {
+        if self.branches.contains_key(&branch_name) {
+            return Err(GitDomainError::BranchAlreadyExists(branch_name.to_string()));
+        }
+
+        let event = GitDomainEvent::BranchCreated(BranchCreated {
+            repository_id: self.id,
+            branch_name,
+            commit_hash,
+            source_branch,
+            checked_out: checkout,
+            timestamp: Utc::now(),
+        });
+
+        self.apply_event(&event)?;
+
+        Ok(vec![event])
+    }
+
+    /// Handle a delete branch command
+    ///
+    /// The current branch can never be deleted. A branch that is not merged
+    /// into HEAD is only deleted with `force`.
+    pub fn delete_branch(
+        &mut self,
+        branch_name: BranchName,
+        last_commit: CommitHash,
+        merged: bool,
+        force: bool,
+    ) -> Result<Vec<GitDomainEvent>, GitDomainError> {
+        if self.current_branch.as_ref() == Some(&branch_name) {
+            return Err(GitDomainError::CannotDeleteCurrentBranch(
+                branch_name.to_string(),
+            ));
+        }
+
+        if !merged && !force {
+            return Err(GitDomainError::BranchNotMerged(branch_name.to_string()));
+        }
+
+        let event = GitDomainEvent::BranchDeleted(BranchDeleted {
+            repository_id: self.id,
+            branch_name,
+            last_commit,
+            timestamp: Utc::now(),
+        });
+
+        self.apply_event(&event)?;
+
+        Ok(vec![event])
+    }
+
+    /// Handle a create tag command
+    ///
+    /// Fails if the tag already exists. `message` and `tagger` are only set
+    /// for annotated tags.
+    pub fn create_tag(
+        &mut self,
+        tag_name: TagName,
+        commit_hash: CommitHash,
+        message: Option<String>,
+        tagger: Option<AuthorInfo>,
+    ) -> Result<Vec<GitDomainEvent>, GitDomainError> {
+        if self.tags.contains_key(&tag_name) {
+            return Err(GitDomainError::TagAlreadyExists(tag_name.to_string()));
+        }
+
+        let event = GitDomainEvent::TagCreated(TagCreated {
+            repository_id: self.id,
+            tag_name,
+            commit_hash,
+            annotated: message.is_some(),
+            message,
+            tagger,
+            signature: None,
+            timestamp: Utc::now(),
+        });
+
+        self.apply_event(&event)?;
+
+        Ok(vec![event])
+    }
+
     /// Apply an event to update the aggregate state
     pub fn apply_event(&mut self, event: &GitDomainEvent) -> Result<(), GitDomainError> {
         match event {
@@ -240,6 +345,54 @@ impl Repository {
             GitDomainEvent::BranchCreated(e) => {
                 self.branches
                     .insert(e.branch_name.clone(), e.commit_hash.clone());
+                if e.checked_out {
+                    self.current_branch = Some(e.branch_name.clone());
+                    self.head = Some(e.commit_hash.clone());
+                }
+                self.metadata.updated_at = e.timestamp;
+            }
+            GitDomainEvent::BranchDeleted(e) => {
+                self.branches.remove(&e.branch_name);
+                self.metadata.updated_at = e.timestamp;
+            }
+            GitDomainEvent::BranchMoved(e) => {
+                self.branches
+                    .insert(e.branch_name.clone(), e.new_commit.clone());
+                self.metadata.updated_at = e.timestamp;
+            }
+            GitDomainEvent::TagCreated(e) => {
+                self.tags.insert(e.tag_name.clone(), e.commit_hash.clone());
+                self.metadata.updated_at = e.timestamp;
+            }
+            GitDomainEvent::TagDeleted(e) => {
+                self.tags.remove(&e.tag_name);
+                self.metadata.updated_at = e.timestamp;
+            }
+            GitDomainEvent::RemoteFetched(e) => {
+                // Remote-tracking branches are tracked by their short name
+                for update in &e.ref_updates {
+                    let name = update
+                        .ref_name
+                        .strip_prefix("refs/remotes/")
+                        .and_then(|name| BranchName::new(name).ok());
+                    match (name, &update.new_target) {
+                        (Some(name), Some(target)) => {
+                            self.branches.insert(name, target.clone());
+                        }
+                        (Some(name), None) => {
+                            self.branches.remove(&name);
+                        }
+                        (None, _) => {}
+                    }
+                }
+                self.metadata.updated_at = e.timestamp;
+            }
+            GitDomainEvent::RepositoryAnalyzed(e) => {
+                self.metadata.name.clone_from(&e.name);
+                self.local_path = Some(e.path.clone());
+                self.current_branch.clone_from(&e.head_branch);
+                self.analyzed_tips
+                    .extend(e.ref_tips.iter().map(|(r, tip)| (r.clone(), tip.clone())));
                 self.metadata.updated_at = e.timestamp;
             }
             _ => {} // Handle other events as needed
@@ -364,7 +517,64 @@ mod tests {
 
         assert_eq!(events.len(), 1);
         assert!(repo.remote_url.is_some());
-        assert_eq!(repo.local_path, Some(local_path));
+        assert_eq!(repo.local_path, Some(local_path.clone()));
+        assert_eq!(repo.version, 1);
+
+        assert!(matches!(
+            repo.clone_repository(remote_url, local_path),
+            Err(GitDomainError::RepositoryAlreadyCloned(id)) if id == repo.id
+        ));
+    }
+
+    #[test]
+    fn test_branch_invariants() {
+        let mut repo = Repository::new("test-repo".to_string());
+        let main = BranchName::new("main").unwrap();
+        let feature = BranchName::new("feature").unwrap();
+        let commit = CommitHash::new("abc123def456789").unwrap();
+
+        repo.create_branch(main.clone(), commit.clone(), None, true)
+            .unwrap();
+        repo.create_branch(feature.clone(), commit.clone(), Some(main.clone()), false)
+            .unwrap();
+        assert_eq!(repo.current_branch, Some(main.clone()));
+        assert_eq!(repo.head, Some(commit.clone()));
+
+        assert!(matches!(
+            repo.create_branch(feature.clone(), commit.clone(), None, false),
+            Err(GitDomainError::BranchAlreadyExists(_))
+        ));
+        assert!(matches!(
+            repo.delete_branch(main.clone(), commit.clone(), true, true),
+            Err(GitDomainError::CannotDeleteCurrentBranch(_))
+        ));
+        assert!(matches!(
+            repo.delete_branch(feature.clone(), commit.clone(), false, false),
+            Err(GitDomainError::BranchNotMerged(_))
+        ));
+
+        let events = repo
+            .delete_branch(feature.clone(), commit, false, true)
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(!repo.branches.contains_key(&feature));
+        assert_eq!(repo.version, 3);
+    }
+
+    #[test]
+    fn test_duplicate_tag_rejected() {
+        let mut repo = Repository::new("test-repo".to_string());
+        let tag = TagName::new("v1.0.0").unwrap();
+        let commit = CommitHash::new("abc123def456789").unwrap();
+
+        repo.create_tag(tag.clone(), commit.clone(), None, None)
+            .unwrap();
+        assert_eq!(repo.tags.get(&tag), Some(&commit));
+
+        assert!(matches!(
+            repo.create_tag(tag, commit, Some("again".to_string()), None),
+            Err(GitDomainError::TagAlreadyExists(_))
+        ));
         assert_eq!(repo.version, 1);
     }
 }
@@ -0,0 +1,229 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! Line attribution for blame-weighted ownership
+//!
+//! libgit2 blames whitespace-insensitively on request, but has no support for
+//! ignored revisions and does not implement its copy-tracking flags. Both are
+//! layered on top here: lines attributed to an ignored commit, or moved within
+//! the file by their commit, are passed on to the matching line of the first
+//! parent's version of the file. Authors are mapped through the repository's
+//! `.mailmap` and the configured identity resolver.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::rc::Rc;
+
+use git2::{BlameOptions, ErrorCode, ObjectType, Oid, Repository as Git2Repository};
+
+use crate::identity::{author_info, IdentityMapper};
+use crate::value_objects::AuthorInfo;
+use crate::GitDomainError;
+
+/// Alphanumeric characters a line needs before a move is recognised,
+/// matching git's default `blame.moveThreshold`
+const MOVE_THRESHOLD: usize = 20;
+
+/// Maximum number of parents a line is followed through
+const MAX_PASSES: usize = 64;
+
+/// Blame of one file at one commit
+struct FileBlame {
+    /// Line contents, normalised for comparison
+    lines: Vec<String>,
+
+    /// Commit, path within that commit and author each line is attributed to
+    origins: Vec<(Oid, String, AuthorInfo)>,
+}
+
+/// Attributes the lines of files to the authors who wrote them
+pub(crate) struct LineBlamer<'repo> {
+    git_repo: &'repo Git2Repository,
+    identities: IdentityMapper<'repo>,
+    ignore_whitespace: bool,
+    detect_moves: bool,
+    ignored: HashSet<Oid>,
+    cache: HashMap<(Oid, String), Option<Rc<FileBlame>>>,
+}
+
+impl<'repo> LineBlamer<'repo> {
+    pub(crate) fn new(
+        git_repo: &'repo Git2Repository,
+        identities: IdentityMapper<'repo>,
+        ignore_whitespace: bool,
+        detect_moves: bool,
+        ignored: HashSet<Oid>,
+    ) -> Self {
+        Self {
+            git_repo,
+            identities,
+            ignore_whitespace,
+            detect_moves,
+            ignored,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Author of every line of `path` as of `commit`, or `None` if the
+    /// commit has no such file
+    pub(crate) fn line_authors(
+        &mut self,
+        commit: Oid,
+        path: &str,
+    ) -> Result<Option<Vec<AuthorInfo>>, GitDomainError> {
+        match self.blame(commit, path)? {
+            Some(blame) => blame
+                .lines
+                .iter()
+                .zip(&blame.origins)
+                .map(|(line, origin)| self.author_of(line, origin.clone()))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Follow a line through ignored commits and moves to its author
+    fn author_of(
+        &mut self,
+        line: &str,
+        mut origin: (Oid, String, AuthorInfo),
+    ) -> Result<AuthorInfo, GitDomainError> {
+        for _ in 0..MAX_PASSES {
+            let (commit, path, author) = origin;
+            let ignored = self.ignored.contains(&commit);
+            let movable = self.detect_moves
+                && line.chars().filter(|c| c.is_alphanumeric()).count() >= MOVE_THRESHOLD;
+            if !ignored && !movable {
+                return Ok(author);
+            }
+
+            // Root commits and files new in the commit have nothing to pass on to
+            let previous = match self
+                .git_repo
+                .find_commit(commit)
+                .and_then(|c| c.parent_id(0))
+            {
+                Ok(parent) => self.blame(parent, &path)?,
+                Err(_) => None,
+            };
+            let matched = previous.and_then(|previous| {
+                let index = previous
+                    .lines
+                    .iter()
+                    .position(|candidate| candidate == line)?;
+                Some(previous.origins[index].clone())
+            });
+
+            match matched {
+                Some(previous_origin) => origin = previous_origin,
+                None => return Ok(author),
+            }
+        }
+
+        Ok(origin.2)
+    }
+
+    /// Blame `path` at `commit`, reusing earlier results
+    fn blame(&mut self, commit: Oid, path: &str) -> Result<Option<Rc<FileBlame>>, GitDomainError> {
+        let key = (commit, path.to_string());
+        if let Some(cached) = self.cache.get(&key) {
+            return Ok(cached.clone());
+        }
+
+        let blame = self.blame_uncached(commit, path)?.map(Rc::new);
+        self.cache.insert(key, blame.clone());
+        Ok(blame)
+    }
+
+    fn blame_uncached(&self, commit: Oid, path: &str) -> Result<Option<FileBlame>, GitDomainError> {
+        let blame_error = |e: git2::Error| {
+            GitDomainError::GitOperationFailed(format!("Failed to blame {path} at {commit}: {e}"))
+        };
+
+        let entry = match self
+            .git_repo
+            .find_commit(commit)
+            .and_then(|c| c.tree())
+            .and_then(|tree| tree.get_path(Path::new(path)))
+        {
+            Ok(entry) => entry,
+            Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(blame_error(e)),
+        };
+        // Submodules and directories have no lines to attribute
+        if entry.kind() != Some(ObjectType::Blob) {
+            return Ok(None);
+        }
+        let blob = self.git_repo.find_blob(entry.id()).map_err(blame_error)?;
+        if blob.is_binary() {
+            return Ok(None);
+        }
+
+        let mut options = BlameOptions::new();
+        options
+            .newest_commit(commit)
+            .ignore_whitespace(self.ignore_whitespace)
+            .use_mailmap(true);
+        let blame = self
+            .git_repo
+            .blame_file(Path::new(path), Some(&mut options))
+            .map_err(blame_error)?;
+
+        let mut origins = Vec::new();
+        for hunk in blame.iter() {
+            let author = self
+                .identities
+                .canonical(author_info(&hunk.final_signature()));
+            let hunk_path = hunk
+                .path()
+                .and_then(Path::to_str)
+                .unwrap_or(path)
+                .to_string();
+            for _ in 0..hunk.lines_in_hunk() {
+                origins.push((hunk.final_commit_id(), hunk_path.clone(), author.clone()));
+            }
+        }
+
+        let content = String::from_utf8_lossy(blob.content());
+        let lines = content
+            .lines()
+            .map(|line| self.normalise(line))
+            .take(origins.len())
+            .collect();
+
+        Ok(Some(FileBlame { lines, origins }))
+    }
+
+    fn normalise(&self, line: &str) -> String {
+        if self.ignore_whitespace {
+            line.split_whitespace().collect()
+        } else {
+            line.to_string()
+        }
+    }
+}
+
+/// Parse a `.git-blame-ignore-revs` style list into commit IDs
+///
+/// Blank lines and `#` comments are skipped; every other line must name a
+/// commit of the repository.
+pub(crate) fn parse_ignore_revs(
+    git_repo: &Git2Repository,
+    content: &str,
+) -> Result<HashSet<Oid>, GitDomainError> {
+    let mut ignored = HashSet::new();
+    for line in content.lines() {
+        let revision = line.split('#').next().unwrap_or_default().trim();
+        if revision.is_empty() {
+            continue;
+        }
+
+        let commit = git_repo
+            .revparse_single(revision)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|e| GitDomainError::InvalidCommitHash(format!("{revision}: {e}")))?;
+        ignored.insert(commit.id());
+    }
+
+    Ok(ignored)
+}
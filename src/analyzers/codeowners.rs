@@ -0,0 +1,260 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! CODEOWNERS parsing
+//!
+//! Understands both the GitHub and the GitLab flavour of the file: lines of a
+//! gitignore-style pattern followed by owners, and GitLab's `[Section]` headers
+//! with optional default owners for the patterns below them. Within a section
+//! the last matching pattern wins, and a path is owned by the owners of every
+//! section that has a matching pattern. A file without section headers is one
+//! section, so it behaves exactly as it does on GitHub.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use git2::{ErrorCode, ObjectType, Repository as Git2Repository};
+
+use crate::path_glob::PathGlob;
+use crate::value_objects::AuthorInfo;
+use crate::GitDomainError;
+
+/// Where a CODEOWNERS file is looked for, in order of precedence
+const LOCATIONS: [&str; 4] = [
+    ".github/CODEOWNERS",
+    "CODEOWNERS",
+    "docs/CODEOWNERS",
+    ".gitlab/CODEOWNERS",
+];
+
+/// A pattern and the owners it assigns
+struct Rule {
+    glob: PathGlob,
+    owners: Vec<String>,
+}
+
+/// Rules under one section header, in file order
+struct Section {
+    name: String,
+    rules: Vec<Rule>,
+}
+
+/// Owners declared by a CODEOWNERS file
+pub struct CodeOwners {
+    sections: Vec<Section>,
+
+    /// Author emails of owners that are not a single person, such as teams
+    aliases: HashMap<String, Vec<String>>,
+}
+
+impl CodeOwners {
+    /// Parse the contents of a CODEOWNERS file
+    ///
+    /// Patterns without owners leave matching paths unowned, unless their
+    /// section header names default owners. A section header repeated later
+    /// in the file continues the earlier section.
+    pub fn parse(content: &str) -> Result<Self, GitDomainError> {
+        let mut sections = vec![Section {
+            name: String::new(),
+            rules: Vec::new(),
+        }];
+        let mut current = 0;
+        let mut default_owners = Vec::new();
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with('[') || line.starts_with("^[") {
+                let (name, owners) = parse_section_header(line).ok_or_else(|| {
+                    GitDomainError::ValidationError(format!(
+                        "Invalid CODEOWNERS section header on line {}: {line}",
+                        index + 1
+                    ))
+                })?;
+                let existing = sections
+                    .iter()
+                    .position(|section| section.name.eq_ignore_ascii_case(&name));
+                current = existing.unwrap_or_else(|| {
+                    sections.push(Section {
+                        name,
+                        rules: Vec::new(),
+                    });
+                    sections.len() - 1
+                });
+                default_owners = owners;
+                continue;
+            }
+
+            let mut fields = split_fields(line).into_iter();
+            let pattern = fields.next().unwrap_or_default();
+            let mut owners: Vec<String> =
+                fields.take_while(|field| !field.starts_with('#')).collect();
+            if owners.is_empty() {
+                owners.clone_from(&default_owners);
+            }
+
+            sections[current].rules.push(Rule {
+                glob: PathGlob::codeowners(&pattern)?,
+                owners,
+            });
+        }
+
+        Ok(Self {
+            sections,
+            aliases: HashMap::new(),
+        })
+    }
+
+    /// Load the CODEOWNERS file of a revision, if it has one
+    ///
+    /// Looks in `.github/`, the repository root, `docs/` and `.gitlab/`, and
+    /// uses the first file found.
+    pub fn load(git_repo: &Git2Repository, revision: &str) -> Result<Option<Self>, GitDomainError> {
+        let read_error = |e: git2::Error| {
+            GitDomainError::GitOperationFailed(format!(
+                "Failed to read CODEOWNERS at {revision}: {e}"
+            ))
+        };
+
+        let tree = git_repo
+            .revparse_single(revision)
+            .and_then(|object| object.peel_to_tree())
+            .map_err(read_error)?;
+        for location in LOCATIONS {
+            let entry = match tree.get_path(Path::new(location)) {
+                Ok(entry) if entry.kind() == Some(ObjectType::Blob) => entry,
+                Ok(_) => continue,
+                Err(e) if e.code() == ErrorCode::NotFound => continue,
+                Err(e) => return Err(read_error(e)),
+            };
+            let blob = git_repo.find_blob(entry.id()).map_err(read_error)?;
+            return Self::parse(&String::from_utf8_lossy(blob.content())).map(Some);
+        }
+
+        Ok(None)
+    }
+
+    /// Match a group owner, such as `@org/team`, to the given author emails
+    ///
+    /// Also overrides how a user or email owner is matched.
+    #[must_use]
+    pub fn with_alias<I, S>(mut self, owner: impl Into<String>, emails: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.aliases
+            .insert(owner.into(), emails.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Owners of a path, in declaration order
+    ///
+    /// Empty if no pattern matches or the matching patterns name no owners.
+    #[must_use]
+    pub fn owners_of(&self, path: &str) -> Vec<String> {
+        let mut owners: Vec<String> = Vec::new();
+        for section in &self.sections {
+            if let Some(rule) = section
+                .rules
+                .iter()
+                .rev()
+                .find(|rule| rule.glob.matches(path))
+            {
+                for owner in &rule.owners {
+                    if !owners.contains(owner) {
+                        owners.push(owner.clone());
+                    }
+                }
+            }
+        }
+        owners
+    }
+
+    /// Whether an owner can be matched to authors at all
+    ///
+    /// Groups and roles can only be matched through an alias.
+    #[must_use]
+    pub fn resolves(&self, owner: &str) -> bool {
+        self.aliases.contains_key(owner)
+            || !owner
+                .strip_prefix('@')
+                .is_some_and(|handle| handle.contains('/') || handle.starts_with('@'))
+    }
+
+    /// Whether an owner refers to the given author
+    ///
+    /// Email owners match the author's email and `@user` owners the author's
+    /// name or the local part of their email, all ignoring case.
+    #[must_use]
+    pub fn is_owner(&self, owner: &str, author: &AuthorInfo) -> bool {
+        if let Some(emails) = self.aliases.get(owner) {
+            return emails
+                .iter()
+                .any(|email| email.eq_ignore_ascii_case(&author.email));
+        }
+        if !self.resolves(owner) {
+            return false;
+        }
+
+        match owner.strip_prefix('@') {
+            Some(handle) => {
+                handle.eq_ignore_ascii_case(&author.name)
+                    || author
+                        .email
+                        .split('@')
+                        .next()
+                        .is_some_and(|local| local.eq_ignore_ascii_case(handle))
+            }
+            None => owner.eq_ignore_ascii_case(&author.email),
+        }
+    }
+}
+
+/// Name and default owners of a GitLab section header
+///
+/// Headers look like `[Name]`, `^[Optional name]` or `[Name][2]`, optionally
+/// followed by owners.
+fn parse_section_header(line: &str) -> Option<(String, Vec<String>)> {
+    let rest = line.strip_prefix('^').unwrap_or(line).strip_prefix('[')?;
+    let (name, mut rest) = rest.split_once(']')?;
+    // Required approvals do not change who owns a path
+    if let Some(approvals) = rest.strip_prefix('[') {
+        rest = approvals.split_once(']')?.1;
+    }
+
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let owners = split_fields(rest)
+        .into_iter()
+        .take_while(|field| !field.starts_with('#'))
+        .collect();
+
+    Some((name.to_string(), owners))
+}
+
+/// Split a line on whitespace, honouring backslash escapes
+fn split_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => field.extend(chars.next()),
+            c if c.is_whitespace() => {
+                if !field.is_empty() {
+                    fields.push(std::mem::take(&mut field));
+                }
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() {
+        fields.push(field);
+    }
+    fields
+}
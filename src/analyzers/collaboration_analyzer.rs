@@ -8,14 +8,71 @@
 use chrono::{DateTime, Utc};
 #[cfg(test)]
 use chrono::Duration;
+use git2::Repository as Git2Repository;
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
 
+use super::blame::{parse_ignore_revs, LineBlamer};
+use super::codeowners::CodeOwners;
 use crate::{
     aggregate::RepositoryId,
     events::collaboration_events::*,
-    value_objects::{AuthorInfo, CommitHash, FilePath},
+    identity::{IdentityMapper, IdentityResolver},
+    value_objects::{AuthorInfo, CommitHash, CommitMessage, FilePath, Trailer},
+    GitDomainError,
 };
 
+/// Trailers that record a review of the commit
+const REVIEW_TRAILERS: [&str; 2] = ["Reviewed-by", "Acked-by"];
+
+/// Options for blame-weighted code ownership
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlameOwnershipOptions {
+    /// Revision whose surviving lines are attributed, `HEAD` when not given
+    pub revision: Option<String>,
+    
+    /// Ignore whitespace changes when attributing lines, like `git blame -w`
+    pub ignore_whitespace: bool,
+    
+    /// Credit lines moved within a file to their original author, like `git blame -M`
+    pub detect_moves: bool,
+    
+    /// File listing revisions whose changes are not credited, such as
+    /// `.git-blame-ignore-revs`; relative paths start at the working directory
+    pub ignore_revs_file: Option<PathBuf>,
+}
+
+impl BlameOwnershipOptions {
+    /// Attribute the lines of the given revision instead of `HEAD`
+    #[must_use]
+    pub fn with_revision(mut self, revision: impl Into<String>) -> Self {
+        self.revision = Some(revision.into());
+        self
+    }
+    
+    /// Ignore whitespace changes
+    #[must_use]
+    pub fn ignore_whitespace(mut self) -> Self {
+        self.ignore_whitespace = true;
+        self
+    }
+    
+    /// Credit moved lines to their original author
+    #[must_use]
+    pub fn detect_moves(mut self) -> Self {
+        self.detect_moves = true;
+        self
+    }
+    
+    /// Skip the revisions listed in the given file
+    #[must_use]
+    pub fn with_ignore_revs_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ignore_revs_file = Some(path.into());
+        self
+    }
+}
+
 /// Analyzes collaboration patterns in a repository
 pub struct CollaborationAnalyzer {
     /// Time window for considering commits as collaborative
@@ -23,6 +80,9 @@ pub struct CollaborationAnalyzer {
     
     /// Minimum shared files to consider collaboration
     min_shared_files: usize,
+    
+    /// Resolves the authors of blamed lines after the repository's mailmap
+    identity_resolver: Option<Arc<dyn IdentityResolver>>,
 }
 
 impl CollaborationAnalyzer {
@@ -31,9 +91,20 @@ impl CollaborationAnalyzer {
         Self {
             collaboration_window_hours: 168, // 1 week
             min_shared_files: 2,
+            identity_resolver: None,
         }
     }
     
+    /// Resolve the authors of blamed lines with the given resolver
+    ///
+    /// Use the resolver the commits were analyzed with, so line ownership
+    /// names the same identities as commit-based ownership.
+    #[must_use]
+    pub fn with_identity_resolver(mut self, resolver: impl IdentityResolver + 'static) -> Self {
+        self.identity_resolver = Some(Arc::new(resolver));
+        self
+    }
+    
     /// Analyze commits to find collaboration patterns
     pub fn analyze_collaboration(
         &self,
@@ -150,6 +221,10 @@ impl CollaborationAnalyzer {
                 ownership_percentage,
                 contributors,
                 total_commits,
+                line_owner: None,
+                line_ownership_percentage: None,
+                line_contributors: Vec::new(),
+                total_lines: 0,
                 timestamp: Utc::now(),
             });
         }
@@ -157,6 +232,217 @@ impl CollaborationAnalyzer {
         ownership_events
     }
     
+    /// Detect code ownership weighted by the surviving lines each author wrote
+    ///
+    /// Commit-based ownership is calculated as by [`Self::calculate_ownership`],
+    /// and the line-based fields are filled from a blame of each file at the
+    /// requested revision. Files that do not exist there, or are binary, keep
+    /// empty line ownership.
+    pub fn calculate_blame_ownership(
+        &self,
+        repository_id: RepositoryId,
+        git_repo: &Git2Repository,
+        file_commits: &HashMap<FilePath, Vec<(AuthorInfo, DateTime<Utc>)>>,
+        options: &BlameOwnershipOptions,
+    ) -> Result<Vec<CodeOwnershipCalculated>, GitDomainError> {
+        let revision = options.revision.as_deref().unwrap_or("HEAD");
+        let commit = git_repo
+            .revparse_single(revision)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|e| {
+                GitDomainError::GitOperationFailed(format!(
+                    "Failed to resolve revision {revision}: {e}"
+                ))
+            })?;
+        
+        let ignored = match &options.ignore_revs_file {
+            Some(file) => {
+                let path = git_repo.workdir().unwrap_or_else(|| git_repo.path()).join(file);
+                let content = std::fs::read_to_string(&path).map_err(|e| {
+                    GitDomainError::GitOperationFailed(format!(
+                        "Failed to read {}: {e}",
+                        path.display()
+                    ))
+                })?;
+                parse_ignore_revs(git_repo, &content)?
+            }
+            None => HashSet::new(),
+        };
+        
+        let mut blamer = LineBlamer::new(
+            git_repo,
+            IdentityMapper::new(git_repo, self.identity_resolver.as_deref()),
+            options.ignore_whitespace,
+            options.detect_moves,
+            ignored,
+        );
+        let mut ownership_events = self.calculate_ownership(repository_id, file_commits);
+        
+        for ownership in &mut ownership_events {
+            let authors = match blamer.line_authors(commit.id(), ownership.path.as_str())? {
+                Some(authors) if !authors.is_empty() => authors,
+                _ => continue,
+            };
+            
+            let mut line_counts: HashMap<AuthorInfo, u32> = HashMap::new();
+            for author in authors {
+                *line_counts.entry(author).or_insert(0) += 1;
+            }
+            let total_lines: u32 = line_counts.values().sum();
+            
+            // Largest share first, ties broken by name for stable results
+            let mut shares: Vec<(AuthorInfo, f64)> = line_counts
+                .into_iter()
+                .map(|(author, count)| (author, f64::from(count) / f64::from(total_lines)))
+                .collect();
+            shares.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.name.cmp(&b.0.name)));
+            
+            let (line_owner, line_percentage) = shares.remove(0);
+            ownership.line_owner = Some(line_owner);
+            ownership.line_ownership_percentage = Some(line_percentage);
+            ownership.line_contributors = shares;
+            ownership.total_lines = total_lines;
+        }
+        
+        Ok(ownership_events)
+    }
+    
+    /// Detect paths whose declared owners disagree with calculated ownership
+    ///
+    /// A path drifts when one of the owners CODEOWNERS declares for it has not
+    /// contributed to it, or when its top contributor is not a declared owner.
+    /// The top contributor is the line owner of blame-weighted ownership and
+    /// the primary owner otherwise. Owners that cannot be matched to authors,
+    /// such as teams without an alias, are never reported inactive and keep
+    /// the top contributor from being reported as unlisted.
+    pub fn detect_ownership_drift(
+        &self,
+        repository_id: RepositoryId,
+        codeowners: &CodeOwners,
+        ownership: &[CodeOwnershipCalculated],
+    ) -> Vec<OwnershipDriftDetected> {
+        let mut drifts = Vec::new();
+        
+        for calculated in ownership {
+            let declared_owners = codeowners.owners_of(calculated.path.as_str());
+            let contributors: Vec<&AuthorInfo> = std::iter::once(&calculated.primary_owner)
+                .chain(calculated.contributors.iter().map(|(author, _)| author))
+                .chain(calculated.line_owner.iter())
+                .chain(calculated.line_contributors.iter().map(|(author, _)| author))
+                .collect();
+            
+            let inactive_owners: Vec<String> = declared_owners
+                .iter()
+                .filter(|owner| {
+                    codeowners.resolves(owner)
+                        && !contributors.iter().any(|author| codeowners.is_owner(owner, author))
+                })
+                .cloned()
+                .collect();
+            
+            let top_contributor = calculated
+                .line_owner
+                .clone()
+                .unwrap_or_else(|| calculated.primary_owner.clone());
+            let top_contributor_listed = declared_owners.iter().any(|owner| {
+                !codeowners.resolves(owner) || codeowners.is_owner(owner, &top_contributor)
+            });
+            
+            if inactive_owners.is_empty() && top_contributor_listed {
+                continue;
+            }
+            
+            drifts.push(OwnershipDriftDetected {
+                repository_id,
+                path: calculated.path.clone(),
+                declared_owners,
+                inactive_owners,
+                top_contributor,
+                top_contributor_listed,
+                timestamp: Utc::now(),
+            });
+        }
+        
+        drifts
+    }
+    
+    /// Detect code reviews recorded in commit trailers
+    ///
+    /// Each `Reviewed-by:` or `Acked-by:` trailer counts as a review of the
+    /// commit's author and of every `Co-authored-by:` co-author. One event is
+    /// produced per author and reviewer, listing the files of all commits the
+    /// reviewer reviewed for that author, with the number of those commits as
+    /// review intensity. People reviewing their own commits are skipped.
+    pub fn detect_code_reviews(
+        &self,
+        repository_id: RepositoryId,
+        commits: &[(CommitHash, AuthorInfo, CommitMessage, Vec<FilePath>)],
+    ) -> Vec<CodeReviewDetected> {
+        let mut reviews: Vec<CodeReviewDetected> = Vec::new();
+        let mut index: HashMap<(AuthorInfo, AuthorInfo), usize> = HashMap::new();
+        
+        for (_, author, message, files) in commits {
+            let trailers = message.trailers();
+            let authors = people(
+                std::iter::once(author.clone()).chain(
+                    trailers
+                        .iter()
+                        .filter(|trailer| trailer.is("Co-authored-by"))
+                        .filter_map(Trailer::identity),
+                ),
+            );
+            let reviewers = people(
+                trailers
+                    .iter()
+                    .filter(|trailer| REVIEW_TRAILERS.iter().any(|key| trailer.is(key)))
+                    .filter_map(Trailer::identity),
+            );
+            
+            for reviewer in &reviewers {
+                for author in &authors {
+                    if reviewer.email.eq_ignore_ascii_case(&author.email) {
+                        continue;
+                    }
+                    
+                    let slot = *index
+                        .entry((author.clone(), reviewer.clone()))
+                        .or_insert_with(|| {
+                            reviews.push(CodeReviewDetected {
+                                repository_id,
+                                author: author.clone(),
+                                reviewer: reviewer.clone(),
+                                reviewed_files: Vec::new(),
+                                review_intensity: 0,
+                                timestamp: Utc::now(),
+                            });
+                            reviews.len() - 1
+                        });
+                    let review = &mut reviews[slot];
+                    review.review_intensity += 1;
+                    for file in files {
+                        if !review.reviewed_files.contains(file) {
+                            review.reviewed_files.push(file.clone());
+                        }
+                    }
+                }
+            }
+        }
+        
+        reviews
+    }
+    
+    /// Number of commits each reviewer reviewed, busiest reviewer first
+    pub fn review_load(&self, reviews: &[CodeReviewDetected]) -> Vec<(AuthorInfo, u32)> {
+        let mut load: HashMap<AuthorInfo, u32> = HashMap::new();
+        for review in reviews {
+            *load.entry(review.reviewer.clone()).or_default() += review.review_intensity;
+        }
+        
+        let mut load: Vec<(AuthorInfo, u32)> = load.into_iter().collect();
+        load.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.name.cmp(&b.0.name)));
+        load
+    }
+    
     /// Detect team clusters based on collaboration patterns
     pub fn detect_team_clusters(
         &self,
@@ -255,6 +541,17 @@ impl CollaborationAnalyzer {
     }
 }
 
+/// Distinct people of a list of identities, by email
+fn people(identities: impl Iterator<Item = AuthorInfo>) -> Vec<AuthorInfo> {
+    let mut people: Vec<AuthorInfo> = Vec::new();
+    for identity in identities {
+        if !people.iter().any(|person| person.email.eq_ignore_ascii_case(&identity.email)) {
+            people.push(identity);
+        }
+    }
+    people
+}
+
 impl Default for CollaborationAnalyzer {
     fn default() -> Self {
         Self::new()
@@ -300,4 +597,77 @@ mod tests {
         assert_eq!(collaborations[0].shared_files.len(), 2);
         assert!(collaborations[0].collaboration_strength > 0.5);
     }
+    
+    #[test]
+    fn test_code_review_detection_from_trailers() {
+        let analyzer = CollaborationAnalyzer::new();
+        let repo_id = RepositoryId::new();
+        
+        let message = |text: &str| CommitMessage::new(text).unwrap();
+        let commits = vec![
+            (
+                CommitHash::new("abc123def456789").unwrap(),
+                create_test_author("Alice"),
+                message(
+                    "Add parser\n\nReviewed-by: Bob <bob@example.com>\n\
+                     Co-authored-by: Carol <carol@example.com>\n\
+                     Signed-off-by: Alice <alice@example.com>",
+                ),
+                vec![FilePath::new("src/parser.rs").unwrap()],
+            ),
+            (
+                CommitHash::new("def456abc789012").unwrap(),
+                create_test_author("Alice"),
+                message(
+                    "Fix parser\n\nReviewed-by: Bob <bob@example.com>\n\
+                     Acked-by: Bob <BOB@example.com>\n\
+                     Acked-by: Alice <alice@example.com>",
+                ),
+                vec![FilePath::new("src/parser.rs").unwrap(), FilePath::new("src/lib.rs").unwrap()],
+            ),
+            (
+                CommitHash::new("789012abc456def").unwrap(),
+                create_test_author("Bob"),
+                message("Update docs\n\nReviewed-by: nobody"),
+                vec![FilePath::new("README.md").unwrap()],
+            ),
+        ];
+        
+        let reviews = analyzer.detect_code_reviews(repo_id, &commits);
+        
+        assert_eq!(reviews.len(), 2);
+        assert_eq!(reviews[0].author, create_test_author("Alice"));
+        assert_eq!(reviews[0].reviewer, create_test_author("Bob"));
+        assert_eq!(reviews[0].review_intensity, 2);
+        assert_eq!(reviews[0].reviewed_files.len(), 2);
+        assert_eq!(reviews[1].author, create_test_author("Carol"));
+        assert_eq!(reviews[1].review_intensity, 1);
+        
+        let load = analyzer.review_load(&reviews);
+        assert_eq!(load, vec![(create_test_author("Bob"), 3)]);
+    }
+    
+    #[test]
+    fn test_code_review_detection_with_conventional_footers() {
+        let analyzer = CollaborationAnalyzer::new();
+        let repo_id = RepositoryId::new();
+        
+        let commits = vec![(
+            CommitHash::new("abc123def456789").unwrap(),
+            create_test_author("Alice"),
+            CommitMessage::new(
+                "feat(parser)!: stream tokens\n\nBREAKING CHANGE: Parser::parse is async\n\
+                 Closes #12\n\
+                 Reviewed-by: Bob <bob@example.com>",
+            )
+            .unwrap(),
+            vec![FilePath::new("src/parser.rs").unwrap()],
+        )];
+        
+        let reviews = analyzer.detect_code_reviews(repo_id, &commits);
+        
+        assert_eq!(reviews.len(), 1);
+        assert_eq!(reviews[0].author, create_test_author("Alice"));
+        assert_eq!(reviews[0].reviewer, create_test_author("Bob"));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,257 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! Commit relationship analyzer
+//!
+//! Finds commits that revert, cherry-pick, fix or mention other commits.
+//! Messages are searched for the lines `git revert` and `git cherry-pick -x`
+//! write, for `Fixes:` style references and for bare commit hashes; hashes
+//! that do not name a commit of the repository are ignored. Cherry-picks made
+//! without `-x` are found by comparing the patch IDs of the commits the
+//! analyzer has seen.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+
+use chrono::Utc;
+use git2::{Commit, Oid, Repository as Git2Repository};
+use regex::Regex;
+
+use crate::{
+    aggregate::RepositoryId,
+    events::collaboration_events::{CommitRelationshipDetected, CommitRelationshipType},
+    value_objects::CommitHash,
+    GitDomainError,
+};
+
+/// Message line written by `git revert`
+static REVERT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^This reverts commit ([0-9a-f]{7,40})\b").expect("Invalid regex")
+});
+
+/// Message line written by `git cherry-pick -x`
+static CHERRY_PICK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\(cherry picked from commit ([0-9a-f]{7,40})\)").expect("Invalid regex")
+});
+
+/// `Fixes: <hash>` trailers and "fixes <hash>" in prose
+static FIXES: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\bfix(?:es|ed)?:?\s+(?:commit\s+)?([0-9a-f]{7,40})\b").expect("Invalid regex")
+});
+
+/// Anything that may be an abbreviated or full commit hash
+static HASH: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b[0-9a-f]{7,40}\b").expect("Invalid regex"));
+
+/// Detects relationships between the commits of a repository
+///
+/// Patch IDs are remembered across calls, so one analyzer should see every
+/// commit of an analysis.
+#[derive(Debug, Default)]
+pub struct CommitRelationshipAnalyzer {
+    /// Commits seen so far, by patch ID
+    patches: HashMap<Oid, Vec<Oid>>,
+
+    /// Cherry-picks already reported, as source and target
+    cherry_picks: HashSet<(Oid, Oid)>,
+}
+
+impl CommitRelationshipAnalyzer {
+    /// Create a new commit relationship analyzer
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Relationships of a commit to the commits it names or shares a patch with
+    ///
+    /// The commit is the target of the relationships found in its message,
+    /// and each named commit is reported once, as revert, cherry-pick, fix or
+    /// plain reference in that order of precedence. A non-merge commit with
+    /// the same patch as a commit seen earlier is reported as a cherry-pick of
+    /// the older of the two, unless one descends from the other or the pick
+    /// was already reported from its message.
+    pub fn analyze_commit(
+        &mut self,
+        repository_id: RepositoryId,
+        git_repo: &Git2Repository,
+        commit: &Commit<'_>,
+    ) -> Result<Vec<CommitRelationshipDetected>, GitDomainError> {
+        let target = commit.id();
+        let mut relationships = Vec::new();
+        let mut named = HashSet::new();
+
+        let references = message_references(commit.message().unwrap_or_default())
+            .into_iter()
+            .filter_map(|(relationship_type, hash)| {
+                let source = git_repo.find_commit_by_prefix(hash).ok()?.id();
+                Some((relationship_type, source))
+            });
+        for (relationship_type, source) in references {
+            if source == target || !named.insert(source) {
+                continue;
+            }
+            if relationship_type == CommitRelationshipType::CherryPick {
+                self.cherry_picks.insert((source, target));
+            }
+            relationships.push(relationship(
+                repository_id,
+                source,
+                target,
+                relationship_type,
+            )?);
+        }
+
+        if let Some(patch_id) = patch_id(git_repo, commit)? {
+            let seen = self.patches.entry(patch_id).or_default();
+            for &other in seen.iter() {
+                let other_time = git_repo
+                    .find_commit(other)
+                    .map_err(|e| {
+                        GitDomainError::GitOperationFailed(format!(
+                            "Failed to read commit {other}: {e}"
+                        ))
+                    })?
+                    .time()
+                    .seconds();
+                let (source, target) = if other_time < commit.time().seconds() {
+                    (other, target)
+                } else {
+                    (target, other)
+                };
+
+                let related = git_repo
+                    .graph_descendant_of(target, source)
+                    .and_then(|descends| {
+                        Ok(descends || git_repo.graph_descendant_of(source, target)?)
+                    })
+                    .map_err(|e| {
+                        GitDomainError::GitOperationFailed(format!(
+                            "Failed to compare {source} and {target}: {e}"
+                        ))
+                    })?;
+                if related || !self.cherry_picks.insert((source, target)) {
+                    continue;
+                }
+
+                relationships.push(relationship(
+                    repository_id,
+                    source,
+                    target,
+                    CommitRelationshipType::CherryPick,
+                )?);
+            }
+            seen.push(target);
+        }
+
+        Ok(relationships)
+    }
+}
+
+/// Hashes named in a commit message, with the relationship they express
+///
+/// Hashes expressing a specific relationship come before plain references,
+/// and numbers without any hex letter are not taken for hashes.
+fn message_references(message: &str) -> Vec<(CommitRelationshipType, &str)> {
+    let mut references = Vec::new();
+    for (pattern, relationship_type) in [
+        (&*REVERT, CommitRelationshipType::Revert),
+        (&*CHERRY_PICK, CommitRelationshipType::CherryPick),
+        (&*FIXES, CommitRelationshipType::Fixes),
+    ] {
+        references.extend(
+            pattern
+                .captures_iter(message)
+                .filter_map(|captures| captures.get(1))
+                .map(|hash| hash.as_str())
+                .filter(|hash| has_hex_letter(hash))
+                .map(|hash| (relationship_type.clone(), hash)),
+        );
+    }
+
+    references.extend(
+        HASH.find_iter(message)
+            .map(|hash| hash.as_str())
+            .filter(|hash| has_hex_letter(hash))
+            .map(|hash| (CommitRelationshipType::References, hash)),
+    );
+
+    references
+}
+
+/// Whether a candidate hash has a hex letter, telling it apart from a number
+fn has_hex_letter(hash: &str) -> bool {
+    !hash.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Patch ID of a non-merge commit's changes, or `None` if it has none
+fn patch_id(git_repo: &Git2Repository, commit: &Commit<'_>) -> Result<Option<Oid>, GitDomainError> {
+    if commit.parent_count() > 1 {
+        return Ok(None);
+    }
+
+    let patch_error = |e: git2::Error| {
+        GitDomainError::GitOperationFailed(format!(
+            "Failed to compute patch ID of {}: {e}",
+            commit.id()
+        ))
+    };
+    let tree = commit.tree().map_err(patch_error)?;
+    let parent_tree = if commit.parent_count() == 0 {
+        None
+    } else {
+        Some(
+            commit
+                .parent(0)
+                .and_then(|parent| parent.tree())
+                .map_err(patch_error)?,
+        )
+    };
+
+    let diff = git_repo
+        .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+        .map_err(patch_error)?;
+    if diff.deltas().len() == 0 {
+        return Ok(None);
+    }
+
+    diff.patchid(None).map(Some).map_err(patch_error)
+}
+
+/// Build the event for one relationship
+fn relationship(
+    repository_id: RepositoryId,
+    source: Oid,
+    target: Oid,
+    relationship_type: CommitRelationshipType,
+) -> Result<CommitRelationshipDetected, GitDomainError> {
+    let commit_hash = |oid: Oid| {
+        CommitHash::new(oid.to_string())
+            .map_err(|e| GitDomainError::GitOperationFailed(format!("Invalid commit hash: {e}")))
+    };
+
+    Ok(CommitRelationshipDetected {
+        repository_id,
+        source_commit: commit_hash(source)?,
+        target_commit: commit_hash(target)?,
+        relationship_type,
+        timestamp: Utc::now(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbers_are_not_taken_for_hashes() {
+        let references = message_references("Fixes 1234567\n\nFixes: abc1234 (\"Parse tags\")");
+
+        assert_eq!(
+            references,
+            vec![
+                (CommitRelationshipType::Fixes, "abc1234"),
+                (CommitRelationshipType::References, "abc1234"),
+            ]
+        );
+    }
+}
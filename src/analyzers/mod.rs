@@ -5,8 +5,13 @@
 //! This module provides analyzers that extract metadata optimized for
 //! building graphs with cim-ipld and cim-domain-graphs.
 
+mod blame;
+mod codeowners;
 mod collaboration_analyzer;
+mod commit_relationships;
 mod code_quality_analyzer;
 
-pub use collaboration_analyzer::{CollaborationAnalyzer};
+pub use codeowners::CodeOwners;
+pub use collaboration_analyzer::{BlameOwnershipOptions, CollaborationAnalyzer};
+pub use commit_relationships::CommitRelationshipAnalyzer;
 pub use code_quality_analyzer::{CodeQualityAnalyzer, FileMetrics};
\ No newline at end of file
@@ -10,6 +10,7 @@ mod analyze_for_graphs;
 pub use analyze_for_graphs::{AnalyzeForGraphs, FileMetricsInput, RepositoryHealthMetrics};
 
 use crate::aggregate::{Repository, RepositoryId};
+use crate::signature::SignaturePolicy;
 use crate::value_objects::{BranchName, CommitHash, FilePath, RemoteUrl, TagName};
 use cim_domain::{Command, EntityId};
 use serde::{Deserialize, Serialize};
@@ -365,6 +366,50 @@ impl Command for CompareBranches {
     }
 }
 
+/// Generate release notes for the commits between two tags
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerateReleaseNotes {
+    /// Repository ID
+    pub repository_id: RepositoryId,
+
+    /// Tag of the previous release, the latest semantic version tag before
+    /// the release when not given
+    pub from_tag: Option<TagName>,
+
+    /// Tag of the release, `HEAD` when not given
+    pub to_tag: Option<TagName>,
+}
+
+impl Command for GenerateReleaseNotes {
+    type Aggregate = Repository;
+
+    fn aggregate_id(&self) -> Option<EntityId<Self::Aggregate>> {
+        Some(EntityId::from_uuid(*self.repository_id.as_uuid()))
+    }
+}
+
+/// Check that the commits on protected branches are signed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckSignaturePolicy {
+    /// Repository ID
+    pub repository_id: RepositoryId,
+
+    /// Protected branches and whether signatures have to verify
+    pub policy: SignaturePolicy,
+
+    /// Maximum number of commits to check per branch, newest first
+    /// (unbounded when `None`)
+    pub max_commits: Option<usize>,
+}
+
+impl Command for CheckSignaturePolicy {
+    type Aggregate = Repository;
+
+    fn aggregate_id(&self) -> Option<EntityId<Self::Aggregate>> {
+        Some(EntityId::from_uuid(*self.repository_id.as_uuid()))
+    }
+}
+
 /// Search repository content
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchRepository {
@@ -385,6 +430,18 @@ pub struct SearchRepository {
 
     /// Maximum results to return
     pub max_results: Option<usize>,
+
+    /// Revision to search, `HEAD` when not given
+    ///
+    /// In pickaxe mode this may also be a range such as `v1.0..main`;
+    /// symmetric `a...b` ranges are rejected.
+    #[serde(default)]
+    pub revision: Option<String>,
+
+    /// Find the commits that changed how often the pattern occurs in a file
+    /// instead of searching a single tree
+    #[serde(default)]
+    pub pickaxe: bool,
 }
 
 impl Command for SearchRepository {
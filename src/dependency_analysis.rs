@@ -93,7 +93,7 @@ impl DependencyAnalyzer {
             Language::Rust,
             vec![
                 (
-                    Regex::new(r"^\s*use\s+([a-zA-Z0-9_:]+)").unwrap(),
+                    Regex::new(r"^\s*use\s+([a-zA-Z0-9_]+(?:::[a-zA-Z0-9_]+)*)").unwrap(),
                     DependencyType::Use,
                 ),
                 (
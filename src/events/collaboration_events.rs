@@ -79,13 +79,13 @@ pub struct CodeReviewDetected {
     /// Author of the original commits
     pub author: AuthorInfo,
     
-    /// Reviewer (identified by subsequent commits to same files)
+    /// Reviewer, as named by a `Reviewed-by:` or `Acked-by:` trailer
     pub reviewer: AuthorInfo,
     
     /// Files reviewed
     pub reviewed_files: Vec<FilePath>,
     
-    /// Review intensity (number of changes by reviewer)
+    /// Review intensity (number of the author's commits reviewed)
     pub review_intensity: u32,
     
     /// When this review pattern was detected
@@ -113,10 +113,51 @@ pub struct CodeOwnershipCalculated {
     /// Total commits to this path
     pub total_commits: u32,
     
+    /// Author of most surviving lines, when ownership was blame-weighted
+    #[serde(default)]
+    pub line_owner: Option<AuthorInfo>,
+    
+    /// Share of surviving lines written by the line owner (0.0 to 1.0)
+    #[serde(default)]
+    pub line_ownership_percentage: Option<f64>,
+    
+    /// Other authors of surviving lines and their shares
+    #[serde(default)]
+    pub line_contributors: Vec<(AuthorInfo, f64)>,
+    
+    /// Surviving lines attributed by blame
+    #[serde(default)]
+    pub total_lines: u32,
+    
     /// When this calculation was performed
     pub timestamp: DateTime<Utc>,
 }
 
+/// Detected when declared code owners disagree with computed ownership
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OwnershipDriftDetected {
+    /// Repository analyzed
+    pub repository_id: RepositoryId,
+    
+    /// File or directory path
+    pub path: FilePath,
+    
+    /// Owners the CODEOWNERS file declares for the path
+    pub declared_owners: Vec<String>,
+    
+    /// Declared owners who have not contributed to the path
+    pub inactive_owners: Vec<String>,
+    
+    /// Author of most surviving lines, or of most commits without blame data
+    pub top_contributor: AuthorInfo,
+    
+    /// Whether the top contributor is among the declared owners
+    pub top_contributor_listed: bool,
+    
+    /// When this drift was detected
+    pub timestamp: DateTime<Utc>,
+}
+
 /// Team formation detected based on collaboration patterns
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TeamClusterDetected {
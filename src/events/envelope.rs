@@ -76,13 +76,22 @@ impl EventEnvelope {
     pub fn event_type(&self) -> &'static str {
         match &self.event {
             GitDomainEvent::RepositoryCloned(_) => "RepositoryCloned",
+            GitDomainEvent::CloneProgressUpdated(_) => "CloneProgressUpdated",
             GitDomainEvent::CommitAnalyzed(_) => "CommitAnalyzed",
             GitDomainEvent::BranchCreated(_) => "BranchCreated",
             GitDomainEvent::BranchDeleted(_) => "BranchDeleted",
+            GitDomainEvent::BranchMoved(_) => "BranchMoved",
+            GitDomainEvent::BranchesCompared(_) => "BranchesCompared",
             GitDomainEvent::TagCreated(_) => "TagCreated",
+            GitDomainEvent::TagDeleted(_) => "TagDeleted",
+            GitDomainEvent::ReleaseNotesGenerated(_) => "ReleaseNotesGenerated",
+            GitDomainEvent::SignaturePolicyChecked(_) => "SignaturePolicyChecked",
+            GitDomainEvent::RemoteFetched(_) => "RemoteFetched",
             GitDomainEvent::RepositoryMetadataUpdated(_) => "RepositoryMetadataUpdated",
             GitDomainEvent::MergeDetected(_) => "MergeDetected",
+            GitDomainEvent::CommitRelationshipDetected(_) => "CommitRelationshipDetected",
             GitDomainEvent::FileAnalyzed(_) => "FileAnalyzed",
+            GitDomainEvent::FileHistoryAnalyzed(_) => "FileHistoryAnalyzed",
             GitDomainEvent::RepositoryAnalyzed(_) => "RepositoryAnalyzed",
         }
     }
@@ -91,13 +100,22 @@ impl EventEnvelope {
     pub fn aggregate_id(&self) -> String {
         match &self.event {
             GitDomainEvent::RepositoryCloned(e) => e.repository_id.to_string(),
+            GitDomainEvent::CloneProgressUpdated(e) => e.repository_id.to_string(),
             GitDomainEvent::CommitAnalyzed(e) => e.repository_id.to_string(),
             GitDomainEvent::BranchCreated(e) => e.repository_id.to_string(),
             GitDomainEvent::BranchDeleted(e) => e.repository_id.to_string(),
+            GitDomainEvent::BranchMoved(e) => e.repository_id.to_string(),
+            GitDomainEvent::BranchesCompared(e) => e.repository_id.to_string(),
             GitDomainEvent::TagCreated(e) => e.repository_id.to_string(),
+            GitDomainEvent::TagDeleted(e) => e.repository_id.to_string(),
+            GitDomainEvent::ReleaseNotesGenerated(e) => e.repository_id.to_string(),
+            GitDomainEvent::SignaturePolicyChecked(e) => e.repository_id.to_string(),
+            GitDomainEvent::RemoteFetched(e) => e.repository_id.to_string(),
             GitDomainEvent::RepositoryMetadataUpdated(e) => e.repository_id.to_string(),
             GitDomainEvent::MergeDetected(e) => e.repository_id.to_string(),
+            GitDomainEvent::CommitRelationshipDetected(e) => e.repository_id.to_string(),
             GitDomainEvent::FileAnalyzed(e) => e.repository_id.to_string(),
+            GitDomainEvent::FileHistoryAnalyzed(e) => e.repository_id.to_string(),
             GitDomainEvent::RepositoryAnalyzed(e) => e.repository_id.to_string(),
         }
     }
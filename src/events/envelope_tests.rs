@@ -95,8 +95,13 @@ mod tests {
                     name: "Test Author".to_string(),
                     email: "test@example.com".to_string(),
                 },
+                raw_author: None,
                 message: "Test commit".to_string(),
+                parsed_message: Default::default(),
+                signature: None,
                 files_changed: vec![],
+                parent_diffs: vec![],
+                combined_files: vec![],
                 commit_timestamp: Utc::now(),
                 timestamp: Utc::now(),
             }),
@@ -105,6 +110,7 @@ mod tests {
                 branch_name: crate::value_objects::BranchName::new("feature/test").unwrap(),
                 commit_hash: crate::value_objects::CommitHash::new("abc123d").unwrap(),
                 source_branch: None,
+                checked_out: false,
                 timestamp: Utc::now(),
             }),
             GitDomainEvent::BranchDeleted(BranchDeleted {
@@ -113,12 +119,74 @@ mod tests {
                 last_commit: crate::value_objects::CommitHash::new("def456a").unwrap(),
                 timestamp: Utc::now(),
             }),
+            GitDomainEvent::BranchMoved(BranchMoved {
+                repository_id: repo_id,
+                branch_name: crate::value_objects::BranchName::new("main").unwrap(),
+                old_commit: crate::value_objects::CommitHash::new("abc123d").unwrap(),
+                new_commit: crate::value_objects::CommitHash::new("def456a").unwrap(),
+                fast_forward: true,
+                timestamp: Utc::now(),
+            }),
+            GitDomainEvent::BranchesCompared(BranchesCompared {
+                repository_id: repo_id,
+                base_branch: crate::value_objects::BranchName::new("main").unwrap(),
+                compare_branch: crate::value_objects::BranchName::new("feature").unwrap(),
+                base_commit: crate::value_objects::CommitHash::new("abc123d").unwrap(),
+                compare_commit: crate::value_objects::CommitHash::new("def456a").unwrap(),
+                merge_base: Some(crate::value_objects::CommitHash::new("abc123d").unwrap()),
+                ahead: vec![crate::value_objects::CommitHash::new("def456a").unwrap()],
+                behind: vec![],
+                files_changed: vec![],
+                timestamp: Utc::now(),
+            }),
             GitDomainEvent::TagCreated(TagCreated {
                 repository_id: repo_id,
                 tag_name: crate::value_objects::TagName::new("v1.0.0").unwrap(),
                 commit_hash: crate::value_objects::CommitHash::new("abc123d").unwrap(),
                 message: Some("Release v1.0.0".to_string()),
                 tagger: None,
+                annotated: true,
+                signature: None,
+                timestamp: Utc::now(),
+            }),
+            GitDomainEvent::TagDeleted(TagDeleted {
+                repository_id: repo_id,
+                tag_name: crate::value_objects::TagName::new("v0.9.0").unwrap(),
+                last_commit: crate::value_objects::CommitHash::new("def456a").unwrap(),
+                timestamp: Utc::now(),
+            }),
+            GitDomainEvent::ReleaseNotesGenerated(ReleaseNotesGenerated {
+                repository_id: repo_id,
+                notes: crate::release::ReleaseNotes::new(
+                    None,
+                    None,
+                    None,
+                    crate::value_objects::CommitHash::new("abc123d").unwrap(),
+                    vec![],
+                ),
+                markdown: "## Unreleased\n\nNo changes.\n".to_string(),
+                timestamp: Utc::now(),
+            }),
+            GitDomainEvent::SignaturePolicyChecked(SignaturePolicyChecked {
+                repository_id: repo_id,
+                report: crate::signature::SignaturePolicyReport {
+                    policy: crate::signature::SignaturePolicy::new(["main"]),
+                    branches: vec![crate::value_objects::BranchName::new("main").unwrap()],
+                    commits_checked: 0,
+                    violations: vec![],
+                },
+                timestamp: Utc::now(),
+            }),
+            GitDomainEvent::RemoteFetched(RemoteFetched {
+                repository_id: repo_id,
+                remotes: vec!["origin".to_string()],
+                pruned: true,
+                ref_updates: vec![RefUpdate {
+                    ref_name: "refs/remotes/origin/main".to_string(),
+                    update_type: RefUpdateType::Updated,
+                    old_target: Some(crate::value_objects::CommitHash::new("abc123d").unwrap()),
+                    new_target: Some(crate::value_objects::CommitHash::new("def456a").unwrap()),
+                }],
                 timestamp: Utc::now(),
             }),
             GitDomainEvent::RepositoryMetadataUpdated(RepositoryMetadataUpdated {
@@ -144,6 +212,15 @@ mod tests {
                 conflicts: vec![],
                 timestamp: Utc::now(),
             }),
+            GitDomainEvent::CommitRelationshipDetected(
+                collaboration_events::CommitRelationshipDetected {
+                    repository_id: repo_id,
+                    source_commit: crate::value_objects::CommitHash::new("1234567").unwrap(),
+                    target_commit: crate::value_objects::CommitHash::new("abcdef0").unwrap(),
+                    relationship_type: collaboration_events::CommitRelationshipType::Revert,
+                    timestamp: Utc::now(),
+                },
+            ),
             GitDomainEvent::FileAnalyzed(FileAnalyzed {
                 repository_id: repo_id,
                 file_path: crate::value_objects::FilePath::new("src/main.rs").unwrap(),
@@ -158,12 +235,23 @@ mod tests {
                 dependencies: vec![],
                 timestamp: Utc::now(),
             }),
+            GitDomainEvent::FileHistoryAnalyzed(FileHistoryAnalyzed {
+                repository_id: repo_id,
+                file_path: crate::value_objects::FilePath::new("src/main.rs").unwrap(),
+                start_commit: None,
+                end_commit: crate::value_objects::CommitHash::new("abc123d").unwrap(),
+                revisions: vec![],
+                blame: vec![],
+                timestamp: Utc::now(),
+            }),
             GitDomainEvent::RepositoryAnalyzed(RepositoryAnalyzed {
                 repository_id: repo_id,
                 path: "/tmp/repo".to_string(),
                 name: "test-repo".to_string(),
                 branch_count: 5,
                 commit_count: 100,
+                ref_tips: HashMap::new(),
+                head_branch: None,
                 timestamp: Utc::now(),
             }),
         ];
@@ -174,10 +262,18 @@ mod tests {
             "CommitAnalyzed",
             "BranchCreated",
             "BranchDeleted",
+            "BranchMoved",
+            "BranchesCompared",
             "TagCreated",
+            "TagDeleted",
+            "ReleaseNotesGenerated",
+            "SignaturePolicyChecked",
+            "RemoteFetched",
             "RepositoryMetadataUpdated",
             "MergeDetected",
+            "CommitRelationshipDetected",
             "FileAnalyzed",
+            "FileHistoryAnalyzed",
             "RepositoryAnalyzed",
         ];
 
@@ -215,6 +311,8 @@ mod tests {
             change_type: FileChangeType::Modified,
             additions: 10,
             deletions: 5,
+            old_path: None,
+            binary: false,
         };
 
         assert_eq!(change.additions, 10);
@@ -229,6 +327,7 @@ mod tests {
             FileChangeType::Modified,
             FileChangeType::Deleted,
             FileChangeType::Renamed,
+            FileChangeType::Copied,
         ];
 
         for change_type in types {
@@ -237,6 +336,8 @@ mod tests {
                 change_type,
                 additions: 0,
                 deletions: 0,
+                old_path: None,
+                binary: false,
             };
 
             match change.change_type {
@@ -244,6 +345,7 @@ mod tests {
                 FileChangeType::Modified => assert_eq!(change_type, FileChangeType::Modified),
                 FileChangeType::Deleted => assert_eq!(change_type, FileChangeType::Deleted),
                 FileChangeType::Renamed => assert_eq!(change_type, FileChangeType::Renamed),
+                FileChangeType::Copied => assert_eq!(change_type, FileChangeType::Copied),
             }
         }
     }
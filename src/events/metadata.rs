@@ -6,6 +6,13 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// Schema version of the events written by this version of the crate
+///
+/// Raise it together with an [`Upcaster`](super::upcast::Upcaster) for the
+/// previous version whenever a change to an event would change how its
+/// stored JSON reads.
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
 /// Event metadata that tracks correlation and causation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventMetadata {
@@ -39,7 +46,7 @@ impl EventMetadata {
             causation_id: event_id,   // Self-caused (root event)
             user_id: None,
             occurred_at: Utc::now(),
-            schema_version: 1,
+            schema_version: CURRENT_SCHEMA_VERSION,
         }
     }
 
@@ -51,7 +58,7 @@ impl EventMetadata {
             causation_id,
             user_id: None,
             occurred_at: Utc::now(),
-            schema_version: 1,
+            schema_version: CURRENT_SCHEMA_VERSION,
         }
     }
 
@@ -63,7 +70,7 @@ impl EventMetadata {
             causation_id: command_id,   // Command caused the event
             user_id: None,
             occurred_at: Utc::now(),
-            schema_version: 1,
+            schema_version: CURRENT_SCHEMA_VERSION,
         }
     }
 
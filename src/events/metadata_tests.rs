@@ -14,7 +14,7 @@ mod tests {
         // Verify defaults
         assert_eq!(metadata.correlation_id, metadata.event_id);
         assert_eq!(metadata.causation_id, metadata.event_id);
-        assert_eq!(metadata.schema_version, 1);
+        assert_eq!(metadata.schema_version, CURRENT_SCHEMA_VERSION);
         assert!(metadata.user_id.is_none());
     }
 
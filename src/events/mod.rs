@@ -9,15 +9,22 @@ pub mod collaboration_events;
 pub mod code_quality_events;
 pub mod envelope;
 pub mod metadata;
+pub mod upcast;
 
 use crate::aggregate::RepositoryId;
-use crate::value_objects::{AuthorInfo, BranchName, CommitHash, FilePath, RemoteUrl, TagName};
+use crate::release::ReleaseNotes;
+use crate::signature::{SignatureInfo, SignaturePolicyReport};
+use crate::value_objects::{
+    AuthorInfo, BranchName, CommitHash, FilePath, ParsedCommitMessage, RemoteUrl, TagName,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 // Re-export commonly used types
 pub use envelope::{EventEnvelope, EventEnvelopeBuilder};
-pub use metadata::{CorrelationContext, EventMetadata, WithMetadata};
+pub use metadata::{CorrelationContext, EventMetadata, WithMetadata, CURRENT_SCHEMA_VERSION};
+pub use upcast::{Upcaster, UpcasterRegistry};
 
 /// Enumeration of all Git domain events
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +33,9 @@ pub enum GitDomainEvent {
     /// A repository was cloned
     RepositoryCloned(RepositoryCloned),
 
+    /// Progress was made while cloning a repository
+    CloneProgressUpdated(CloneProgressUpdated),
+
     /// A commit was analyzed
     CommitAnalyzed(CommitAnalyzed),
 
@@ -35,18 +45,42 @@ pub enum GitDomainEvent {
     /// A branch was deleted
     BranchDeleted(BranchDeleted),
 
+    /// A branch was moved to a different commit
+    BranchMoved(BranchMoved),
+
+    /// Two branches were compared
+    BranchesCompared(BranchesCompared),
+
     /// A tag was created
     TagCreated(TagCreated),
 
+    /// A tag was deleted
+    TagDeleted(TagDeleted),
+
+    /// Release notes were generated for the commits of a release
+    ReleaseNotesGenerated(ReleaseNotesGenerated),
+
+    /// Protected branches were checked for unsigned commits
+    SignaturePolicyChecked(SignaturePolicyChecked),
+
+    /// Changes were fetched from one or more remotes
+    RemoteFetched(RemoteFetched),
+
     /// Repository metadata was updated
     RepositoryMetadataUpdated(RepositoryMetadataUpdated),
 
     /// A merge was detected
     MergeDetected(MergeDetected),
 
+    /// A commit was found to revert, cherry-pick, fix or reference another
+    CommitRelationshipDetected(collaboration_events::CommitRelationshipDetected),
+
     /// A file was analyzed
     FileAnalyzed(FileAnalyzed),
 
+    /// The history of a single file was analyzed
+    FileHistoryAnalyzed(FileHistoryAnalyzed),
+
     /// A repository was analyzed
     RepositoryAnalyzed(RepositoryAnalyzed),
 }
@@ -67,6 +101,46 @@ pub struct RepositoryCloned {
     pub timestamp: DateTime<Utc>,
 }
 
+/// Event: Progress was made while cloning a repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloneProgressUpdated {
+    /// Repository ID
+    pub repository_id: RepositoryId,
+
+    /// Current stage of the clone
+    pub stage: CloneStage,
+
+    /// Objects received so far
+    pub received_objects: usize,
+
+    /// Objects indexed so far
+    pub indexed_objects: usize,
+
+    /// Total objects in the pack
+    pub total_objects: usize,
+
+    /// Bytes received so far
+    pub received_bytes: usize,
+
+    /// Deltas resolved so far
+    pub indexed_deltas: usize,
+
+    /// Total deltas in the pack
+    pub total_deltas: usize,
+
+    /// Timestamp of the event
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Stage of a clone operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CloneStage {
+    /// Objects are being downloaded
+    ReceivingObjects,
+    /// Deltas are being resolved into full objects
+    ResolvingDeltas,
+}
+
 /// Event: A commit was analyzed
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommitAnalyzed {
@@ -79,15 +153,35 @@ pub struct CommitAnalyzed {
     /// Parent commits
     pub parents: Vec<CommitHash>,
 
-    /// Author information
+    /// Author information, resolved to the canonical identity
     pub author: AuthorInfo,
 
+    /// Author as recorded in the commit, when identity resolution changed it
+    #[serde(default)]
+    pub raw_author: Option<AuthorInfo>,
+
     /// Commit message
     pub message: String,
 
-    /// Files changed
+    /// Conventional Commits header, trailers and issue references of the message
+    #[serde(default)]
+    pub parsed_message: ParsedCommitMessage,
+
+    /// Signature of the commit, `None` if it is unsigned
+    #[serde(default)]
+    pub signature: Option<SignatureInfo>,
+
+    /// Files changed (against the first parent, or the empty tree for a root commit)
     pub files_changed: Vec<FileChangeInfo>,
 
+    /// Files changed against each parent of a merge commit
+    #[serde(default)]
+    pub parent_diffs: Vec<ParentDiff>,
+
+    /// Files of a merge commit that differ from every parent
+    #[serde(default)]
+    pub combined_files: Vec<FilePath>,
+
     /// Timestamp of the commit
     pub commit_timestamp: DateTime<Utc>,
 
@@ -95,6 +189,16 @@ pub struct CommitAnalyzed {
     pub timestamp: DateTime<Utc>,
 }
 
+/// File changes of a merge commit relative to one of its parents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParentDiff {
+    /// Parent commit
+    pub parent: CommitHash,
+
+    /// Files changed against this parent
+    pub files_changed: Vec<FileChangeInfo>,
+}
+
 /// Information about a file change in a commit
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileChangeInfo {
@@ -109,6 +213,14 @@ pub struct FileChangeInfo {
 
     /// Lines deleted
     pub deletions: usize,
+
+    /// Previous path of a renamed or copied file
+    #[serde(default)]
+    pub old_path: Option<FilePath>,
+
+    /// Whether the file content is binary (line counts are zero)
+    #[serde(default)]
+    pub binary: bool,
 }
 
 /// Type of file change
@@ -122,6 +234,8 @@ pub enum FileChangeType {
     Deleted,
     /// File was renamed
     Renamed,
+    /// File was copied from another file
+    Copied,
 }
 
 /// Event: A branch was created
@@ -139,6 +253,10 @@ pub struct BranchCreated {
     /// Source branch (if branched from another)
     pub source_branch: Option<BranchName>,
 
+    /// Whether HEAD was switched to the new branch
+    #[serde(default)]
+    pub checked_out: bool,
+
     /// Timestamp of the event
     pub timestamp: DateTime<Utc>,
 }
@@ -159,6 +277,62 @@ pub struct BranchDeleted {
     pub timestamp: DateTime<Utc>,
 }
 
+/// Event: A branch was moved to a different commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchMoved {
+    /// Repository ID
+    pub repository_id: RepositoryId,
+
+    /// Branch name
+    pub branch_name: BranchName,
+
+    /// Commit the branch pointed to before
+    pub old_commit: CommitHash,
+
+    /// Commit the branch points to now
+    pub new_commit: CommitHash,
+
+    /// Whether the new commit descends from the old one
+    pub fast_forward: bool,
+
+    /// Timestamp of the event
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Event: Two branches were compared
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchesCompared {
+    /// Repository ID
+    pub repository_id: RepositoryId,
+
+    /// Branch compared against
+    pub base_branch: BranchName,
+
+    /// Branch compared to the base
+    pub compare_branch: BranchName,
+
+    /// Commit the base branch pointed to
+    pub base_commit: CommitHash,
+
+    /// Commit the compare branch pointed to
+    pub compare_commit: CommitHash,
+
+    /// Best common ancestor, if the branches share history
+    pub merge_base: Option<CommitHash>,
+
+    /// Commits on the compare branch that are not on the base branch, newest first
+    pub ahead: Vec<CommitHash>,
+
+    /// Commits on the base branch that are not on the compare branch, newest first
+    pub behind: Vec<CommitHash>,
+
+    /// Files the compare branch changed since the merge base
+    pub files_changed: Vec<FileChangeInfo>,
+
+    /// Timestamp of the event
+    pub timestamp: DateTime<Utc>,
+}
+
 /// Event: A tag was created
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TagCreated {
@@ -177,10 +351,109 @@ pub struct TagCreated {
     /// Tagger information (if annotated)
     pub tagger: Option<AuthorInfo>,
 
+    /// Whether the tag is an annotated tag object rather than a plain ref
+    #[serde(default)]
+    pub annotated: bool,
+
+    /// Signature of the annotated tag, `None` if it is unsigned
+    #[serde(default)]
+    pub signature: Option<SignatureInfo>,
+
     /// Timestamp of the event
     pub timestamp: DateTime<Utc>,
 }
 
+/// Event: A tag was deleted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagDeleted {
+    /// Repository ID
+    pub repository_id: RepositoryId,
+
+    /// Tag name
+    pub tag_name: TagName,
+
+    /// Last commit the tag pointed to
+    pub last_commit: CommitHash,
+
+    /// Timestamp of the event
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Event: Protected branches were checked against a signature policy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignaturePolicyChecked {
+    /// Repository ID
+    pub repository_id: RepositoryId,
+
+    /// Checked branches, commits and the commits that violate the policy
+    pub report: SignaturePolicyReport,
+
+    /// Timestamp of the event
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Event: Release notes were generated for the commits of a release
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseNotesGenerated {
+    /// Repository ID
+    pub repository_id: RepositoryId,
+
+    /// Classified commits, proposed version and bump
+    pub notes: ReleaseNotes,
+
+    /// The notes rendered as a Markdown changelog entry
+    pub markdown: String,
+
+    /// Timestamp of the event
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Event: Changes were fetched from one or more remotes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteFetched {
+    /// Repository ID
+    pub repository_id: RepositoryId,
+
+    /// Names of the remotes that were fetched
+    pub remotes: Vec<String>,
+
+    /// Whether stale remote-tracking refs were pruned
+    pub pruned: bool,
+
+    /// Remote-tracking refs the fetch created, updated or deleted
+    pub ref_updates: Vec<RefUpdate>,
+
+    /// Timestamp of the event
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A remote-tracking ref changed by a fetch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RefUpdate {
+    /// Full ref name, e.g. `refs/remotes/origin/main`
+    pub ref_name: String,
+
+    /// Type of update
+    pub update_type: RefUpdateType,
+
+    /// Commit the ref pointed to before the fetch
+    pub old_target: Option<CommitHash>,
+
+    /// Commit the ref points to after the fetch
+    pub new_target: Option<CommitHash>,
+}
+
+/// Type of remote-tracking ref update
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RefUpdateType {
+    /// Ref did not exist before the fetch
+    Created,
+    /// Ref moved to a different commit
+    Updated,
+    /// Ref was pruned because the remote branch is gone
+    Deleted,
+}
+
 /// Event: Repository metadata was updated
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepositoryMetadataUpdated {
@@ -232,6 +505,10 @@ pub struct MergeDetected {
     pub merge_strategy: Option<String>,
 
     /// Conflicts that occurred
+    ///
+    /// Empty when detected from history, which does not record conflicts;
+    /// `CommitAnalyzed::combined_files` lists the files the merge changed
+    /// relative to every parent.
     pub conflicts: Vec<FilePath>,
 
     /// Timestamp of the event
@@ -279,7 +556,70 @@ pub struct FileMetrics {
     pub size_bytes: u64,
 }
 
+/// Event: The history of a single file was analyzed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileHistoryAnalyzed {
+    /// Repository ID
+    pub repository_id: RepositoryId,
+
+    /// File path at the end of the analyzed range
+    pub file_path: FilePath,
+
+    /// Oldest commit considered, when the range was bounded
+    pub start_commit: Option<CommitHash>,
+
+    /// Commit the history was read back from
+    pub end_commit: CommitHash,
+
+    /// Revisions that changed the file, newest first
+    pub revisions: Vec<FileRevision>,
+
+    /// Line-level blame of the file at the end commit, in line order
+    pub blame: Vec<BlameHunk>,
+
+    /// Timestamp of the event
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A commit that changed a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileRevision {
+    /// Commit that changed the file
+    pub commit_hash: CommitHash,
+
+    /// Author of the commit, resolved to the canonical identity
+    pub author: AuthorInfo,
+
+    /// Author as recorded in the commit, when identity resolution changed it
+    #[serde(default)]
+    pub raw_author: Option<AuthorInfo>,
+
+    /// The change, under the path the file had in this commit
+    pub change: FileChangeInfo,
+
+    /// When the commit was made
+    pub commit_timestamp: DateTime<Utc>,
+}
+
+/// A range of lines last changed by the same commit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlameHunk {
+    /// Commit that last changed the lines
+    pub commit_hash: CommitHash,
+
+    /// Author of that commit
+    pub author: AuthorInfo,
+
+    /// First line of the range, starting at 1
+    pub start_line: usize,
+
+    /// Number of lines in the range
+    pub line_count: usize,
+}
+
 /// Event: A repository was analyzed
+///
+/// Follows the other events of the analysis and carries the totals it reached.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepositoryAnalyzed {
     /// Repository ID
@@ -291,12 +631,22 @@ pub struct RepositoryAnalyzed {
     /// Repository name
     pub name: String,
 
-    /// Number of branches found
+    /// Number of branches known after the analysis
     pub branch_count: usize,
 
-    /// Number of commits analyzed
+    /// Number of commits analyzed by this and earlier analyses of the repository
     pub commit_count: usize,
 
+    /// Tips of the refs the analysis walked from, keyed by ref
+    ///
+    /// Empty when the walk was limited and did not reach their whole history.
+    #[serde(default)]
+    pub ref_tips: HashMap<String, CommitHash>,
+
+    /// Branch HEAD points to, or `None` when detached or unborn
+    #[serde(default)]
+    pub head_branch: Option<BranchName>,
+
     /// Timestamp of the event
     pub timestamp: DateTime<Utc>,
 }
@@ -305,6 +655,8 @@ pub struct RepositoryAnalyzed {
 mod envelope_tests;
 #[cfg(test)]
 mod metadata_tests;
+#[cfg(test)]
+mod upcast_tests;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,147 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! Upcasting of stored events to the current schema
+//!
+//! Events are stored as JSON and kept forever, so a change to an event struct
+//! must not make older events unreadable. Every envelope records the schema
+//! version it was written with in [`EventMetadata::schema_version`]; before
+//! an envelope is deserialized, the upcasters registered for its version and
+//! every later one rewrite the raw JSON into the current shape.
+//!
+//! [`EventMetadata::schema_version`]: super::EventMetadata::schema_version
+
+use serde_json::Value;
+use std::collections::BTreeMap;
+use tracing::debug;
+
+use super::metadata::CURRENT_SCHEMA_VERSION;
+use super::EventEnvelope;
+use crate::value_objects::CommitMessage;
+
+/// Rewrites events of one schema version into the shape of the next
+pub trait Upcaster: Send + Sync {
+    /// Schema version of the events this upcaster reads
+    fn source_version(&self) -> u32;
+
+    /// Rewrite an event in place
+    ///
+    /// `event` is the JSON of a [`GitDomainEvent`](super::GitDomainEvent),
+    /// tagged with its `event_type`.
+    fn upcast(&self, event: &mut Value) -> serde_json::Result<()>;
+}
+
+/// Upcasters by the schema version they read
+pub struct UpcasterRegistry {
+    upcasters: BTreeMap<u32, Box<dyn Upcaster>>,
+}
+
+impl UpcasterRegistry {
+    /// Create a registry with the upcasters for this crate's events
+    #[must_use]
+    pub fn new() -> Self {
+        Self::empty().with_upcaster(V1Upcaster)
+    }
+
+    /// Create a registry without any upcasters
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            upcasters: BTreeMap::new(),
+        }
+    }
+
+    /// Register an upcaster, replacing any registered for the same version
+    #[must_use]
+    pub fn with_upcaster(mut self, upcaster: impl Upcaster + 'static) -> Self {
+        self.upcasters
+            .insert(upcaster.source_version(), Box::new(upcaster));
+        self
+    }
+
+    /// Rewrite the raw JSON of an envelope into the current schema
+    ///
+    /// Versions without an upcaster are taken to share the shape of the next
+    /// version. Envelopes from a newer schema are left unchanged.
+    pub fn upcast(&self, envelope: &mut Value) -> serde_json::Result<()> {
+        let version = envelope
+            .pointer("/metadata/schema_version")
+            .and_then(Value::as_u64)
+            .and_then(|version| u32::try_from(version).ok())
+            .unwrap_or(1);
+        if version >= CURRENT_SCHEMA_VERSION {
+            return Ok(());
+        }
+
+        if let Some(event) = envelope.get_mut("event") {
+            for upcaster in self
+                .upcasters
+                .range(version..CURRENT_SCHEMA_VERSION)
+                .map(|(_, upcaster)| upcaster)
+            {
+                upcaster.upcast(event)?;
+            }
+        }
+        if let Some(metadata) = envelope.get_mut("metadata").and_then(Value::as_object_mut) {
+            metadata.insert("schema_version".to_string(), CURRENT_SCHEMA_VERSION.into());
+        }
+
+        debug!(
+            "Upcast event from schema version {} to {}",
+            version, CURRENT_SCHEMA_VERSION
+        );
+        Ok(())
+    }
+
+    /// Deserialize an envelope, upcasting it to the current schema first
+    pub fn decode(&self, payload: &[u8]) -> serde_json::Result<EventEnvelope> {
+        let mut envelope: Value = serde_json::from_slice(payload)?;
+        self.upcast(&mut envelope)?;
+        serde_json::from_value(envelope)
+    }
+}
+
+impl Default for UpcasterRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Schema version 1 to 2
+///
+/// Version 1 events predate the `annotated` flag of `TagCreated` and the
+/// parsed message of `CommitAnalyzed`, which would otherwise read as a
+/// lightweight tag and a message without Conventional Commits header.
+struct V1Upcaster;
+
+impl Upcaster for V1Upcaster {
+    fn source_version(&self) -> u32 {
+        1
+    }
+
+    fn upcast(&self, event: &mut Value) -> serde_json::Result<()> {
+        let Some(fields) = event.as_object_mut() else {
+            return Ok(());
+        };
+
+        match fields.get("event_type").and_then(Value::as_str) {
+            Some("TagCreated") if !fields.contains_key("annotated") => {
+                // Only annotated tags carry a message
+                let annotated = fields.get("message").is_some_and(Value::is_string);
+                fields.insert("annotated".to_string(), annotated.into());
+            }
+            Some("CommitAnalyzed") if !fields.contains_key("parsed_message") => {
+                let parsed = fields
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .and_then(|message| CommitMessage::new(message).ok())
+                    .map(|message| message.parse());
+                if let Some(parsed) = parsed {
+                    fields.insert("parsed_message".to_string(), serde_json::to_value(parsed)?);
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! Tests for event upcasting
+
+#[cfg(test)]
+mod tests {
+    use super::super::metadata::CURRENT_SCHEMA_VERSION;
+    use super::super::upcast::*;
+    use crate::value_objects::TagName;
+    use serde_json::{json, Value};
+
+    fn tag_created(schema_version: u32) -> Value {
+        json!({
+            "metadata": {
+                "event_id": "5b2f3c1e-8a47-4f6d-9c0e-2d1a7b3e4f50",
+                "correlation_id": "5b2f3c1e-8a47-4f6d-9c0e-2d1a7b3e4f50",
+                "causation_id": "5b2f3c1e-8a47-4f6d-9c0e-2d1a7b3e4f50",
+                "occurred_at": "2025-03-14T09:26:53Z",
+                "schema_version": schema_version
+            },
+            "event": {
+                "event_type": "TagCreated",
+                "repository_id": "0b7f5e6a-6c39-4d8e-9a57-1d2c3b4a5f60",
+                "tag_name": "v1.0.0",
+                "commit_hash": "8f2e4a6c1b3d5f7e9a0c2e4f6a8b0d1c3e5f7a9b",
+                "message": null,
+                "tagger": null,
+                "timestamp": "2025-03-14T09:26:53Z"
+            }
+        })
+    }
+
+    struct RenameTag;
+
+    impl Upcaster for RenameTag {
+        fn source_version(&self) -> u32 {
+            1
+        }
+
+        fn upcast(&self, event: &mut Value) -> serde_json::Result<()> {
+            event["tag_name"] = "v1.0.0-renamed".into();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_upcast_sets_current_schema_version() {
+        let mut envelope = tag_created(1);
+        UpcasterRegistry::new().upcast(&mut envelope).unwrap();
+
+        assert_eq!(
+            envelope["metadata"]["schema_version"],
+            CURRENT_SCHEMA_VERSION
+        );
+        // A tag without message was lightweight
+        assert_eq!(envelope["event"]["annotated"], false);
+    }
+
+    #[test]
+    fn test_current_events_are_left_unchanged() {
+        let envelope = tag_created(CURRENT_SCHEMA_VERSION);
+        let mut upcast = envelope.clone();
+        UpcasterRegistry::new()
+            .with_upcaster(RenameTag)
+            .upcast(&mut upcast)
+            .unwrap();
+
+        assert_eq!(upcast, envelope);
+    }
+
+    #[test]
+    fn test_registered_upcaster_replaces_builtin() {
+        let envelope = UpcasterRegistry::new()
+            .with_upcaster(RenameTag)
+            .decode(tag_created(1).to_string().as_bytes())
+            .unwrap();
+
+        match envelope.event {
+            crate::events::GitDomainEvent::TagCreated(e) => {
+                assert_eq!(e.tag_name, TagName::new("v1.0.0-renamed").unwrap());
+                assert!(!e.annotated);
+            }
+            _ => panic!("Wrong event type deserialized"),
+        }
+    }
+
+    #[test]
+    fn test_envelope_without_schema_version_is_version_one() {
+        let mut envelope = tag_created(1);
+        envelope["metadata"]
+            .as_object_mut()
+            .unwrap()
+            .remove("schema_version");
+        envelope["event"]["message"] = "Release 1.0.0".into();
+
+        let envelope = UpcasterRegistry::new()
+            .decode(envelope.to_string().as_bytes())
+            .unwrap();
+
+        assert_eq!(envelope.metadata.schema_version, CURRENT_SCHEMA_VERSION);
+        match envelope.event {
+            crate::events::GitDomainEvent::TagCreated(e) => assert!(e.annotated),
+            _ => panic!("Wrong event type deserialized"),
+        }
+    }
+}
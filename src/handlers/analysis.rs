@@ -0,0 +1,736 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! Repository history analysis for the repository command handler
+//!
+//! The history walk runs on a blocking thread and hands events over a bounded
+//! channel, so very large repositories can be analysed as an async stream
+//! without materialising every event in memory.
+
+use super::{diff, RepositoryCommandHandler};
+use crate::aggregate::{Repository, RepositoryId};
+use crate::analyzers::CommitRelationshipAnalyzer;
+use crate::events::{
+    BranchCreated, BranchDeleted, BranchMoved, CommitAnalyzed, GitDomainEvent, MergeDetected,
+    RepositoryAnalyzed, TagCreated, TagDeleted,
+};
+use crate::identity::{IdentityMapper, IdentityResolver};
+use crate::signature::{commit_signature, strip_signature, tag_signature, SignatureVerifier};
+use crate::value_objects::{BranchName, CommitHash, CommitMessage, RemoteUrl, TagName};
+use crate::GitDomainError;
+use chrono::{DateTime, Utc};
+use futures::{Stream, TryStreamExt};
+use git2::{Oid, Repository as Git2Repository, Sort};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{Arc, LazyLock};
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, info, warn, Span};
+
+/// Number of events buffered between the history walk and the consumer
+const STREAM_BUFFER: usize = 256;
+
+/// Default merge message, e.g. `Merge branch 'feature' into main`
+static MERGE_MESSAGE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^Merge (?:remote-tracking )?branch(?:es)? (.+?)(?: of \S+)?(?: into (\S+))?$")
+        .expect("Invalid regex")
+});
+
+/// Quoted branch name inside a merge message
+static QUOTED_BRANCH: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"'([^']+)'").expect("Invalid regex"));
+
+/// Options controlling which part of the history is analyzed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnalysisOptions {
+    /// Maximum number of commits to analyze (unbounded when `None`)
+    pub max_commits: Option<usize>,
+
+    /// Only analyze commits made at or after this time
+    pub since: Option<DateTime<Utc>>,
+
+    /// Only analyze commits made at or before this time
+    pub until: Option<DateTime<Utc>>,
+
+    /// Refs to start the walk from; revspecs or globs such as `refs/heads/*`
+    ///
+    /// An empty set walks from `HEAD`.
+    pub refs: Vec<String>,
+
+    /// Follow only the first parent of merge commits
+    pub first_parent_only: bool,
+}
+
+impl AnalysisOptions {
+    /// Limit the number of analyzed commits
+    #[must_use]
+    pub fn with_max_commits(mut self, max_commits: usize) -> Self {
+        self.max_commits = Some(max_commits);
+        self
+    }
+
+    /// Skip commits made before the given time
+    #[must_use]
+    pub fn with_since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// Skip commits made after the given time
+    #[must_use]
+    pub fn with_until(mut self, until: DateTime<Utc>) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    /// Add a ref to start the walk from
+    #[must_use]
+    pub fn with_ref(mut self, reference: impl Into<String>) -> Self {
+        self.refs.push(reference.into());
+        self
+    }
+
+    /// Follow only first parents when walking merges
+    #[must_use]
+    pub fn first_parent_only(mut self) -> Self {
+        self.first_parent_only = true;
+        self
+    }
+
+    /// Check whether the walk reaches every commit behind its start points
+    ///
+    /// Only then may the start points be hidden from later walks.
+    fn walks_full_history(&self) -> bool {
+        self.max_commits.is_none()
+            && self.since.is_none()
+            && self.until.is_none()
+            && !self.first_parent_only
+    }
+
+    /// Check whether a commit time falls inside the configured window
+    fn covers(&self, time: DateTime<Utc>) -> bool {
+        self.since.is_none_or(|since| time >= since) && self.until.is_none_or(|until| time <= until)
+    }
+}
+
+/// Stream of events produced by a repository analysis
+///
+/// Dropping the stream stops the underlying history walk.
+pub struct AnalysisStream {
+    repository_id: RepositoryId,
+    events: ReceiverStream<Result<GitDomainEvent, GitDomainError>>,
+}
+
+impl AnalysisStream {
+    /// ID of the repository being analyzed
+    #[must_use]
+    pub fn repository_id(&self) -> RepositoryId {
+        self.repository_id
+    }
+}
+
+impl Stream for AnalysisStream {
+    type Item = Result<GitDomainEvent, GitDomainError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.events).poll_next(cx)
+    }
+}
+
+impl RepositoryCommandHandler {
+    /// Analyze a Git repository at the given path using the given options
+    pub async fn analyze_repository_with_options(
+        &self,
+        path: impl AsRef<str>,
+        options: AnalysisOptions,
+    ) -> Result<(RepositoryId, Vec<GitDomainEvent>), GitDomainError> {
+        let stream = self.analyze_repository_stream(path, options);
+        let repo_id = stream.repository_id();
+        let events = stream.try_collect().await?;
+
+        Ok((repo_id, events))
+    }
+
+    /// Analyze a Git repository, yielding events as they are produced
+    ///
+    /// A repository that was analyzed or cloned before keeps its ID and only
+    /// the changes since then are emitted; commits reachable from a previously
+    /// analyzed tip are not revisited. The repository aggregate is stored
+    /// once the stream has been fully consumed. Must be called from within a
+    /// Tokio runtime.
+    pub fn analyze_repository_stream(
+        &self,
+        path: impl AsRef<str>,
+        options: AnalysisOptions,
+    ) -> AnalysisStream {
+        let path = path.as_ref().to_string();
+        let known = self.find_known_repository(&path);
+        let repository_id = known
+            .as_ref()
+            .map_or_else(RepositoryId::new, |repository| repository.id);
+        let repositories = Arc::clone(&self.repositories);
+        let resolver = self.identity_resolver.clone();
+        let verifier = self.signature_verifier.clone();
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+        let span = Span::current();
+
+        tokio::task::spawn_blocking(move || {
+            let _entered = span.enter();
+            let mut emit = |event| sender.blocking_send(Ok(event)).is_ok();
+
+            let result = match walk_repository(
+                &path,
+                repository_id,
+                known,
+                &options,
+                resolver.as_deref(),
+                verifier.as_deref(),
+                &mut emit,
+            ) {
+                Ok(Some(repository)) => repositories
+                    .lock()
+                    .map(|mut repos| {
+                        repos.insert(repository_id, repository);
+                    })
+                    .map_err(|_| {
+                        GitDomainError::GitOperationFailed(
+                            "Failed to acquire repository lock".to_string(),
+                        )
+                    }),
+                Ok(None) => {
+                    debug!("Analysis of {} stopped by consumer", path);
+                    Ok(())
+                }
+                Err(e) => Err(e),
+            };
+
+            if let Err(e) = result {
+                // The consumer may already be gone, in which case nobody cares
+                let _ = sender.blocking_send(Err(e));
+            }
+        });
+
+        AnalysisStream {
+            repository_id,
+            events: ReceiverStream::new(receiver),
+        }
+    }
+
+    /// Find a stored repository by canonical local path or `origin` URL
+    fn find_known_repository(&self, path: &str) -> Option<Repository> {
+        let canonical = std::fs::canonicalize(path).ok();
+        let origin = Git2Repository::open(path).ok().and_then(|git_repo| {
+            let remote = git_repo.find_remote("origin").ok()?;
+            remote.url().and_then(|url| RemoteUrl::new(url).ok())
+        });
+
+        let repos = self.repositories.lock().ok()?;
+        let same_path = |repository: &&Repository| {
+            canonical.is_some()
+                && repository
+                    .local_path
+                    .as_ref()
+                    .and_then(|local| std::fs::canonicalize(local).ok())
+                    == canonical
+        };
+        let same_remote =
+            |repository: &&Repository| origin.is_some() && repository.remote_url == origin;
+
+        repos
+            .values()
+            .find(same_path)
+            .or_else(|| repos.values().find(same_remote))
+            .cloned()
+    }
+}
+
+/// Walk the repository, emitting events and folding them into its aggregate
+///
+/// When `known` is given only the changes since its last analysis are
+/// emitted. Returns `None` if `emit` signalled that the consumer stopped
+/// listening.
+fn walk_repository(
+    path: &str,
+    repo_id: RepositoryId,
+    known: Option<Repository>,
+    options: &AnalysisOptions,
+    resolver: Option<&dyn IdentityResolver>,
+    verifier: Option<&SignatureVerifier>,
+    emit: &mut dyn FnMut(GitDomainEvent) -> bool,
+) -> Result<Option<Repository>, GitDomainError> {
+    info!("Analyzing Git repository at: {}", path);
+
+    // Open repository with git2
+    let git_repo = Git2Repository::open(path).map_err(|e| {
+        GitDomainError::GitOperationFailed(format!("Failed to open repository: {e}"))
+    })?;
+
+    // Get repository metadata
+    let repo_name = Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    let mut repository = known.unwrap_or_else(|| {
+        let mut repository = Repository::new(repo_name.clone());
+        repository.id = repo_id;
+        repository
+    });
+
+    // Everything reachable from a previously analyzed tip has been emitted
+    let previous_tips: Vec<Oid> = repository
+        .analyzed_tips
+        .values()
+        .filter_map(|tip| Oid::from_str(tip.as_str()).ok())
+        .collect();
+
+    let start_points = resolve_start_points(&git_repo, options)?;
+
+    // A bounded walk skips commits behind its start points, so recording them
+    // would hide those commits from every later run
+    let ref_tips = if options.walks_full_history() {
+        start_points
+            .iter()
+            .filter_map(|(name, oid)| Some((name.clone(), CommitHash::new(oid.to_string()).ok()?)))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
+    // Analyze branches
+    let branch_events = branch_changes(&git_repo, &repository)?;
+    let branch_count = branch_events.len();
+    for event in branch_events {
+        if !publish(&mut repository, emit, event)? {
+            return Ok(None);
+        }
+    }
+
+    // Analyze tags
+    let identities = IdentityMapper::new(&git_repo, resolver);
+    let tag_events = tag_changes(&git_repo, &repository, &identities, verifier)?;
+    let tag_count = tag_events.len();
+    for event in tag_events {
+        if !publish(&mut repository, emit, event)? {
+            return Ok(None);
+        }
+    }
+
+    // Analyze commits
+    let mut revwalk = git_repo.revwalk().map_err(|e| {
+        GitDomainError::GitOperationFailed(format!("Failed to create revwalk: {e}"))
+    })?;
+
+    revwalk
+        .set_sorting(Sort::TIME)
+        .map_err(|e| GitDomainError::GitOperationFailed(format!("Failed to set sort: {e}")))?;
+
+    if options.first_parent_only {
+        revwalk.simplify_first_parent().map_err(|e| {
+            GitDomainError::GitOperationFailed(format!("Failed to simplify to first parent: {e}"))
+        })?;
+    }
+
+    for (name, oid) in &start_points {
+        revwalk.push(*oid).map_err(|e| {
+            GitDomainError::GitOperationFailed(format!("Failed to push {name}: {e}"))
+        })?;
+    }
+
+    for tip in previous_tips {
+        // Tips may have been garbage collected after a history rewrite
+        if let Err(e) = revwalk.hide(tip) {
+            debug!("Not hiding previously analyzed tip {}: {}", tip, e);
+        }
+    }
+
+    let mut relationships = CommitRelationshipAnalyzer::new();
+    let mut commit_count = 0;
+    for commit_oid in revwalk {
+        if options.max_commits.is_some_and(|max| commit_count >= max) {
+            break;
+        }
+
+        if let Ok(commit) = commit_oid.and_then(|oid| git_repo.find_commit(oid)) {
+            let commit_timestamp =
+                DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or_else(Utc::now);
+            // Sorting makes libgit2 collect the whole walk, first-parent or
+            // not, before handing it out newest first. Every later commit is
+            // older, even where clock skew dates a parent after its child.
+            if options.since.is_some_and(|since| commit_timestamp < since) {
+                break;
+            }
+            if !options.covers(commit_timestamp) {
+                continue;
+            }
+
+            let commit_event = analyze_commit(
+                &git_repo,
+                repo_id,
+                &commit,
+                commit_timestamp,
+                &identities,
+                verifier,
+            )?;
+            let merge_event = merge_detected(&commit_event);
+
+            if !publish(
+                &mut repository,
+                emit,
+                GitDomainEvent::CommitAnalyzed(commit_event),
+            )? {
+                return Ok(None);
+            }
+            if let Some(merge_event) = merge_event {
+                if !publish(
+                    &mut repository,
+                    emit,
+                    GitDomainEvent::MergeDetected(merge_event),
+                )? {
+                    return Ok(None);
+                }
+            }
+            for relationship in relationships.analyze_commit(repo_id, &git_repo, &commit)? {
+                if !publish(
+                    &mut repository,
+                    emit,
+                    GitDomainEvent::CommitRelationshipDetected(relationship),
+                )? {
+                    return Ok(None);
+                }
+            }
+            commit_count += 1;
+        }
+    }
+
+    info!(
+        "Analyzed repository: {} branch changes, {} tag changes, {} new commits",
+        branch_count, tag_count, commit_count
+    );
+
+    // Summarizes the walk, so it follows the events and carries the totals
+    let analyzed_event = RepositoryAnalyzed {
+        repository_id: repo_id,
+        path: path.to_string(),
+        name: repo_name,
+        branch_count: repository.branches.len(),
+        commit_count: repository.metadata.commit_count.unwrap_or(0),
+        ref_tips,
+        head_branch: git_repo
+            .head()
+            .ok()
+            .filter(git2::Reference::is_branch)
+            .and_then(|head| BranchName::new(head.shorthand()?).ok()),
+        timestamp: Utc::now(),
+    };
+
+    if !publish(
+        &mut repository,
+        emit,
+        GitDomainEvent::RepositoryAnalyzed(analyzed_event),
+    )? {
+        return Ok(None);
+    }
+
+    Ok(Some(repository))
+}
+
+/// Resolve the refs the history walk starts from to commits
+fn resolve_start_points(
+    git_repo: &Git2Repository,
+    options: &AnalysisOptions,
+) -> Result<Vec<(String, Oid)>, GitDomainError> {
+    let mut start_points = Vec::new();
+
+    if options.refs.is_empty() {
+        // Start from HEAD
+        if let Ok(head) = git_repo.head() {
+            if let Some(target) = head.target() {
+                start_points.push(("HEAD".to_string(), target));
+            }
+        } else {
+            warn!("Repository has no HEAD - might be empty");
+        }
+    }
+
+    for reference in &options.refs {
+        let resolve_error = |e: git2::Error| {
+            GitDomainError::GitOperationFailed(format!("Failed to resolve ref {reference}: {e}"))
+        };
+
+        if reference.contains('*') {
+            for matched in git_repo.references_glob(reference).map_err(resolve_error)? {
+                let matched = matched.map_err(resolve_error)?;
+                if let (Some(name), Ok(commit)) = (matched.name(), matched.peel_to_commit()) {
+                    start_points.push((name.to_string(), commit.id()));
+                }
+            }
+        } else {
+            let commit = git_repo
+                .revparse_single(reference)
+                .and_then(|object| object.peel_to_commit())
+                .map_err(resolve_error)?;
+            start_points.push((reference.clone(), commit.id()));
+        }
+    }
+
+    Ok(start_points)
+}
+
+/// Compare the branches in the repository against those the aggregate knows
+fn branch_changes(
+    git_repo: &Git2Repository,
+    repository: &Repository,
+) -> Result<Vec<GitDomainEvent>, GitDomainError> {
+    let branches = git_repo
+        .branches(None)
+        .map_err(|e| GitDomainError::GitOperationFailed(format!("Failed to get branches: {e}")))?;
+
+    let mut events = Vec::new();
+    let mut current = HashSet::new();
+
+    for (branch, _) in branches.flatten() {
+        if let Some(name) = branch.name().ok().flatten() {
+            if let (Ok(branch_name), Some(target)) = (BranchName::new(name), branch.get().target())
+            {
+                if let Ok(commit_hash) = CommitHash::new(target.to_string()) {
+                    current.insert(branch_name.clone());
+
+                    match repository.branches.get(&branch_name) {
+                        None => events.push(GitDomainEvent::BranchCreated(BranchCreated {
+                            repository_id: repository.id,
+                            branch_name,
+                            commit_hash,
+                            source_branch: None,
+                            checked_out: false,
+                            timestamp: Utc::now(),
+                        })),
+                        Some(previous) if *previous != commit_hash => {
+                            let fast_forward = Oid::from_str(previous.as_str())
+                                .and_then(|old| git_repo.graph_descendant_of(target, old))
+                                .unwrap_or(false);
+
+                            events.push(GitDomainEvent::BranchMoved(BranchMoved {
+                                repository_id: repository.id,
+                                branch_name,
+                                old_commit: previous.clone(),
+                                new_commit: commit_hash,
+                                fast_forward,
+                                timestamp: Utc::now(),
+                            }));
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+    }
+
+    let mut deleted: Vec<_> = repository
+        .branches
+        .iter()
+        .filter(|(name, _)| !current.contains(*name))
+        .collect();
+    deleted.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+
+    events.extend(deleted.into_iter().map(|(name, last_commit)| {
+        GitDomainEvent::BranchDeleted(BranchDeleted {
+            repository_id: repository.id,
+            branch_name: name.clone(),
+            last_commit: last_commit.clone(),
+            timestamp: Utc::now(),
+        })
+    }));
+
+    Ok(events)
+}
+
+/// Compare the tags in the repository against those the aggregate knows
+///
+/// Tags that do not point to a commit are skipped. A tag that now points to
+/// a different commit is reported as deleted and created again.
+fn tag_changes(
+    git_repo: &Git2Repository,
+    repository: &Repository,
+    identities: &IdentityMapper<'_>,
+    verifier: Option<&SignatureVerifier>,
+) -> Result<Vec<GitDomainEvent>, GitDomainError> {
+    let references = git_repo
+        .references_glob("refs/tags/*")
+        .map_err(|e| GitDomainError::GitOperationFailed(format!("Failed to get tags: {e}")))?;
+
+    let mut found = Vec::new();
+    for reference in references.flatten() {
+        let Some(tag_name) = reference
+            .name()
+            .and_then(|name| name.strip_prefix("refs/tags/"))
+            .and_then(|name| TagName::new(name).ok())
+        else {
+            continue;
+        };
+        let Some(commit_hash) = reference
+            .peel_to_commit()
+            .ok()
+            .and_then(|commit| CommitHash::new(commit.id().to_string()).ok())
+        else {
+            debug!("Skipping tag {} that does not point to a commit", tag_name);
+            continue;
+        };
+        // Lightweight tags point straight at the commit
+        let annotation = reference
+            .target()
+            .and_then(|target| git_repo.find_tag(target).ok());
+        found.push((tag_name, commit_hash, annotation));
+    }
+    found.sort_by(|(a, ..), (b, ..)| a.as_str().cmp(b.as_str()));
+
+    let mut events = Vec::new();
+    let mut current = HashSet::new();
+    for (tag_name, commit_hash, annotation) in found {
+        current.insert(tag_name.clone());
+        match repository.tags.get(&tag_name) {
+            Some(previous) if *previous == commit_hash => continue,
+            Some(previous) => events.push(GitDomainEvent::TagDeleted(TagDeleted {
+                repository_id: repository.id,
+                tag_name: tag_name.clone(),
+                last_commit: previous.clone(),
+                timestamp: Utc::now(),
+            })),
+            None => {}
+        }
+
+        events.push(GitDomainEvent::TagCreated(TagCreated {
+            repository_id: repository.id,
+            tag_name,
+            commit_hash,
+            message: annotation
+                .as_ref()
+                .and_then(|tag| tag.message())
+                .map(|message| strip_signature(message).trim_end().to_string()),
+            tagger: annotation
+                .as_ref()
+                .and_then(git2::Tag::tagger)
+                .map(|tagger| identities.resolve(&tagger).0),
+            annotated: annotation.is_some(),
+            signature: annotation
+                .as_ref()
+                .and_then(|tag| tag_signature(git_repo, tag.id(), verifier)),
+            timestamp: Utc::now(),
+        }));
+    }
+
+    let mut deleted: Vec<_> = repository
+        .tags
+        .iter()
+        .filter(|(name, _)| !current.contains(*name))
+        .collect();
+    deleted.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+
+    events.extend(deleted.into_iter().map(|(name, last_commit)| {
+        GitDomainEvent::TagDeleted(TagDeleted {
+            repository_id: repository.id,
+            tag_name: name.clone(),
+            last_commit: last_commit.clone(),
+            timestamp: Utc::now(),
+        })
+    }));
+
+    Ok(events)
+}
+
+/// Apply an event to the aggregate and hand it to the consumer
+fn publish(
+    repository: &mut Repository,
+    emit: &mut dyn FnMut(GitDomainEvent) -> bool,
+    event: GitDomainEvent,
+) -> Result<bool, GitDomainError> {
+    repository
+        .apply_event(&event)
+        .map_err(|e| GitDomainError::GitOperationFailed(format!("Failed to apply event: {e}")))?;
+
+    Ok(emit(event))
+}
+
+/// Build the analysis event for a single commit
+pub(super) fn analyze_commit(
+    git_repo: &Git2Repository,
+    repo_id: RepositoryId,
+    commit: &git2::Commit<'_>,
+    commit_timestamp: DateTime<Utc>,
+    identities: &IdentityMapper<'_>,
+    verifier: Option<&SignatureVerifier>,
+) -> Result<CommitAnalyzed, GitDomainError> {
+    let commit_hash = CommitHash::new(commit.id().to_string())
+        .map_err(|e| GitDomainError::GitOperationFailed(format!("Invalid commit hash: {e}")))?;
+
+    let (author, raw_author) = identities.resolve(&commit.author());
+
+    let parents: Vec<CommitHash> = commit
+        .parent_ids()
+        .filter_map(|oid| CommitHash::new(oid.to_string()).ok())
+        .collect();
+
+    let changes = diff::commit_changes(git_repo, commit).map_err(|e| {
+        GitDomainError::GitOperationFailed(format!("Failed to diff commit {commit_hash}: {e}"))
+    })?;
+
+    let message = commit.message().unwrap_or("No message").to_string();
+    // Blank messages have no structure to parse
+    let parsed_message = CommitMessage::new(&message)
+        .map(|message| message.parse())
+        .unwrap_or_default();
+
+    Ok(CommitAnalyzed {
+        repository_id: repo_id,
+        commit_hash,
+        parents,
+        author,
+        raw_author,
+        message,
+        parsed_message,
+        signature: commit_signature(git_repo, commit.id(), verifier),
+        files_changed: changes.files_changed,
+        parent_diffs: changes.parent_diffs,
+        combined_files: changes.combined_files,
+        commit_timestamp,
+        timestamp: Utc::now(),
+    })
+}
+
+/// Build the merge event for a commit with more than one parent
+fn merge_detected(commit: &CommitAnalyzed) -> Option<MergeDetected> {
+    if commit.parents.len() < 2 {
+        return None;
+    }
+
+    // Only the branch names git puts in its default merge messages are known
+    let branches = MERGE_MESSAGE
+        .captures(commit.message.lines().next().unwrap_or_default())
+        .map(|captures| {
+            let merged = captures.get(1).map_or("", |m| m.as_str());
+            QUOTED_BRANCH
+                .captures_iter(merged)
+                .filter_map(|quoted| quoted.get(1))
+                .chain(captures.get(2))
+                .filter_map(|name| BranchName::new(name.as_str()).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(MergeDetected {
+        repository_id: commit.repository_id,
+        merge_commit: commit.commit_hash.clone(),
+        parents: commit.parents.clone(),
+        branches,
+        // Git does not record the strategy; more than two parents is an octopus
+        merge_strategy: (commit.parents.len() > 2).then(|| "octopus".to_string()),
+        // A merge commit does not record which of its files conflicted
+        conflicts: Vec::new(),
+        timestamp: Utc::now(),
+    })
+}
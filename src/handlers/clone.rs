@@ -0,0 +1,241 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! Remote cloning for the repository command handler
+//!
+//! Clones are performed through git2 so that `branch` and `depth` on
+//! [`CloneRepository`] are honoured, and transfer progress is surfaced as
+//! [`CloneProgressUpdated`] events while the clone is running.
+
+use super::RepositoryCommandHandler;
+use crate::aggregate::{Repository, RepositoryId};
+use crate::commands::CloneRepository;
+use crate::events::{CloneProgressUpdated, CloneStage, GitDomainEvent};
+use crate::GitDomainError;
+use chrono::Utc;
+use git2::build::RepoBuilder;
+use git2::{ErrorClass, ErrorCode, FetchOptions, RemoteCallbacks};
+use std::path::Path;
+use tokio::sync::mpsc;
+use tracing::{info, instrument, Span};
+
+/// Percentage step between two consecutive progress events of the same stage
+const PROGRESS_STEP_PERCENT: usize = 10;
+
+/// Progress events buffered between the clone thread and the caller
+const PROGRESS_BUFFER: usize = 64;
+
+impl RepositoryCommandHandler {
+    /// Clone a remote repository to its local path
+    ///
+    /// Returns the repository ID together with every event produced by the
+    /// clone, including the intermediate progress events.
+    pub async fn clone_repository(
+        &self,
+        command: &CloneRepository,
+    ) -> Result<(RepositoryId, Vec<GitDomainEvent>), GitDomainError> {
+        self.clone_repository_with_progress(command, |_| {}).await
+    }
+
+    /// Clone a remote repository, reporting progress events as they happen
+    ///
+    /// The clone runs on a blocking thread; `on_progress` is invoked for each
+    /// [`CloneProgressUpdated`] event as it arrives, so long clones can be
+    /// observed before the operation completes.
+    #[instrument(skip(self, command, on_progress), fields(url = %command.remote_url))]
+    pub async fn clone_repository_with_progress<F>(
+        &self,
+        command: &CloneRepository,
+        mut on_progress: F,
+    ) -> Result<(RepositoryId, Vec<GitDomainEvent>), GitDomainError>
+    where
+        F: FnMut(&GitDomainEvent),
+    {
+        crate::security::validate_path(&command.local_path)?;
+
+        if command.depth.is_some() && command.remote_url.is_local() {
+            return Err(GitDomainError::ShallowCloneUnsupported(format!(
+                "local transport cannot provide a shallow clone of {}",
+                command.remote_url
+            )));
+        }
+
+        let repo_id = command.repository_id.unwrap_or_default();
+        if self
+            .get_repository(&repo_id)
+            .is_some_and(|repository| repository.local_path.is_some())
+        {
+            return Err(GitDomainError::RepositoryAlreadyCloned(repo_id));
+        }
+
+        info!("Cloning {} into {}", command.remote_url, command.local_path);
+
+        // libgit2 blocks for the whole transfer, so the clone runs on a
+        // blocking thread and hands its progress back through a channel
+        let (sender, mut receiver) = mpsc::channel(PROGRESS_BUFFER);
+        let clone = {
+            let command = command.clone();
+            let span = Span::current();
+            tokio::task::spawn_blocking(move || {
+                let _entered = span.enter();
+                clone_with_progress(&command, repo_id, |event| {
+                    // A dropped receiver only means nobody watches progress
+                    let _ = sender.blocking_send(GitDomainEvent::CloneProgressUpdated(event));
+                })
+            })
+        };
+
+        let mut events = Vec::new();
+        while let Some(event) = receiver.recv().await {
+            on_progress(&event);
+            events.push(event);
+        }
+        clone
+            .await
+            .map_err(|e| GitDomainError::GitOperationFailed(format!("Clone task failed: {e}")))??;
+
+        let mut repos = self.repositories.lock().map_err(|_| {
+            GitDomainError::GitOperationFailed("Failed to acquire repository lock".to_string())
+        })?;
+
+        let mut repository = repos.remove(&repo_id).unwrap_or_else(|| {
+            let name = command
+                .remote_url
+                .repository_name()
+                .unwrap_or("unknown")
+                .to_string();
+            let mut repository = Repository::new(name);
+            repository.id = repo_id;
+            repository
+        });
+
+        let cloned =
+            repository.clone_repository(command.remote_url.clone(), command.local_path.clone());
+        repos.insert(repo_id, repository);
+        events.extend(cloned?);
+
+        info!("Cloned repository {} ({} events)", repo_id, events.len());
+
+        Ok((repo_id, events))
+    }
+}
+
+/// Clone `command.remote_url` with git2, passing progress events to `on_progress`
+fn clone_with_progress(
+    command: &CloneRepository,
+    repo_id: RepositoryId,
+    mut on_progress: impl FnMut(CloneProgressUpdated),
+) -> Result<(), GitDomainError> {
+    let mut tracker = ProgressTracker::default();
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.transfer_progress(|progress| {
+        if let Some(event) = tracker.observe(repo_id, &progress) {
+            on_progress(event);
+        }
+        true
+    });
+
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(callbacks);
+    if let Some(depth) = command.depth {
+        fetch_options.depth(i32::try_from(depth).unwrap_or(i32::MAX));
+    }
+
+    let mut builder = RepoBuilder::new();
+    builder.fetch_options(fetch_options);
+    if let Some(branch) = &command.branch {
+        builder.branch(branch.as_str());
+    }
+
+    builder
+        .clone(command.remote_url.as_str(), Path::new(&command.local_path))
+        .map(|_| ())
+        .map_err(|e| map_clone_error(&e, command))
+}
+
+/// Throttles libgit2 transfer callbacks into discrete progress events
+#[derive(Default)]
+struct ProgressTracker {
+    last: Option<(CloneStage, usize)>,
+}
+
+impl ProgressTracker {
+    fn observe(
+        &mut self,
+        repository_id: RepositoryId,
+        progress: &git2::Progress<'_>,
+    ) -> Option<CloneProgressUpdated> {
+        let (stage, done, total) = if progress.received_objects() < progress.total_objects() {
+            (
+                CloneStage::ReceivingObjects,
+                progress.received_objects(),
+                progress.total_objects(),
+            )
+        } else {
+            (
+                CloneStage::ResolvingDeltas,
+                progress.indexed_deltas(),
+                progress.total_deltas(),
+            )
+        };
+
+        let percent = (done * 100).checked_div(total).unwrap_or(100);
+        let bucket = percent / PROGRESS_STEP_PERCENT;
+
+        if self.last == Some((stage, bucket)) {
+            return None;
+        }
+        self.last = Some((stage, bucket));
+
+        Some(CloneProgressUpdated {
+            repository_id,
+            stage,
+            received_objects: progress.received_objects(),
+            indexed_objects: progress.indexed_objects(),
+            total_objects: progress.total_objects(),
+            received_bytes: progress.received_bytes(),
+            indexed_deltas: progress.indexed_deltas(),
+            total_deltas: progress.total_deltas(),
+            timestamp: Utc::now(),
+        })
+    }
+}
+
+/// Map a git2 clone failure onto the matching domain error
+fn map_clone_error(error: &git2::Error, command: &CloneRepository) -> GitDomainError {
+    let url = command.remote_url.as_str();
+
+    if let Some(transport) = map_transport_error(error, url) {
+        return transport;
+    }
+
+    match (error.code(), error.class()) {
+        (ErrorCode::Exists, _) => GitDomainError::CloneTargetExists(command.local_path.clone()),
+        (ErrorCode::NotFound, ErrorClass::Reference) if command.branch.is_some() => {
+            GitDomainError::BranchNotFound(
+                command
+                    .branch
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default(),
+            )
+        }
+        _ => GitDomainError::GitOperationFailed(format!("Failed to clone {url}: {error}")),
+    }
+}
+
+/// Map authentication and network failures talking to `url`
+pub(super) fn map_transport_error(error: &git2::Error, url: &str) -> Option<GitDomainError> {
+    match (error.code(), error.class()) {
+        (ErrorCode::Auth | ErrorCode::Certificate, _) => Some(
+            GitDomainError::AuthenticationFailed(format!("{url}: {}", error.message())),
+        ),
+        (
+            _,
+            ErrorClass::Net | ErrorClass::Http | ErrorClass::Ssh | ErrorClass::Ssl | ErrorClass::Os,
+        ) => Some(GitDomainError::RemoteUnreachable(format!(
+            "{url}: {}",
+            error.message()
+        ))),
+        _ => None,
+    }
+}
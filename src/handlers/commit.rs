@@ -0,0 +1,244 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! On-demand analysis of a single commit for the repository command handler
+//!
+//! Answers "what did this commit do" without walking the rest of the history:
+//! the commit is diffed against its parents and, when requested, every file it
+//! touched is measured and scanned for dependencies.
+
+use super::{analysis, RepositoryCommandHandler};
+use crate::aggregate::RepositoryId;
+use crate::commands::AnalyzeCommit;
+use crate::dependency_analysis::{DependencyAnalyzer, Language};
+use crate::events::{FileAnalyzed, FileChangeInfo, FileChangeType, FileMetrics, GitDomainEvent};
+use crate::value_objects::{CommitHash, FilePath};
+use crate::GitDomainError;
+use chrono::{DateTime, Utc};
+use git2::{Commit, ObjectType, Repository as Git2Repository};
+use regex::Regex;
+use std::sync::LazyLock;
+use tracing::{info, instrument};
+
+/// Function definitions in languages that declare them with a keyword
+static FUNCTION_DEFINITION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?(?:async\s+)?(?:fn|def|func|function)\s+[A-Za-z_(]",
+    )
+    .expect("Invalid regex")
+});
+
+/// Branch points counted towards cyclomatic complexity
+static DECISION_POINT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(?:if|elif|for|while|case|catch|except)\b|&&|\|\|").expect("Invalid regex")
+});
+
+/// Manifests understood by [`DependencyAnalyzer::analyze_manifest`]
+const MANIFESTS: [&str; 4] = ["Cargo.toml", "package.json", "requirements.txt", "go.mod"];
+
+impl RepositoryCommandHandler {
+    /// Analyze a single commit of a known repository
+    ///
+    /// Emits a [`CommitAnalyzed`](crate::events::CommitAnalyzed) event with
+    /// the full file changes, followed by one [`FileAnalyzed`] event per added
+    /// or modified file when `analyze_files` is set. The commit hash may be
+    /// abbreviated.
+    #[instrument(skip(self, command), fields(commit = %command.commit_hash))]
+    pub async fn analyze_commit(
+        &self,
+        command: &AnalyzeCommit,
+    ) -> Result<Vec<GitDomainEvent>, GitDomainError> {
+        let (_, git_repo) = self.open_local(&command.repository_id)?;
+        let commit = git_repo
+            .revparse_single(command.commit_hash.as_str())
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|e| {
+                GitDomainError::InvalidCommitHash(format!("{}: {e}", command.commit_hash))
+            })?;
+
+        let commit_timestamp =
+            DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or_else(Utc::now);
+        let analyzed = analysis::analyze_commit(
+            &git_repo,
+            command.repository_id,
+            &commit,
+            commit_timestamp,
+            &self.identities(&git_repo),
+            self.signature_verifier.as_deref(),
+        )?;
+
+        let mut events = Vec::with_capacity(analyzed.files_changed.len() + 1);
+        if command.analyze_files {
+            let dependencies = command.extract_dependencies.then(DependencyAnalyzer::new);
+            for change in &analyzed.files_changed {
+                if let Some(event) = analyze_file(
+                    &git_repo,
+                    command.repository_id,
+                    &commit,
+                    &analyzed.commit_hash,
+                    change,
+                    dependencies.as_ref(),
+                )? {
+                    events.push(GitDomainEvent::FileAnalyzed(event));
+                }
+            }
+        }
+        events.insert(0, GitDomainEvent::CommitAnalyzed(analyzed));
+
+        info!(
+            "Analyzed commit {} ({} events)",
+            command.commit_hash,
+            events.len()
+        );
+
+        Ok(events)
+    }
+}
+
+/// Measure a file as it exists in `commit`
+///
+/// Returns `None` for deleted files and paths that are not regular blobs,
+/// such as submodules.
+pub(super) fn analyze_file(
+    git_repo: &Git2Repository,
+    repo_id: RepositoryId,
+    commit: &Commit<'_>,
+    commit_hash: &CommitHash,
+    change: &FileChangeInfo,
+    analyzer: Option<&DependencyAnalyzer>,
+) -> Result<Option<FileAnalyzed>, GitDomainError> {
+    if change.change_type == FileChangeType::Deleted {
+        return Ok(None);
+    }
+
+    let read_error = |e: git2::Error| {
+        GitDomainError::GitOperationFailed(format!("Failed to read {}: {e}", change.path))
+    };
+    let entry = commit
+        .tree()
+        .and_then(|tree| tree.get_path(std::path::Path::new(change.path.as_str())))
+        .map_err(read_error)?;
+    if entry.kind() != Some(ObjectType::Blob) {
+        return Ok(None);
+    }
+    let blob = git_repo.find_blob(entry.id()).map_err(read_error)?;
+
+    let size_bytes = blob.size() as u64;
+    let content = if change.binary || blob.is_binary() {
+        None
+    } else {
+        std::str::from_utf8(blob.content()).ok()
+    };
+
+    let language = change
+        .path
+        .as_str()
+        .rsplit_once('.')
+        .map(|(_, ext)| Language::from_extension(ext));
+
+    let metrics = match content {
+        Some(content) => file_metrics(content, language.as_ref(), size_bytes),
+        None => FileMetrics {
+            lines_of_code: 0,
+            function_count: None,
+            complexity: None,
+            language: None,
+            size_bytes,
+        },
+    };
+
+    let dependencies = match (analyzer, content) {
+        (Some(analyzer), Some(content)) => {
+            file_dependencies(analyzer, &change.path, content, language.as_ref())?
+        }
+        _ => Vec::new(),
+    };
+
+    Ok(Some(FileAnalyzed {
+        repository_id: repo_id,
+        file_path: change.path.clone(),
+        commit_hash: commit_hash.clone(),
+        metrics,
+        dependencies,
+        timestamp: Utc::now(),
+    }))
+}
+
+/// Compute line, function and complexity metrics for textual content
+///
+/// Complexity is a decision-point count and is only reported for languages
+/// the dependency analyzer recognises; functions are only counted for
+/// languages that introduce them with a keyword.
+fn file_metrics(content: &str, language: Option<&Language>, size_bytes: u64) -> FileMetrics {
+    let lines_of_code = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .count();
+
+    let function_count = match language {
+        Some(
+            Language::Rust
+            | Language::Python
+            | Language::JavaScript
+            | Language::TypeScript
+            | Language::Go,
+        ) => Some(FUNCTION_DEFINITION.find_iter(content).count()),
+        _ => None,
+    };
+
+    let language = language.and_then(language_name);
+    let complexity = language.as_ref().map(|_| {
+        let decisions = DECISION_POINT.find_iter(content).count();
+        u32::try_from(decisions + 1).unwrap_or(u32::MAX)
+    });
+
+    FileMetrics {
+        lines_of_code,
+        function_count,
+        complexity,
+        language,
+        size_bytes,
+    }
+}
+
+/// Extract the dependencies declared by a source file or package manifest
+fn file_dependencies(
+    analyzer: &DependencyAnalyzer,
+    path: &FilePath,
+    content: &str,
+    language: Option<&Language>,
+) -> Result<Vec<FilePath>, GitDomainError> {
+    let file_name = path.file_name().unwrap_or_default();
+    let found = if MANIFESTS.contains(&file_name) {
+        analyzer.analyze_manifest(content, file_name)?
+    } else if let Some(language) = language {
+        analyzer.analyze_file(content, language)?
+    } else {
+        return Ok(Vec::new());
+    };
+
+    // Relative imports that climb out of the tree are not valid paths
+    let mut dependencies: Vec<FilePath> = found
+        .into_iter()
+        .filter_map(|dependency| FilePath::new(dependency.name).ok())
+        .collect();
+    dependencies.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+    dependencies.dedup();
+
+    Ok(dependencies)
+}
+
+/// Display name of a recognised language
+fn language_name(language: &Language) -> Option<String> {
+    let name = match language {
+        Language::Rust => "Rust",
+        Language::Python => "Python",
+        Language::JavaScript => "JavaScript",
+        Language::TypeScript => "TypeScript",
+        Language::Java => "Java",
+        Language::Go => "Go",
+        Language::C => "C",
+        Language::Cpp => "C++",
+        Language::Other(_) => return None,
+    };
+    Some(name.to_string())
+}
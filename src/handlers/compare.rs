@@ -0,0 +1,171 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! Branch comparison for the repository command handler
+//!
+//! Compares two branches the way a pull request would: commits ahead and
+//! behind, and the changes the compare branch made since the merge base.
+
+use super::diff::{self, DiffHunk};
+use super::{git_error, to_commit_hash, RepositoryCommandHandler};
+use crate::commands::CompareBranches;
+use crate::events::{BranchesCompared, FileChangeInfo, GitDomainEvent};
+use crate::value_objects::{BranchName, CommitHash};
+use crate::GitDomainError;
+use chrono::Utc;
+use git2::{Commit, ErrorCode, Oid, Repository as Git2Repository, Sort};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+
+/// Result of comparing two branches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchComparison {
+    /// Branch compared against
+    pub base_branch: BranchName,
+
+    /// Branch compared to the base
+    pub compare_branch: BranchName,
+
+    /// Commit the base branch points to
+    pub base_commit: CommitHash,
+
+    /// Commit the compare branch points to
+    pub compare_commit: CommitHash,
+
+    /// Best common ancestor, if the branches share history
+    pub merge_base: Option<CommitHash>,
+
+    /// Commits on the compare branch that are not on the base branch, newest first
+    pub ahead: Vec<CommitHash>,
+
+    /// Commits on the base branch that are not on the compare branch, newest first
+    pub behind: Vec<CommitHash>,
+
+    /// Files the compare branch changed since the merge base
+    pub files_changed: Vec<FileChangeInfo>,
+
+    /// Unified diff hunks, only filled when diffs were requested
+    pub hunks: Vec<DiffHunk>,
+}
+
+impl BranchComparison {
+    /// Whether the base branch can be fast-forwarded to the compare branch
+    #[must_use]
+    pub fn is_fast_forward(&self) -> bool {
+        self.behind.is_empty()
+    }
+}
+
+impl RepositoryCommandHandler {
+    /// Compare two branches of a known repository
+    ///
+    /// Changes are those of the compare branch since the merge base, like
+    /// `git diff base...compare`. Without a merge base the two tips are diffed
+    /// directly. The comparison is recorded as a [`BranchesCompared`] event.
+    #[instrument(skip(self, command), fields(base = %command.base_branch, compare = %command.compare_branch))]
+    pub async fn compare_branches(
+        &self,
+        command: &CompareBranches,
+    ) -> Result<(BranchComparison, Vec<GitDomainEvent>), GitDomainError> {
+        let (_, git_repo) = self.open_local(&command.repository_id)?;
+
+        let base = resolve_branch(&git_repo, &command.base_branch)?;
+        let compare = resolve_branch(&git_repo, &command.compare_branch)?;
+
+        let merge_base = match git_repo.merge_base(base.id(), compare.id()) {
+            Ok(oid) => Some(oid),
+            Err(e) if e.code() == ErrorCode::NotFound => None,
+            Err(e) => return Err(git_error("find merge base", &e)),
+        };
+
+        let ahead = exclusive_commits(&git_repo, compare.id(), base.id())?;
+        let behind = exclusive_commits(&git_repo, base.id(), compare.id())?;
+
+        let old_tree = match merge_base {
+            Some(oid) => git_repo.find_commit(oid).and_then(|commit| commit.tree()),
+            None => base.tree(),
+        }
+        .map_err(|e| git_error("read base tree", &e))?;
+        let new_tree = compare
+            .tree()
+            .map_err(|e| git_error("read compare tree", &e))?;
+
+        let files_changed = diff::file_changes(&git_repo, Some(&old_tree), &new_tree)
+            .map_err(|e| git_error("diff branches", &e))?;
+        let hunks = if command.include_diffs {
+            diff::diff_hunks(&git_repo, Some(&old_tree), &new_tree)
+                .map_err(|e| git_error("diff branches", &e))?
+        } else {
+            Vec::new()
+        };
+
+        let comparison = BranchComparison {
+            base_branch: command.base_branch.clone(),
+            compare_branch: command.compare_branch.clone(),
+            base_commit: to_commit_hash(base.id())?,
+            compare_commit: to_commit_hash(compare.id())?,
+            merge_base: merge_base.map(to_commit_hash).transpose()?,
+            ahead,
+            behind,
+            files_changed,
+            hunks,
+        };
+
+        info!(
+            "Compared {}..{}: {} ahead, {} behind, {} files changed",
+            comparison.base_branch,
+            comparison.compare_branch,
+            comparison.ahead.len(),
+            comparison.behind.len(),
+            comparison.files_changed.len()
+        );
+
+        let event = GitDomainEvent::BranchesCompared(BranchesCompared {
+            repository_id: command.repository_id,
+            base_branch: comparison.base_branch.clone(),
+            compare_branch: comparison.compare_branch.clone(),
+            base_commit: comparison.base_commit.clone(),
+            compare_commit: comparison.compare_commit.clone(),
+            merge_base: comparison.merge_base.clone(),
+            ahead: comparison.ahead.clone(),
+            behind: comparison.behind.clone(),
+            files_changed: comparison.files_changed.clone(),
+            timestamp: Utc::now(),
+        });
+
+        Ok((comparison, vec![event]))
+    }
+}
+
+/// Resolve a local or remote-tracking branch to its tip commit
+fn resolve_branch<'repo>(
+    git_repo: &'repo Git2Repository,
+    branch: &BranchName,
+) -> Result<Commit<'repo>, GitDomainError> {
+    git_repo
+        .resolve_reference_from_short_name(branch.as_str())
+        .and_then(|reference| reference.peel_to_commit())
+        .map_err(|_| GitDomainError::BranchNotFound(branch.to_string()))
+}
+
+/// Commits reachable from `from` but not from `exclude`, newest first
+fn exclusive_commits(
+    git_repo: &Git2Repository,
+    from: Oid,
+    exclude: Oid,
+) -> Result<Vec<CommitHash>, GitDomainError> {
+    let mut revwalk = git_repo
+        .revwalk()
+        .map_err(|e| git_error("create revwalk", &e))?;
+    revwalk
+        .set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+        .and_then(|()| revwalk.push(from))
+        .and_then(|()| revwalk.hide(exclude))
+        .map_err(|e| git_error("walk history", &e))?;
+
+    revwalk
+        .map(|oid| {
+            oid.map_err(|e| git_error("walk history", &e))
+                .and_then(to_commit_hash)
+        })
+        .collect()
+}
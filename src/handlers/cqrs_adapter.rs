@@ -3,16 +3,28 @@
 //! CQRS adapter for Git domain handlers
 
 use crate::commands::{
-    AnalyzeCommit, AnalyzeFileHistory, AnalyzeRepository, CloneRepository, CompareBranches,
-    CreateBranch, CreateTag, DeleteBranch, FetchRemote, GitHubIntegration, SearchRepository,
+    AnalyzeCommit, AnalyzeFileHistory, AnalyzeRepository, CheckSignaturePolicy, CloneRepository,
+    CompareBranches, CreateBranch, CreateTag, DeleteBranch, FetchRemote, GenerateReleaseNotes,
+    GitHubIntegration, SearchRepository,
 };
 // Note: ExtractCommitGraph and ExtractDependencyGraph have been removed
 use crate::handlers::RepositoryCommandHandler;
+use crate::GitDomainError;
 use cim_domain::{CommandAcknowledgment, CommandEnvelope, CommandHandler, CommandStatus};
 
 // Note: ExtractCommitGraphHandler has been removed
 // This was dependent on cim_domain_graph which is no longer available
 
+/// Reason reported when a command is rejected
+///
+/// Unknown repositories keep the short "Repository not found" reason.
+fn rejection_reason(error: &GitDomainError, action: &str) -> String {
+    match error {
+        GitDomainError::RepositoryNotFound(_) => "Repository not found".to_string(),
+        e => format!("Failed to {action}: {e}"),
+    }
+}
+
 /// CQRS adapter for `CloneRepository` command
 pub struct CloneRepositoryHandler {
     repository_handler: RepositoryCommandHandler,
@@ -31,13 +43,8 @@ impl CommandHandler<CloneRepository> for CloneRepositoryHandler {
 
         // Clone repository using git2
         let runtime = tokio::runtime::Runtime::new().unwrap();
-        let result = runtime.block_on(async {
-            // For now, analyze the repository at the given path
-            // In a full implementation, this would actually clone from remote
-            self.repository_handler
-                .analyze_repository_at_path(&command.local_path)
-                .await
-        });
+        let result =
+            runtime.block_on(async { self.repository_handler.clone_repository(&command).await });
 
         match result {
             Ok(_) => CommandAcknowledgment {
@@ -72,26 +79,22 @@ impl CommandHandler<AnalyzeCommit> for AnalyzeCommitHandler {
     fn handle(&mut self, envelope: CommandEnvelope<AnalyzeCommit>) -> CommandAcknowledgment {
         let command = envelope.command;
 
-        // Get repository and analyze specific commit
-        let repo = self
-            .repository_handler
-            .get_repository(&command.repository_id);
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result =
+            runtime.block_on(async { self.repository_handler.analyze_commit(&command).await });
 
-        match repo {
-            Some(_) => {
-                // In a full implementation, would analyze the specific commit
-                CommandAcknowledgment {
-                    command_id: envelope.id,
-                    correlation_id: envelope.identity.correlation_id,
-                    status: CommandStatus::Accepted,
-                    reason: None,
-                }
-            }
-            None => CommandAcknowledgment {
+        match result {
+            Ok(_) => CommandAcknowledgment {
+                command_id: envelope.id,
+                correlation_id: envelope.identity.correlation_id,
+                status: CommandStatus::Accepted,
+                reason: None,
+            },
+            Err(e) => CommandAcknowledgment {
                 command_id: envelope.id,
                 correlation_id: envelope.identity.correlation_id,
                 status: CommandStatus::Rejected,
-                reason: Some("Repository not found".to_string()),
+                reason: Some(rejection_reason(&e, "analyze commit")),
             },
         }
     }
@@ -116,26 +119,22 @@ impl CommandHandler<CreateBranch> for CreateBranchHandler {
     fn handle(&mut self, envelope: CommandEnvelope<CreateBranch>) -> CommandAcknowledgment {
         let command = envelope.command;
 
-        // Check if repository exists
-        let repo = self
-            .repository_handler
-            .get_repository(&command.repository_id);
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result =
+            runtime.block_on(async { self.repository_handler.create_branch(&command).await });
 
-        match repo {
-            Some(_) => {
-                // In a full implementation, would create the branch
-                CommandAcknowledgment {
-                    command_id: envelope.id,
-                    correlation_id: envelope.identity.correlation_id,
-                    status: CommandStatus::Accepted,
-                    reason: None,
-                }
-            }
-            None => CommandAcknowledgment {
+        match result {
+            Ok(_) => CommandAcknowledgment {
+                command_id: envelope.id,
+                correlation_id: envelope.identity.correlation_id,
+                status: CommandStatus::Accepted,
+                reason: None,
+            },
+            Err(e) => CommandAcknowledgment {
                 command_id: envelope.id,
                 correlation_id: envelope.identity.correlation_id,
                 status: CommandStatus::Rejected,
-                reason: Some("Repository not found".to_string()),
+                reason: Some(rejection_reason(&e, "create branch")),
             },
         }
     }
@@ -157,26 +156,22 @@ impl CommandHandler<DeleteBranch> for DeleteBranchHandler {
     fn handle(&mut self, envelope: CommandEnvelope<DeleteBranch>) -> CommandAcknowledgment {
         let command = envelope.command;
 
-        // Check if repository exists
-        let repo = self
-            .repository_handler
-            .get_repository(&command.repository_id);
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result =
+            runtime.block_on(async { self.repository_handler.delete_branch(&command).await });
 
-        match repo {
-            Some(_) => {
-                // In a full implementation, would delete the branch
-                CommandAcknowledgment {
-                    command_id: envelope.id,
-                    correlation_id: envelope.identity.correlation_id,
-                    status: CommandStatus::Accepted,
-                    reason: None,
-                }
-            }
-            None => CommandAcknowledgment {
+        match result {
+            Ok(_) => CommandAcknowledgment {
+                command_id: envelope.id,
+                correlation_id: envelope.identity.correlation_id,
+                status: CommandStatus::Accepted,
+                reason: None,
+            },
+            Err(e) => CommandAcknowledgment {
                 command_id: envelope.id,
                 correlation_id: envelope.identity.correlation_id,
                 status: CommandStatus::Rejected,
-                reason: Some("Repository not found".to_string()),
+                reason: Some(rejection_reason(&e, "delete branch")),
             },
         }
     }
@@ -198,26 +193,21 @@ impl CommandHandler<CreateTag> for CreateTagHandler {
     fn handle(&mut self, envelope: CommandEnvelope<CreateTag>) -> CommandAcknowledgment {
         let command = envelope.command;
 
-        // Check if repository exists
-        let repo = self
-            .repository_handler
-            .get_repository(&command.repository_id);
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(async { self.repository_handler.create_tag(&command).await });
 
-        match repo {
-            Some(_) => {
-                // In a full implementation, would create the tag
-                CommandAcknowledgment {
-                    command_id: envelope.id,
-                    correlation_id: envelope.identity.correlation_id,
-                    status: CommandStatus::Accepted,
-                    reason: None,
-                }
-            }
-            None => CommandAcknowledgment {
+        match result {
+            Ok(_) => CommandAcknowledgment {
+                command_id: envelope.id,
+                correlation_id: envelope.identity.correlation_id,
+                status: CommandStatus::Accepted,
+                reason: None,
+            },
+            Err(e) => CommandAcknowledgment {
                 command_id: envelope.id,
                 correlation_id: envelope.identity.correlation_id,
                 status: CommandStatus::Rejected,
-                reason: Some("Repository not found".to_string()),
+                reason: Some(rejection_reason(&e, "create tag")),
             },
         }
     }
@@ -304,26 +294,22 @@ impl CommandHandler<FetchRemote> for FetchRemoteHandler {
     fn handle(&mut self, envelope: CommandEnvelope<FetchRemote>) -> CommandAcknowledgment {
         let command = envelope.command;
 
-        // Check if repository exists
-        let repo = self
-            .repository_handler
-            .get_repository(&command.repository_id);
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result =
+            runtime.block_on(async { self.repository_handler.fetch_remote(&command).await });
 
-        match repo {
-            Some(_) => {
-                // In a full implementation, would fetch from remote
-                CommandAcknowledgment {
-                    command_id: envelope.id,
-                    correlation_id: envelope.identity.correlation_id,
-                    status: CommandStatus::Accepted,
-                    reason: None,
-                }
-            }
-            None => CommandAcknowledgment {
+        match result {
+            Ok(_) => CommandAcknowledgment {
+                command_id: envelope.id,
+                correlation_id: envelope.identity.correlation_id,
+                status: CommandStatus::Accepted,
+                reason: None,
+            },
+            Err(e) => CommandAcknowledgment {
                 command_id: envelope.id,
                 correlation_id: envelope.identity.correlation_id,
                 status: CommandStatus::Rejected,
-                reason: Some("Repository not found".to_string()),
+                reason: Some(rejection_reason(&e, "fetch remote")),
             },
         }
     }
@@ -345,26 +331,22 @@ impl CommandHandler<AnalyzeFileHistory> for AnalyzeFileHistoryHandler {
     fn handle(&mut self, envelope: CommandEnvelope<AnalyzeFileHistory>) -> CommandAcknowledgment {
         let command = envelope.command;
 
-        // Check if repository exists
-        let repo = self
-            .repository_handler
-            .get_repository(&command.repository_id);
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime
+            .block_on(async { self.repository_handler.analyze_file_history(&command).await });
 
-        match repo {
-            Some(_) => {
-                // In a full implementation, would analyze file history
-                CommandAcknowledgment {
-                    command_id: envelope.id,
-                    correlation_id: envelope.identity.correlation_id,
-                    status: CommandStatus::Accepted,
-                    reason: None,
-                }
-            }
-            None => CommandAcknowledgment {
+        match result {
+            Ok(_) => CommandAcknowledgment {
+                command_id: envelope.id,
+                correlation_id: envelope.identity.correlation_id,
+                status: CommandStatus::Accepted,
+                reason: None,
+            },
+            Err(e) => CommandAcknowledgment {
                 command_id: envelope.id,
                 correlation_id: envelope.identity.correlation_id,
                 status: CommandStatus::Rejected,
-                reason: Some("Repository not found".to_string()),
+                reason: Some(rejection_reason(&e, "analyze file history")),
             },
         }
     }
@@ -386,26 +368,102 @@ impl CommandHandler<CompareBranches> for CompareBranchesHandler {
     fn handle(&mut self, envelope: CommandEnvelope<CompareBranches>) -> CommandAcknowledgment {
         let command = envelope.command;
 
-        // Check if repository exists
-        let repo = self
-            .repository_handler
-            .get_repository(&command.repository_id);
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result =
+            runtime.block_on(async { self.repository_handler.compare_branches(&command).await });
 
-        match repo {
-            Some(_) => {
-                // In a full implementation, would compare branches
-                CommandAcknowledgment {
-                    command_id: envelope.id,
-                    correlation_id: envelope.identity.correlation_id,
-                    status: CommandStatus::Accepted,
-                    reason: None,
-                }
-            }
-            None => CommandAcknowledgment {
+        match result {
+            Ok(_) => CommandAcknowledgment {
+                command_id: envelope.id,
+                correlation_id: envelope.identity.correlation_id,
+                status: CommandStatus::Accepted,
+                reason: None,
+            },
+            Err(e) => CommandAcknowledgment {
                 command_id: envelope.id,
                 correlation_id: envelope.identity.correlation_id,
                 status: CommandStatus::Rejected,
-                reason: Some("Repository not found".to_string()),
+                reason: Some(rejection_reason(&e, "compare branches")),
+            },
+        }
+    }
+}
+
+/// CQRS adapter for `GenerateReleaseNotes` command
+pub struct GenerateReleaseNotesHandler {
+    repository_handler: RepositoryCommandHandler,
+}
+
+impl GenerateReleaseNotesHandler {
+    /// Create a new `GenerateReleaseNotesHandler` with the given repository handler
+    pub fn new(repository_handler: RepositoryCommandHandler) -> Self {
+        Self { repository_handler }
+    }
+}
+
+impl CommandHandler<GenerateReleaseNotes> for GenerateReleaseNotesHandler {
+    fn handle(&mut self, envelope: CommandEnvelope<GenerateReleaseNotes>) -> CommandAcknowledgment {
+        let command = envelope.command;
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(async {
+            self.repository_handler
+                .generate_release_notes(&command)
+                .await
+        });
+
+        match result {
+            Ok(_) => CommandAcknowledgment {
+                command_id: envelope.id,
+                correlation_id: envelope.identity.correlation_id,
+                status: CommandStatus::Accepted,
+                reason: None,
+            },
+            Err(e) => CommandAcknowledgment {
+                command_id: envelope.id,
+                correlation_id: envelope.identity.correlation_id,
+                status: CommandStatus::Rejected,
+                reason: Some(rejection_reason(&e, "generate release notes")),
+            },
+        }
+    }
+}
+
+/// CQRS adapter for `CheckSignaturePolicy` command
+pub struct CheckSignaturePolicyHandler {
+    repository_handler: RepositoryCommandHandler,
+}
+
+impl CheckSignaturePolicyHandler {
+    /// Create a new `CheckSignaturePolicyHandler` with the given repository handler
+    pub fn new(repository_handler: RepositoryCommandHandler) -> Self {
+        Self { repository_handler }
+    }
+}
+
+impl CommandHandler<CheckSignaturePolicy> for CheckSignaturePolicyHandler {
+    fn handle(&mut self, envelope: CommandEnvelope<CheckSignaturePolicy>) -> CommandAcknowledgment {
+        let command = envelope.command;
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(async {
+            self.repository_handler
+                .check_signature_policy(&command)
+                .await
+        });
+
+        match result {
+            Ok(_) => CommandAcknowledgment {
+                command_id: envelope.id,
+                correlation_id: envelope.identity.correlation_id,
+                status: CommandStatus::Accepted,
+                reason: None,
+            },
+            Err(e) => CommandAcknowledgment {
+                command_id: envelope.id,
+                correlation_id: envelope.identity.correlation_id,
+                status: CommandStatus::Rejected,
+                reason: Some(rejection_reason(&e, "check signature policy")),
             },
         }
     }
@@ -427,26 +485,22 @@ impl CommandHandler<SearchRepository> for SearchRepositoryHandler {
     fn handle(&mut self, envelope: CommandEnvelope<SearchRepository>) -> CommandAcknowledgment {
         let command = envelope.command;
 
-        // Check if repository exists
-        let repo = self
-            .repository_handler
-            .get_repository(&command.repository_id);
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result =
+            runtime.block_on(async { self.repository_handler.search_repository(&command).await });
 
-        match repo {
-            Some(_) => {
-                // In a full implementation, would search the repository
-                CommandAcknowledgment {
-                    command_id: envelope.id,
-                    correlation_id: envelope.identity.correlation_id,
-                    status: CommandStatus::Accepted,
-                    reason: None,
-                }
-            }
-            None => CommandAcknowledgment {
+        match result {
+            Ok(_) => CommandAcknowledgment {
+                command_id: envelope.id,
+                correlation_id: envelope.identity.correlation_id,
+                status: CommandStatus::Accepted,
+                reason: None,
+            },
+            Err(e) => CommandAcknowledgment {
                 command_id: envelope.id,
                 correlation_id: envelope.identity.correlation_id,
                 status: CommandStatus::Rejected,
-                reason: Some("Repository not found".to_string()),
+                reason: Some(rejection_reason(&e, "search repository")),
             },
         }
     }
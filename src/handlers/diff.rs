@@ -0,0 +1,275 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! Tree diffing shared by the repository command handler
+//!
+//! Produces [`FileChangeInfo`] entries with real line counts, rename and copy
+//! detection, and binary flags. Root commits are diffed against the empty tree
+//! and merge commits against every parent. Unified diff hunks are available for
+//! callers that need the changed lines themselves.
+
+use crate::events::{FileChangeInfo, FileChangeType, ParentDiff};
+use crate::value_objects::{CommitHash, FilePath};
+use git2::{
+    Commit, Delta, Diff, DiffDelta, DiffFindOptions, Patch, Repository as Git2Repository, Tree,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Minimum similarity (percent) for a delete/add pair to count as a rename
+pub(crate) const RENAME_SIMILARITY: u16 = 50;
+
+/// Minimum similarity (percent) for an added file to count as a copy
+pub(crate) const COPY_SIMILARITY: u16 = 50;
+
+/// File changes introduced by a commit
+#[derive(Debug, Default)]
+pub(crate) struct CommitChanges {
+    /// Changes against the first parent, or the empty tree for a root commit
+    pub files_changed: Vec<FileChangeInfo>,
+
+    /// Changes against each parent, for merge commits only
+    pub parent_diffs: Vec<ParentDiff>,
+
+    /// Paths of a merge commit that differ from every parent
+    pub combined_files: Vec<FilePath>,
+}
+
+/// A hunk of a unified diff
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffHunk {
+    /// File the hunk belongs to
+    pub path: FilePath,
+
+    /// Hunk header, e.g. `@@ -1,3 +1,4 @@`
+    pub header: String,
+
+    /// First line of the hunk in the old file
+    pub old_start: u32,
+
+    /// Number of old lines in the hunk
+    pub old_lines: u32,
+
+    /// First line of the hunk in the new file
+    pub new_start: u32,
+
+    /// Number of new lines in the hunk
+    pub new_lines: u32,
+
+    /// Hunk lines prefixed with `+`, `-` or a space
+    pub lines: Vec<String>,
+}
+
+/// Compute the file changes a commit introduces
+pub(crate) fn commit_changes(
+    git_repo: &Git2Repository,
+    commit: &Commit<'_>,
+) -> Result<CommitChanges, git2::Error> {
+    let tree = commit.tree()?;
+
+    if commit.parent_count() == 0 {
+        return Ok(CommitChanges {
+            files_changed: file_changes(git_repo, None, &tree)?,
+            ..CommitChanges::default()
+        });
+    }
+
+    let mut parent_diffs = Vec::with_capacity(commit.parent_count());
+    for parent in commit.parents() {
+        let parent_tree = parent.tree()?;
+        let parent_hash = CommitHash::new(parent.id().to_string())
+            .map_err(|e| git2::Error::from_str(&e.to_string()))?;
+
+        parent_diffs.push(ParentDiff {
+            parent: parent_hash,
+            files_changed: file_changes(git_repo, Some(&parent_tree), &tree)?,
+        });
+    }
+
+    let files_changed = parent_diffs
+        .first()
+        .map(|diff| diff.files_changed.clone())
+        .unwrap_or_default();
+
+    if parent_diffs.len() == 1 {
+        return Ok(CommitChanges {
+            files_changed,
+            ..CommitChanges::default()
+        });
+    }
+
+    // Like `git diff --cc`: only paths the merge result changed relative to
+    // every parent, whether they merged cleanly, conflicted or were edited
+    // in the merge itself
+    let others: Vec<HashSet<&FilePath>> = parent_diffs[1..]
+        .iter()
+        .map(|diff| diff.files_changed.iter().map(|c| &c.path).collect())
+        .collect();
+    let combined_files = files_changed
+        .iter()
+        .map(|change| &change.path)
+        .filter(|path| others.iter().all(|paths| paths.contains(path)))
+        .cloned()
+        .collect();
+
+    Ok(CommitChanges {
+        files_changed,
+        parent_diffs,
+        combined_files,
+    })
+}
+
+/// Diff two trees into file change records
+///
+/// A missing `old_tree` diffs against the empty tree.
+pub(crate) fn file_changes(
+    git_repo: &Git2Repository,
+    old_tree: Option<&Tree<'_>>,
+    new_tree: &Tree<'_>,
+) -> Result<Vec<FileChangeInfo>, git2::Error> {
+    let diff = tree_diff(git_repo, old_tree, new_tree)?;
+
+    let mut changes = Vec::with_capacity(diff.deltas().len());
+    for idx in 0..diff.deltas().len() {
+        // Generating the patch loads the blobs, which settles the binary flag
+        let patch = Patch::from_diff(&diff, idx)?;
+        let delta = match &patch {
+            Some(patch) => patch.delta(),
+            None => match diff.get_delta(idx) {
+                Some(delta) => delta,
+                None => continue,
+            },
+        };
+
+        let (additions, deletions) = match &patch {
+            Some(patch) => {
+                let (_, additions, deletions) = patch.line_stats()?;
+                (additions, deletions)
+            }
+            None => (0, 0),
+        };
+
+        if let Some(change) = change_info(&delta, additions, deletions) {
+            changes.push(change);
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Diff two trees into unified diff hunks
+///
+/// Binary files have no hunks. A missing `old_tree` diffs against the empty
+/// tree.
+pub(crate) fn diff_hunks(
+    git_repo: &Git2Repository,
+    old_tree: Option<&Tree<'_>>,
+    new_tree: &Tree<'_>,
+) -> Result<Vec<DiffHunk>, git2::Error> {
+    let diff = tree_diff(git_repo, old_tree, new_tree)?;
+
+    let mut hunks = Vec::new();
+    for idx in 0..diff.deltas().len() {
+        if let Some(patch) = Patch::from_diff(&diff, idx)? {
+            if let Some(file_path) = delta_path(&patch.delta()) {
+                hunks.extend(patch_hunks(&patch, &file_path)?);
+            }
+        }
+    }
+
+    Ok(hunks)
+}
+
+/// Collect the hunks of a single file's patch
+fn patch_hunks(patch: &Patch<'_>, file_path: &FilePath) -> Result<Vec<DiffHunk>, git2::Error> {
+    let mut hunks = Vec::with_capacity(patch.num_hunks());
+    for hunk_idx in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch.hunk(hunk_idx)?;
+        let mut lines = Vec::with_capacity(line_count);
+        for line_idx in 0..line_count {
+            let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+            let content = String::from_utf8_lossy(line.content());
+            lines.push(format!(
+                "{}{}",
+                line.origin(),
+                content.trim_end_matches('\n')
+            ));
+        }
+
+        hunks.push(DiffHunk {
+            path: file_path.clone(),
+            header: String::from_utf8_lossy(hunk.header())
+                .trim_end()
+                .to_string(),
+            old_start: hunk.old_start(),
+            old_lines: hunk.old_lines(),
+            new_start: hunk.new_start(),
+            new_lines: hunk.new_lines(),
+            lines,
+        });
+    }
+
+    Ok(hunks)
+}
+
+/// Diff two trees with rename and copy detection
+fn tree_diff<'repo>(
+    git_repo: &'repo Git2Repository,
+    old_tree: Option<&Tree<'_>>,
+    new_tree: &Tree<'_>,
+) -> Result<Diff<'repo>, git2::Error> {
+    let mut diff = git_repo.diff_tree_to_tree(old_tree, Some(new_tree), None)?;
+
+    let mut find = DiffFindOptions::new();
+    find.renames(true)
+        .copies(true)
+        .rename_threshold(RENAME_SIMILARITY)
+        .copy_threshold(COPY_SIMILARITY);
+    diff.find_similar(Some(&mut find))?;
+
+    Ok(diff)
+}
+
+/// Build the change record for a single delta
+fn change_info(
+    delta: &DiffDelta<'_>,
+    additions: usize,
+    deletions: usize,
+) -> Option<FileChangeInfo> {
+    let change_type = match delta.status() {
+        Delta::Added | Delta::Untracked => FileChangeType::Added,
+        Delta::Deleted => FileChangeType::Deleted,
+        Delta::Renamed => FileChangeType::Renamed,
+        Delta::Copied => FileChangeType::Copied,
+        _ => FileChangeType::Modified,
+    };
+
+    let path = delta_path(delta)?;
+
+    let old_path = match change_type {
+        FileChangeType::Renamed | FileChangeType::Copied => delta
+            .old_file()
+            .path()
+            .and_then(|path| path.to_str())
+            .and_then(|path| FilePath::new(path).ok()),
+        _ => None,
+    };
+
+    Some(FileChangeInfo {
+        path,
+        change_type,
+        additions,
+        deletions,
+        old_path,
+        binary: delta.flags().is_binary(),
+    })
+}
+
+/// Path a delta applies to, preferring the new side
+fn delta_path(delta: &DiffDelta<'_>) -> Option<FilePath> {
+    delta
+        .new_file()
+        .path()
+        .or_else(|| delta.old_file().path())
+        .and_then(|path| path.to_str())
+        .and_then(|path| FilePath::new(path).ok())
+}
@@ -0,0 +1,179 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! Remote fetching for the repository command handler
+//!
+//! Remote-tracking refs are snapshotted before and after the fetch, so the
+//! resulting [`RemoteFetched`] event reports every ref the fetch created,
+//! moved or pruned regardless of which refspecs the remote is configured with.
+
+use super::{clone::map_transport_error, to_commit_hash, RepositoryCommandHandler};
+use crate::commands::FetchRemote;
+use crate::events::{GitDomainEvent, RefUpdate, RefUpdateType, RemoteFetched};
+use crate::GitDomainError;
+use chrono::Utc;
+use git2::{FetchOptions, FetchPrune, Oid, Repository as Git2Repository};
+use std::collections::BTreeMap;
+use tracing::{info, instrument, Span};
+
+/// Remote fetched when the command names none
+const DEFAULT_REMOTE: &str = "origin";
+
+impl RepositoryCommandHandler {
+    /// Fetch from one or all remotes of a known repository
+    ///
+    /// Emits a single [`RemoteFetched`] event, even when nothing changed.
+    #[instrument(skip(self, command), fields(remote = ?command.remote, all = command.all_remotes))]
+    pub async fn fetch_remote(
+        &self,
+        command: &FetchRemote,
+    ) -> Result<Vec<GitDomainEvent>, GitDomainError> {
+        let (mut repository, git_repo) = self.open_local(&command.repository_id)?;
+
+        // libgit2 blocks for the whole transfer, so the fetch runs on a
+        // blocking thread
+        let (remotes, ref_updates) = {
+            let command = command.clone();
+            let span = Span::current();
+            tokio::task::spawn_blocking(move || {
+                let _entered = span.enter();
+                fetch_remotes(&git_repo, &command)
+            })
+            .await
+            .map_err(|e| GitDomainError::GitOperationFailed(format!("Fetch task failed: {e}")))??
+        };
+
+        info!(
+            "Fetched {} ({} refs changed)",
+            remotes.join(", "),
+            ref_updates.len()
+        );
+
+        let event = GitDomainEvent::RemoteFetched(RemoteFetched {
+            repository_id: command.repository_id,
+            remotes,
+            pruned: command.prune,
+            ref_updates,
+            timestamp: Utc::now(),
+        });
+
+        repository.apply_event(&event)?;
+        self.store(repository)?;
+
+        Ok(vec![event])
+    }
+}
+
+/// Fetch the remotes `command` names, returning them with the refs the fetch
+/// changed
+fn fetch_remotes(
+    git_repo: &Git2Repository,
+    command: &FetchRemote,
+) -> Result<(Vec<String>, Vec<RefUpdate>), GitDomainError> {
+    let remotes: Vec<String> = if command.all_remotes {
+        git_repo
+            .remotes()
+            .map_err(|e| {
+                GitDomainError::GitOperationFailed(format!("Failed to list remotes: {e}"))
+            })?
+            .iter()
+            .flatten()
+            .map(ToString::to_string)
+            .collect()
+    } else {
+        vec![command
+            .remote
+            .clone()
+            .unwrap_or_else(|| DEFAULT_REMOTE.to_string())]
+    };
+
+    let mut ref_updates = Vec::new();
+    for name in &remotes {
+        let mut remote = git_repo
+            .find_remote(name)
+            .map_err(|_| GitDomainError::RemoteNotFound(name.clone()))?;
+        let url = remote.url().unwrap_or_default().to_string();
+
+        let before = tracking_refs(git_repo, name)?;
+
+        // Explicit either way, so `fetch.prune` in the config cannot override the command
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.prune(if command.prune {
+            FetchPrune::On
+        } else {
+            FetchPrune::Off
+        });
+        remote
+            .fetch::<&str>(&[], Some(&mut fetch_options), None)
+            .map_err(|e| {
+                map_transport_error(&e, &url).unwrap_or_else(|| {
+                    GitDomainError::GitOperationFailed(format!("Failed to fetch {name}: {e}"))
+                })
+            })?;
+
+        let after = tracking_refs(git_repo, name)?;
+        ref_updates.extend(diff_refs(&before, &after)?);
+    }
+
+    Ok((remotes, ref_updates))
+}
+
+/// Direct targets of the remote-tracking refs of `remote`, by ref name
+///
+/// Symbolic refs such as `refs/remotes/origin/HEAD` are skipped.
+fn tracking_refs(
+    git_repo: &Git2Repository,
+    remote: &str,
+) -> Result<BTreeMap<String, Oid>, GitDomainError> {
+    let list_error = |e: git2::Error| {
+        GitDomainError::GitOperationFailed(format!("Failed to list refs of {remote}: {e}"))
+    };
+
+    let mut refs = BTreeMap::new();
+    for reference in git_repo
+        .references_glob(&format!("refs/remotes/{remote}/*"))
+        .map_err(list_error)?
+    {
+        let reference = reference.map_err(list_error)?;
+        if let (Some(name), Some(target)) = (reference.name(), reference.target()) {
+            refs.insert(name.to_string(), target);
+        }
+    }
+
+    Ok(refs)
+}
+
+/// Compare two ref snapshots, in ref name order
+fn diff_refs(
+    before: &BTreeMap<String, Oid>,
+    after: &BTreeMap<String, Oid>,
+) -> Result<Vec<RefUpdate>, GitDomainError> {
+    let mut updates = Vec::new();
+    for (name, new) in after {
+        let update_type = match before.get(name) {
+            None => RefUpdateType::Created,
+            Some(old) if old != new => RefUpdateType::Updated,
+            Some(_) => continue,
+        };
+        updates.push(RefUpdate {
+            ref_name: name.clone(),
+            update_type,
+            old_target: before.get(name).copied().map(to_commit_hash).transpose()?,
+            new_target: Some(to_commit_hash(*new)?),
+        });
+    }
+
+    for (name, old) in before {
+        if !after.contains_key(name) {
+            updates.push(RefUpdate {
+                ref_name: name.clone(),
+                update_type: RefUpdateType::Deleted,
+                old_target: Some(to_commit_hash(*old)?),
+                new_target: None,
+            });
+        }
+    }
+
+    updates.sort_by(|a, b| a.ref_name.cmp(&b.ref_name));
+
+    Ok(updates)
+}
@@ -0,0 +1,292 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! File history analysis for the repository command handler
+//!
+//! Walks the history of a single path back from the end commit, following the
+//! file through renames when asked to, and takes a blame snapshot of the file
+//! as it exists at the end commit.
+
+use super::{commit, diff, git_error, to_commit_hash, RepositoryCommandHandler};
+use crate::commands::AnalyzeFileHistory;
+use crate::events::{
+    BlameHunk, FileChangeInfo, FileChangeType, FileHistoryAnalyzed, FileRevision, GitDomainEvent,
+};
+use crate::identity::{author_info, IdentityMapper};
+use crate::value_objects::{CommitHash, FilePath};
+use crate::GitDomainError;
+use chrono::{DateTime, Utc};
+use git2::{
+    BlameOptions, Commit, ErrorCode, ObjectType, Oid, Repository as Git2Repository, Sort, Tree,
+};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::{info, instrument};
+
+impl RepositoryCommandHandler {
+    /// Analyze the history of a single file of a known repository
+    ///
+    /// Emits a [`FileHistoryAnalyzed`] event with every revision that changed
+    /// the file, newest first, and a blame snapshot at the end commit (`HEAD`
+    /// by default), followed by one
+    /// [`FileAnalyzed`](crate::events::FileAnalyzed) event per revision in
+    /// which the file exists. `file_path` names the file at the end commit;
+    /// the start commit, when given, is the oldest commit considered. Merge
+    /// commits are skipped, as their changes belong to the merged commits;
+    /// each branch of a merge is followed under the name the file has there.
+    #[instrument(skip(self, command), fields(path = %command.file_path))]
+    pub async fn analyze_file_history(
+        &self,
+        command: &AnalyzeFileHistory,
+    ) -> Result<Vec<GitDomainEvent>, GitDomainError> {
+        let (_, git_repo) = self.open_local(&command.repository_id)?;
+
+        let end = match &command.end_commit {
+            Some(hash) => resolve_commit(&git_repo, hash)?,
+            None => git_repo
+                .head()
+                .and_then(|head| head.peel_to_commit())
+                .map_err(|e| git_error("resolve HEAD", &e))?,
+        };
+        let start = command
+            .start_commit
+            .as_ref()
+            .map(|hash| resolve_commit(&git_repo, hash))
+            .transpose()?;
+
+        let mut revwalk = git_repo
+            .revwalk()
+            .map_err(|e| git_error("create revwalk", &e))?;
+        revwalk
+            .set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+            .and_then(|()| revwalk.push(end.id()))
+            .map_err(|e| git_error("walk history", &e))?;
+        if let Some(start) = &start {
+            let is_ancestor = start.id() == end.id()
+                || git_repo
+                    .graph_descendant_of(end.id(), start.id())
+                    .map_err(|e| git_error("walk history", &e))?;
+            if !is_ancestor {
+                return Err(GitDomainError::ValidationError(format!(
+                    "Start commit {} is not an ancestor of end commit {}",
+                    start.id(),
+                    end.id()
+                )));
+            }
+            for parent in start.parent_ids() {
+                revwalk
+                    .hide(parent)
+                    .map_err(|e| git_error("walk history", &e))?;
+            }
+        }
+
+        let identities = self.identities(&git_repo);
+        // The name of the file in each commit still to be walked, handed from
+        // child to parent so every branch follows its own renames
+        let mut paths = HashMap::from([(end.id(), command.file_path.clone())]);
+        let mut revisions = Vec::new();
+        let mut file_events = Vec::new();
+        for oid in revwalk {
+            let commit = oid
+                .and_then(|oid| git_repo.find_commit(oid))
+                .map_err(|e| git_error("walk history", &e))?;
+            // The topological walk reaches every commit after one of its children
+            let Some(path) = paths.remove(&commit.id()) else {
+                continue;
+            };
+
+            if commit.parent_count() > 1 {
+                for parent in commit.parents() {
+                    let parent_path = if command.follow_renames {
+                        merge_parent_path(&git_repo, &commit, &parent, &path)?
+                    } else {
+                        path.clone()
+                    };
+                    paths.entry(parent.id()).or_insert(parent_path);
+                }
+                continue;
+            }
+
+            let mut parent_path = path.clone();
+            if let Some(change) = path_change(&git_repo, &commit, &path)? {
+                let commit_hash = to_commit_hash(commit.id())?;
+                if let Some(event) = commit::analyze_file(
+                    &git_repo,
+                    command.repository_id,
+                    &commit,
+                    &commit_hash,
+                    &change,
+                    None,
+                )? {
+                    file_events.push(GitDomainEvent::FileAnalyzed(event));
+                }
+
+                // Older revisions know the file under its previous name
+                if command.follow_renames && change.change_type == FileChangeType::Renamed {
+                    if let Some(old_path) = &change.old_path {
+                        parent_path = old_path.clone();
+                    }
+                }
+
+                let (author, raw_author) = identities.resolve(&commit.author());
+                revisions.push(FileRevision {
+                    commit_hash,
+                    author,
+                    raw_author,
+                    change,
+                    commit_timestamp: DateTime::from_timestamp(commit.time().seconds(), 0)
+                        .unwrap_or_else(Utc::now),
+                });
+            }
+            for parent in commit.parent_ids() {
+                paths.entry(parent).or_insert_with(|| parent_path.clone());
+            }
+        }
+
+        let blame = blame_snapshot(
+            &git_repo,
+            &end,
+            &command.file_path,
+            start.as_ref().map(Commit::id),
+            &identities,
+        )?;
+
+        info!(
+            "Analyzed history of {}: {} revisions, {} blame hunks",
+            command.file_path,
+            revisions.len(),
+            blame.len()
+        );
+
+        let mut events = Vec::with_capacity(file_events.len() + 1);
+        events.push(GitDomainEvent::FileHistoryAnalyzed(FileHistoryAnalyzed {
+            repository_id: command.repository_id,
+            file_path: command.file_path.clone(),
+            start_commit: start.map(|start| to_commit_hash(start.id())).transpose()?,
+            end_commit: to_commit_hash(end.id())?,
+            revisions,
+            blame,
+            timestamp: Utc::now(),
+        }));
+        events.extend(file_events);
+
+        Ok(events)
+    }
+}
+
+/// The change a commit made to `path` relative to its first parent, if any
+///
+/// The full diff, with rename detection, only runs when the blob at `path`
+/// differs between the commit and its parent.
+fn path_change(
+    git_repo: &Git2Repository,
+    commit: &Commit<'_>,
+    path: &FilePath,
+) -> Result<Option<FileChangeInfo>, GitDomainError> {
+    let read_error = |e: git2::Error| git_error(&format!("read commit {}", commit.id()), &e);
+    let tree = commit.tree().map_err(read_error)?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree().map_err(read_error)?),
+        Err(e) if e.code() == ErrorCode::NotFound => None,
+        Err(e) => return Err(read_error(e)),
+    };
+
+    let current = blob_id(&tree, path)?;
+    let previous = match &parent_tree {
+        Some(parent_tree) => blob_id(parent_tree, path)?,
+        None => None,
+    };
+    if current == previous {
+        return Ok(None);
+    }
+
+    let changes = diff::file_changes(git_repo, parent_tree.as_ref(), &tree)
+        .map_err(|e| git_error(&format!("diff commit {}", commit.id()), &e))?;
+
+    Ok(changes.into_iter().find(|change| change.path == *path))
+}
+
+/// The name under which a parent of a merge commit knows the merge's `path`
+///
+/// A parent without a file at `path` may hold it under an older name, when
+/// the file was renamed on another branch of the merge.
+fn merge_parent_path(
+    git_repo: &Git2Repository,
+    commit: &Commit<'_>,
+    parent: &Commit<'_>,
+    path: &FilePath,
+) -> Result<FilePath, GitDomainError> {
+    let read_error = |e: git2::Error| git_error(&format!("read commit {}", commit.id()), &e);
+    let parent_tree = parent.tree().map_err(read_error)?;
+    if blob_id(&parent_tree, path)?.is_some() {
+        return Ok(path.clone());
+    }
+
+    let tree = commit.tree().map_err(read_error)?;
+    let changes = diff::file_changes(git_repo, Some(&parent_tree), &tree)
+        .map_err(|e| git_error(&format!("diff commit {}", commit.id()), &e))?;
+
+    Ok(changes
+        .into_iter()
+        .find(|change| change.path == *path && change.change_type == FileChangeType::Renamed)
+        .and_then(|change| change.old_path)
+        .unwrap_or_else(|| path.clone()))
+}
+
+/// ID of the blob at `path`, or `None` if the tree has no file there
+fn blob_id(tree: &Tree<'_>, path: &FilePath) -> Result<Option<Oid>, GitDomainError> {
+    match tree.get_path(Path::new(path.as_str())) {
+        Ok(entry) if entry.kind() == Some(ObjectType::Blob) => Ok(Some(entry.id())),
+        Ok(_) => Ok(None),
+        Err(e) if e.code() == ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(git_error(&format!("read {path}"), &e)),
+    }
+}
+
+/// Blame `path` as of `end`, attributing lines older than `oldest` to it
+///
+/// Returns no hunks if the file does not exist at `end`. Hunk authors are
+/// canonical identities.
+fn blame_snapshot(
+    git_repo: &Git2Repository,
+    end: &Commit<'_>,
+    path: &FilePath,
+    oldest: Option<Oid>,
+    identities: &IdentityMapper<'_>,
+) -> Result<Vec<BlameHunk>, GitDomainError> {
+    let tree = end.tree().map_err(|e| git_error("read tree", &e))?;
+    if blob_id(&tree, path)?.is_none() {
+        return Ok(Vec::new());
+    }
+
+    let mut options = BlameOptions::new();
+    options.newest_commit(end.id()).use_mailmap(true);
+    if let Some(oldest) = oldest {
+        options.oldest_commit(oldest);
+    }
+    let blame = git_repo
+        .blame_file(Path::new(path.as_str()), Some(&mut options))
+        .map_err(|e| git_error(&format!("blame {path}"), &e))?;
+
+    blame
+        .iter()
+        .map(|hunk| {
+            Ok(BlameHunk {
+                commit_hash: to_commit_hash(hunk.final_commit_id())?,
+                author: identities.canonical(author_info(&hunk.final_signature())),
+                start_line: hunk.final_start_line(),
+                line_count: hunk.lines_in_hunk(),
+            })
+        })
+        .collect()
+}
+
+/// Resolve a possibly abbreviated commit hash
+fn resolve_commit<'repo>(
+    git_repo: &'repo Git2Repository,
+    hash: &CommitHash,
+) -> Result<Commit<'repo>, GitDomainError> {
+    git_repo
+        .revparse_single(hash.as_str())
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|e| GitDomainError::InvalidCommitHash(format!("{hash}: {e}")))
+}
@@ -5,28 +5,47 @@
 //! This module contains the implementation of command handlers
 //! that process commands and generate events.
 
+mod analysis;
+mod clone;
 pub mod commands;
+mod commit;
+mod compare;
 mod cqrs_adapter;
-
+mod diff;
+mod fetch;
+mod history;
+mod refs;
+mod release;
+mod search;
+mod signature;
+
+pub use analysis::{AnalysisOptions, AnalysisStream};
+pub use compare::BranchComparison;
 pub use cqrs_adapter::*;
+pub use diff::DiffHunk;
+pub use search::{PickaxeHit, SearchMatch, SearchResults};
 
 use crate::aggregate::{Repository, RepositoryId};
-use crate::events::{
-    BranchCreated, CommitAnalyzed, FileChangeInfo, FileChangeType, GitDomainEvent,
-    RepositoryAnalyzed,
-};
-use crate::value_objects::{AuthorInfo, BranchName, CommitHash, FilePath};
+use crate::events::GitDomainEvent;
+use crate::identity::{IdentityMapper, IdentityResolver};
+use crate::signature::SignatureVerifier;
+use crate::value_objects::CommitHash;
 use crate::GitDomainError;
-use chrono::{DateTime, Utc};
-use git2::{Repository as Git2Repository, Sort};
+use git2::{Oid, Repository as Git2Repository};
 use std::collections::HashMap;
-use std::path::Path;
-use tracing::{info, instrument, warn};
+use std::sync::{Arc, Mutex};
+use tracing::instrument;
 
 /// Repository command handler for Git operations
 pub struct RepositoryCommandHandler {
     /// In-memory repository for demo purposes
-    repositories: std::sync::Mutex<HashMap<RepositoryId, Repository>>,
+    repositories: Arc<Mutex<HashMap<RepositoryId, Repository>>>,
+
+    /// Resolves author identities after the repository's mailmap
+    identity_resolver: Option<Arc<dyn IdentityResolver>>,
+
+    /// Verifies commit and tag signatures
+    signature_verifier: Option<Arc<SignatureVerifier>>,
 }
 
 impl RepositoryCommandHandler {
@@ -34,10 +53,37 @@ impl RepositoryCommandHandler {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            repositories: std::sync::Mutex::new(HashMap::new()),
+            repositories: Arc::new(Mutex::new(HashMap::new())),
+            identity_resolver: None,
+            signature_verifier: None,
         }
     }
 
+    /// Resolve the authors of analyzed commits with the given resolver
+    ///
+    /// The resolver sees identities after the repository's `.mailmap` has
+    /// been applied.
+    #[must_use]
+    pub fn with_identity_resolver(mut self, resolver: impl IdentityResolver + 'static) -> Self {
+        self.identity_resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Verify commit and tag signatures with the given verifier
+    ///
+    /// Without a verifier, signatures are still found but reported as
+    /// [`SignatureStatus::Unverified`](crate::signature::SignatureStatus::Unverified).
+    #[must_use]
+    pub fn with_signature_verifier(mut self, verifier: SignatureVerifier) -> Self {
+        self.signature_verifier = Some(Arc::new(verifier));
+        self
+    }
+
+    /// Identity mapper for a repository opened by this handler
+    fn identities<'a>(&'a self, git_repo: &Git2Repository) -> IdentityMapper<'a> {
+        IdentityMapper::new(git_repo, self.identity_resolver.as_deref())
+    }
+
     /// Analyze the current working directory as a Git repository
     pub async fn analyze_current_repository(
         &self,
@@ -50,208 +96,14 @@ impl RepositoryCommandHandler {
             .await
     }
 
-    /// Analyze a Git repository at the given path
+    /// Analyze the full history of a Git repository at the given path
     #[instrument(skip(self), fields(path = %path.as_ref()))]
     pub async fn analyze_repository_at_path(
         &self,
         path: impl AsRef<str>,
     ) -> Result<(RepositoryId, Vec<GitDomainEvent>), GitDomainError> {
-        let path = path.as_ref();
-        info!("Analyzing Git repository at: {}", path);
-
-        // Open repository with git2
-        let git_repo = Git2Repository::open(path).map_err(|e| {
-            GitDomainError::GitOperationFailed(format!("Failed to open repository: {e}"))
-        })?;
-
-        let repo_id = RepositoryId::new();
-        let mut events = Vec::new();
-
-        // Get repository metadata
-        let repo_name = Path::new(path)
-            .file_name()
-            .and_then(|name| name.to_str())
-            .unwrap_or("unknown")
-            .to_string();
-
-        // Create repository analyzed event
-        let analyzed_event = RepositoryAnalyzed {
-            repository_id: repo_id,
-            path: path.to_string(),
-            name: repo_name.clone(),
-            branch_count: 0, // Will be updated below
-            commit_count: 0, // Will be updated below
-            timestamp: Utc::now(),
-        };
-
-        events.push(GitDomainEvent::RepositoryAnalyzed(analyzed_event));
-
-        // Analyze branches
-        let branches = git_repo.branches(None).map_err(|e| {
-            GitDomainError::GitOperationFailed(format!("Failed to get branches: {e}"))
-        })?;
-
-        let mut branch_count = 0;
-        for branch_result in branches {
-            if let Ok((branch, _)) = branch_result {
-                if let Some(name) = branch.name().ok().flatten() {
-                    if let Ok(branch_name) = BranchName::new(name) {
-                        if let Some(target) = branch.get().target() {
-                            if let Ok(commit_hash) = CommitHash::new(target.to_string()) {
-                                let branch_event = BranchCreated {
-                                    repository_id: repo_id,
-                                    branch_name,
-                                    commit_hash,
-                                    source_branch: None,
-                                    timestamp: Utc::now(),
-                                };
-                                events.push(GitDomainEvent::BranchCreated(branch_event));
-                                branch_count += 1;
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        // Analyze commits
-        let mut revwalk = git_repo.revwalk().map_err(|e| {
-            GitDomainError::GitOperationFailed(format!("Failed to create revwalk: {e}"))
-        })?;
-
-        revwalk
-            .set_sorting(Sort::TIME)
-            .map_err(|e| GitDomainError::GitOperationFailed(format!("Failed to set sort: {e}")))?;
-
-        // Start from HEAD
-        if let Ok(head) = git_repo.head() {
-            if let Some(target) = head.target() {
-                revwalk.push(target).map_err(|e| {
-                    GitDomainError::GitOperationFailed(format!("Failed to push HEAD: {e}"))
-                })?;
-            }
-        } else {
-            warn!("Repository has no HEAD - might be empty");
-        }
-
-        let mut commit_count = 0;
-        for commit_oid in revwalk.take(100) {
-            // Limit to first 100 commits for demo
-            if let Ok(oid) = commit_oid {
-                if let Ok(commit) = git_repo.find_commit(oid) {
-                    let commit_hash = CommitHash::new(oid.to_string()).map_err(|e| {
-                        GitDomainError::GitOperationFailed(format!("Invalid commit hash: {e}"))
-                    })?;
-
-                    let author = commit.author();
-                    let author_info = AuthorInfo::new(
-                        author.name().unwrap_or("Unknown").to_string(),
-                        author.email().unwrap_or("unknown@example.com").to_string(),
-                    );
-
-                    let parents: Vec<CommitHash> = commit
-                        .parent_ids()
-                        .filter_map(|oid| CommitHash::new(oid.to_string()).ok())
-                        .collect();
-
-                    let timestamp = DateTime::from_timestamp(commit.time().seconds(), 0)
-                        .unwrap_or_else(Utc::now);
-
-                    // Get files changed by comparing with parent
-                    let mut files_changed = vec![];
-
-                    if let Ok(parent) = commit.parent(0) {
-                        // Get diff between parent and current commit
-                        if let Ok(parent_tree) = parent.tree() {
-                            if let Ok(current_tree) = commit.tree() {
-                                if let Ok(diff) = git_repo.diff_tree_to_tree(
-                                    Some(&parent_tree),
-                                    Some(&current_tree),
-                                    None,
-                                ) {
-                                    // Collect file changes
-                                    let _ = diff.foreach(
-                                        &mut |delta, _| {
-                                            if let Some(new_file) = delta.new_file().path() {
-                                                if let Some(path_str) = new_file.to_str() {
-                                                    if let Ok(file_path) = FilePath::new(path_str) {
-                                                        files_changed.push(FileChangeInfo {
-                                                            path: file_path,
-                                                            additions: 0, // Would need to parse diff for actual counts
-                                                            deletions: 0,
-                                                            change_type: match delta.status() {
-                                                                git2::Delta::Added => {
-                                                                    FileChangeType::Added
-                                                                }
-                                                                git2::Delta::Deleted => {
-                                                                    FileChangeType::Deleted
-                                                                }
-                                                                git2::Delta::Modified => {
-                                                                    FileChangeType::Modified
-                                                                }
-                                                                git2::Delta::Renamed => {
-                                                                    FileChangeType::Renamed
-                                                                }
-                                                                _ => FileChangeType::Modified,
-                                                            },
-                                                        });
-                                                    }
-                                                }
-                                            }
-                                            true
-                                        },
-                                        None,
-                                        None,
-                                        None,
-                                    );
-                                }
-                            }
-                        }
-                    }
-
-                    let commit_event = CommitAnalyzed {
-                        repository_id: repo_id,
-                        commit_hash,
-                        parents,
-                        author: author_info,
-                        message: commit.message().unwrap_or("No message").to_string(),
-                        files_changed,
-                        commit_timestamp: timestamp,
-                        timestamp: Utc::now(),
-                    };
-
-                    events.push(GitDomainEvent::CommitAnalyzed(commit_event));
-                    commit_count += 1;
-                }
-            }
-        }
-
-        info!(
-            "Analyzed repository: {} branches, {} commits",
-            branch_count, commit_count
-        );
-
-        // Create and store repository aggregate
-        let mut repository = Repository::new(repo_name);
-        repository.id = repo_id;
-        repository.local_path = Some(path.to_string());
-
-        // Apply events to aggregate
-        for event in &events {
-            repository.apply_event(event).map_err(|e| {
-                GitDomainError::GitOperationFailed(format!("Failed to apply event: {e}"))
-            })?;
-        }
-
-        // Store repository
-        {
-            let mut repos = self.repositories.lock().map_err(|_| {
-                GitDomainError::GitOperationFailed("Failed to acquire repository lock".to_string())
-            })?;
-            repos.insert(repo_id, repository);
-        }
-
-        Ok((repo_id, events))
+        self.analyze_repository_with_options(path, AnalysisOptions::default())
+            .await
     }
 
     // Note: Extract commit graph functionality has been removed
@@ -263,6 +115,34 @@ impl RepositoryCommandHandler {
         repos.get(id).cloned()
     }
 
+    /// Look up a known repository and open its working copy
+    fn open_local(
+        &self,
+        id: &RepositoryId,
+    ) -> Result<(Repository, Git2Repository), GitDomainError> {
+        let repository = self
+            .get_repository(id)
+            .ok_or_else(|| GitDomainError::RepositoryNotFound(id.to_string()))?;
+        let local_path = repository.local_path.as_ref().ok_or_else(|| {
+            GitDomainError::GitOperationFailed(format!("Repository {id} has no local path"))
+        })?;
+
+        let git_repo = Git2Repository::open(local_path).map_err(|e| {
+            GitDomainError::GitOperationFailed(format!("Failed to open repository: {e}"))
+        })?;
+
+        Ok((repository, git_repo))
+    }
+
+    /// Replace the stored aggregate with an updated one
+    fn store(&self, repository: Repository) -> Result<(), GitDomainError> {
+        let mut repos = self.repositories.lock().map_err(|_| {
+            GitDomainError::GitOperationFailed("Failed to acquire repository lock".to_string())
+        })?;
+        repos.insert(repository.id, repository);
+        Ok(())
+    }
+
     /// List all repositories
     pub fn list_repositories(&self) -> Vec<Repository> {
         let repos = match self.repositories.lock() {
@@ -279,6 +159,17 @@ impl Default for RepositoryCommandHandler {
     }
 }
 
+/// Convert a git2 object ID into a domain commit hash
+fn to_commit_hash(oid: Oid) -> Result<CommitHash, GitDomainError> {
+    CommitHash::new(oid.to_string())
+        .map_err(|e| GitDomainError::GitOperationFailed(format!("Invalid commit hash: {e}")))
+}
+
+/// Wrap a git2 error with the action that failed
+fn git_error(action: &str, error: &git2::Error) -> GitDomainError {
+    GitDomainError::GitOperationFailed(format!("Failed to {action}: {error}"))
+}
+
 // Note: Extract dependency graph functionality has been removed
 // This was dependent on cim_domain_graph which is no longer available
 
@@ -0,0 +1,214 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! Branch and tag operations for the repository command handler
+//!
+//! Each operation is first validated against the [`Repository`] aggregate and
+//! only then applied to the working copy through git2. The updated aggregate
+//! is stored once the on-disk change has succeeded.
+//!
+//! [`Repository`]: crate::aggregate::Repository
+
+use super::{to_commit_hash, RepositoryCommandHandler};
+use crate::commands::{CreateBranch, CreateTag, DeleteBranch};
+use crate::events::GitDomainEvent;
+use crate::value_objects::BranchName;
+use crate::GitDomainError;
+use git2::build::CheckoutBuilder;
+use git2::{BranchType, ErrorCode};
+use tracing::{info, instrument};
+
+impl RepositoryCommandHandler {
+    /// Create a branch at a start point, optionally checking it out
+    #[instrument(skip(self, command), fields(branch = %command.branch_name))]
+    pub async fn create_branch(
+        &self,
+        command: &CreateBranch,
+    ) -> Result<Vec<GitDomainEvent>, GitDomainError> {
+        let (mut repository, git_repo) = self.open_local(&command.repository_id)?;
+        let name = command.branch_name.as_str();
+
+        let start = git_repo
+            .revparse_single(&command.start_point)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|e| {
+                GitDomainError::GitOperationFailed(format!(
+                    "Failed to resolve start point {}: {e}",
+                    command.start_point
+                ))
+            })?;
+        let commit_hash = to_commit_hash(start.id())?;
+        let source_branch = git_repo
+            .find_branch(&command.start_point, BranchType::Local)
+            .ok()
+            .and_then(|_| BranchName::new(&command.start_point).ok());
+
+        if command.checkout && git_repo.is_bare() {
+            return Err(GitDomainError::GitOperationFailed(
+                "Cannot check out a branch in a bare repository".to_string(),
+            ));
+        }
+
+        // The working copy may hold branches the last analysis did not see
+        if git_repo.find_branch(name, BranchType::Local).is_ok() {
+            return Err(GitDomainError::BranchAlreadyExists(name.to_string()));
+        }
+
+        let events = repository.create_branch(
+            command.branch_name.clone(),
+            commit_hash,
+            source_branch,
+            command.checkout,
+        )?;
+
+        let mut branch = git_repo.branch(name, &start, false).map_err(|e| {
+            if e.code() == ErrorCode::Exists {
+                GitDomainError::BranchAlreadyExists(name.to_string())
+            } else {
+                GitDomainError::GitOperationFailed(format!("Failed to create branch {name}: {e}"))
+            }
+        })?;
+
+        if command.checkout {
+            let mut checkout = CheckoutBuilder::new();
+            checkout.safe();
+            let checked_out = git_repo
+                .checkout_tree(start.as_object(), Some(&mut checkout))
+                .and_then(|()| git_repo.set_head(&format!("refs/heads/{name}")));
+
+            if let Err(e) = checked_out {
+                // Leave the repository as it was rather than half-applied
+                let _ = branch.delete();
+                return Err(GitDomainError::GitOperationFailed(format!(
+                    "Failed to check out branch {name}: {e}"
+                )));
+            }
+        }
+
+        self.store(repository)?;
+        info!("Created branch {} at {}", name, start.id());
+
+        Ok(events)
+    }
+
+    /// Delete a local branch
+    ///
+    /// Branches that are not merged into HEAD are only deleted with `force`.
+    #[instrument(skip(self, command), fields(branch = %command.branch_name))]
+    pub async fn delete_branch(
+        &self,
+        command: &DeleteBranch,
+    ) -> Result<Vec<GitDomainEvent>, GitDomainError> {
+        let (mut repository, git_repo) = self.open_local(&command.repository_id)?;
+        let name = command.branch_name.as_str();
+
+        let mut branch = git_repo
+            .find_branch(name, BranchType::Local)
+            .map_err(|_| GitDomainError::BranchNotFound(name.to_string()))?;
+
+        // HEAD may have moved since the aggregate last saw it
+        if branch.is_head() {
+            return Err(GitDomainError::CannotDeleteCurrentBranch(name.to_string()));
+        }
+
+        let tip = branch
+            .get()
+            .target()
+            .ok_or_else(|| GitDomainError::BranchNotFound(name.to_string()))?;
+        let merged = git_repo
+            .head()
+            .ok()
+            .and_then(|head| head.target())
+            .is_some_and(|head| {
+                head == tip || git_repo.graph_descendant_of(head, tip).unwrap_or(false)
+            });
+
+        let events = repository.delete_branch(
+            command.branch_name.clone(),
+            to_commit_hash(tip)?,
+            merged,
+            command.force,
+        )?;
+
+        branch.delete().map_err(|e| {
+            GitDomainError::GitOperationFailed(format!("Failed to delete branch {name}: {e}"))
+        })?;
+
+        self.store(repository)?;
+        info!("Deleted branch {} (was {})", name, tip);
+
+        Ok(events)
+    }
+
+    /// Create a lightweight or annotated tag
+    ///
+    /// Tags HEAD unless a commit is given. Annotated tags require a message
+    /// and record the repository's configured identity as tagger.
+    #[instrument(skip(self, command), fields(tag = %command.tag_name))]
+    pub async fn create_tag(
+        &self,
+        command: &CreateTag,
+    ) -> Result<Vec<GitDomainEvent>, GitDomainError> {
+        let (mut repository, git_repo) = self.open_local(&command.repository_id)?;
+        let name = command.tag_name.as_str();
+
+        let target = match &command.commit_hash {
+            Some(hash) => git_repo
+                .revparse_single(hash.as_str())
+                .and_then(|object| object.peel_to_commit())
+                .map_err(|e| GitDomainError::InvalidCommitHash(format!("{hash}: {e}")))?,
+            None => git_repo
+                .head()
+                .and_then(|head| head.peel_to_commit())
+                .map_err(|e| {
+                    GitDomainError::GitOperationFailed(format!("Failed to resolve HEAD: {e}"))
+                })?,
+        };
+
+        let annotation = if command.annotated {
+            let message = command.message.clone().ok_or_else(|| {
+                GitDomainError::ValidationError("Annotated tags require a message".to_string())
+            })?;
+            let tagger = git_repo.signature().map_err(|e| {
+                GitDomainError::GitOperationFailed(format!("No tagger identity configured: {e}"))
+            })?;
+            Some((message, tagger))
+        } else {
+            None
+        };
+
+        if git_repo
+            .find_reference(&format!("refs/tags/{name}"))
+            .is_ok()
+        {
+            return Err(GitDomainError::TagAlreadyExists(name.to_string()));
+        }
+
+        let events = repository.create_tag(
+            command.tag_name.clone(),
+            to_commit_hash(target.id())?,
+            annotation.as_ref().map(|(message, _)| message.clone()),
+            annotation
+                .as_ref()
+                .map(|(_, tagger)| self.identities(&git_repo).resolve(tagger).0),
+        )?;
+
+        let created = match &annotation {
+            Some((message, tagger)) => {
+                git_repo.tag(name, target.as_object(), tagger, message, false)
+            }
+            None => git_repo.tag_lightweight(name, target.as_object(), false),
+        };
+        created.map_err(|e| {
+            if e.code() == ErrorCode::Exists {
+                GitDomainError::TagAlreadyExists(name.to_string())
+            } else {
+                GitDomainError::GitOperationFailed(format!("Failed to create tag {name}: {e}"))
+            }
+        })?;
+
+        self.store(repository)?;
+        info!("Created tag {} at {}", name, target.id());
+
+        Ok(events)
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! Release notes for the repository command handler
+//!
+//! Collects the commits of a release, from the previous release tag to the
+//! release tag or `HEAD`, and classifies them into release notes.
+
+use super::{git_error, to_commit_hash, RepositoryCommandHandler};
+use crate::commands::GenerateReleaseNotes;
+use crate::events::{GitDomainEvent, ReleaseNotesGenerated};
+use crate::release::{ReleaseNoteEntry, ReleaseNotes};
+use crate::value_objects::{CommitMessage, SemanticVersion, TagName};
+use crate::GitDomainError;
+use chrono::Utc;
+use git2::{Commit, Oid, Repository as Git2Repository, Sort};
+use tracing::{debug, info, instrument};
+
+impl RepositoryCommandHandler {
+    /// Generate release notes for a known repository
+    ///
+    /// Without a `from_tag`, the previous release is the highest semantic
+    /// version tag, other than a pre-release, on an earlier commit of the
+    /// release's history; without one the notes cover the whole history.
+    /// Merge commits are left out. The notes are recorded as a
+    /// [`ReleaseNotesGenerated`] event.
+    #[instrument(skip(self, command), fields(repository_id = %command.repository_id))]
+    pub async fn generate_release_notes(
+        &self,
+        command: &GenerateReleaseNotes,
+    ) -> Result<(ReleaseNotes, Vec<GitDomainEvent>), GitDomainError> {
+        let (_, git_repo) = self.open_local(&command.repository_id)?;
+
+        let to = match &command.to_tag {
+            Some(tag) => resolve_tag(&git_repo, tag)?,
+            None => git_repo
+                .head()
+                .and_then(|head| head.peel_to_commit())
+                .map_err(|e| git_error("resolve HEAD", &e))?,
+        };
+        let from_tag = match &command.from_tag {
+            Some(tag) => Some(tag.clone()),
+            None => previous_release(&git_repo, to.id())?,
+        };
+        let from = from_tag
+            .as_ref()
+            .map(|tag| resolve_tag(&git_repo, tag))
+            .transpose()?;
+
+        let mut revwalk = git_repo
+            .revwalk()
+            .map_err(|e| git_error("create revwalk", &e))?;
+        revwalk
+            .set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+            .and_then(|()| revwalk.push(to.id()))
+            .and_then(|()| match &from {
+                Some(from) => revwalk.hide(from.id()),
+                None => Ok(()),
+            })
+            .map_err(|e| git_error("walk history", &e))?;
+
+        let identities = self.identities(&git_repo);
+        let mut entries = Vec::new();
+        for oid in revwalk {
+            let commit = oid
+                .and_then(|oid| git_repo.find_commit(oid))
+                .map_err(|e| git_error("walk history", &e))?;
+            if commit.parent_count() > 1 {
+                continue;
+            }
+            // Commits without a message have nothing to say in the notes
+            if let Ok(message) = CommitMessage::new(commit.message().unwrap_or_default()) {
+                let (author, _) = identities.resolve(&commit.author());
+                entries.push(ReleaseNoteEntry::new(
+                    to_commit_hash(commit.id())?,
+                    author,
+                    &message,
+                ));
+            }
+        }
+
+        let notes = ReleaseNotes::new(
+            from_tag,
+            command.to_tag.clone(),
+            from.as_ref()
+                .map(|commit| to_commit_hash(commit.id()))
+                .transpose()?,
+            to_commit_hash(to.id())?,
+            entries,
+        );
+
+        info!(
+            "Generated release notes from {} to {}: {:?} bump to {}",
+            notes
+                .from_tag
+                .as_ref()
+                .map_or("the first commit", TagName::as_str),
+            notes.to_tag.as_ref().map_or("HEAD", TagName::as_str),
+            notes.bump,
+            notes
+                .next_version
+                .as_ref()
+                .map_or_else(|| "no release".to_string(), ToString::to_string)
+        );
+
+        let event = GitDomainEvent::ReleaseNotesGenerated(ReleaseNotesGenerated {
+            repository_id: command.repository_id,
+            notes: notes.clone(),
+            markdown: notes.to_markdown(),
+            timestamp: Utc::now(),
+        });
+
+        Ok((notes, vec![event]))
+    }
+}
+
+/// Resolve a tag to the commit it points to
+fn resolve_tag<'repo>(
+    git_repo: &'repo Git2Repository,
+    tag: &TagName,
+) -> Result<Commit<'repo>, GitDomainError> {
+    git_repo
+        .revparse_single(&format!("refs/tags/{tag}"))
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|_| GitDomainError::TagNotFound(tag.to_string()))
+}
+
+/// Highest release tag on a commit that `to` descends from
+fn previous_release(git_repo: &Git2Repository, to: Oid) -> Result<Option<TagName>, GitDomainError> {
+    let names = git_repo
+        .tag_names(None)
+        .map_err(|e| git_error("list tags", &e))?;
+
+    let mut latest: Option<(SemanticVersion, TagName)> = None;
+    for name in names.iter().flatten() {
+        let Some((tag, version)) = TagName::new(name)
+            .ok()
+            .and_then(|tag| tag.semver().map(|version| (tag, version)))
+            .filter(|(_, version)| !version.is_pre_release())
+        else {
+            continue;
+        };
+        if latest
+            .as_ref()
+            .is_some_and(|(highest, _)| *highest >= version)
+        {
+            continue;
+        }
+
+        // A release tag may name a tree or blob instead of a commit
+        let Ok(commit) = resolve_tag(git_repo, &tag) else {
+            debug!("Skipping tag {} that does not point to a commit", tag);
+            continue;
+        };
+        let earlier = git_repo
+            .graph_descendant_of(to, commit.id())
+            .map_err(|e| git_error("compare tag with release", &e))?;
+        if earlier {
+            latest = Some((version, tag));
+        }
+    }
+
+    Ok(latest.map(|(_, tag)| tag))
+}
@@ -0,0 +1,457 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! Content search for the repository command handler
+//!
+//! Blobs are read straight from the object database, so any revision can be
+//! searched without a checkout. Binary detection follows the `.gitattributes`
+//! files of the searched tree and falls back to git's own heuristic.
+
+use super::{diff, git_error, to_commit_hash, RepositoryCommandHandler};
+use crate::commands::SearchRepository;
+use crate::path_glob::PathGlob;
+use crate::value_objects::{CommitHash, FilePath};
+use crate::GitDomainError;
+use git2::{
+    Blob, Commit, ErrorCode, ObjectType, Oid, Repository as Git2Repository, Sort, Tree,
+    TreeWalkMode, TreeWalkResult,
+};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::{info, instrument};
+
+/// Results returned when the command does not set `max_results`
+const DEFAULT_MAX_RESULTS: usize = 1000;
+
+/// Longest snippet kept for a matching line, in characters
+const MAX_SNIPPET_CHARS: usize = 200;
+
+/// A single match of the search pattern
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchMatch {
+    /// File containing the match
+    pub path: FilePath,
+
+    /// Line number, starting at 1
+    pub line: usize,
+
+    /// Column of the first matched character, starting at 1
+    pub column: usize,
+
+    /// The matching line, truncated for very long lines
+    pub snippet: String,
+}
+
+/// A commit that changed how often the pattern occurs in a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PickaxeHit {
+    /// Commit that made the change
+    pub commit: CommitHash,
+
+    /// File whose match count changed
+    pub path: FilePath,
+
+    /// Matches in the file before the commit
+    pub matches_before: usize,
+
+    /// Matches in the file after the commit
+    pub matches_after: usize,
+}
+
+/// Outcome of a repository search
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchResults {
+    /// Revision or range that was searched
+    pub revision: String,
+
+    /// Matches in the searched tree, in path and line order
+    pub matches: Vec<SearchMatch>,
+
+    /// Commits that changed match counts, newest first; only filled in pickaxe mode
+    pub commits: Vec<PickaxeHit>,
+
+    /// Whether results were dropped because of the result limit
+    pub truncated: bool,
+}
+
+impl RepositoryCommandHandler {
+    /// Search file contents of a known repository
+    ///
+    /// Searches the tree of the requested revision (`HEAD` by default) line by
+    /// line. In pickaxe mode the history is walked instead and every non-merge
+    /// commit that changed the number of matches in a file is reported, like
+    /// `git log -S --pickaxe-regex`.
+    #[instrument(skip(self, command), fields(pattern = %command.pattern, pickaxe = command.pickaxe))]
+    pub async fn search_repository(
+        &self,
+        command: &SearchRepository,
+    ) -> Result<SearchResults, GitDomainError> {
+        let (_, git_repo) = self.open_local(&command.repository_id)?;
+        let searcher = Searcher::new(command)?;
+        let limit = command.max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+
+        let mut results = SearchResults {
+            revision: command
+                .revision
+                .clone()
+                .unwrap_or_else(|| "HEAD".to_string()),
+            ..SearchResults::default()
+        };
+
+        if command.pickaxe {
+            search_history(&git_repo, &searcher, limit, &mut results)?;
+        } else {
+            let commit = resolve_commit(&git_repo, &results.revision)?;
+            search_tree(&git_repo, &searcher, &commit, limit, &mut results)?;
+        }
+
+        info!(
+            "Searched {}: {} matches, {} commits (truncated: {})",
+            results.revision,
+            results.matches.len(),
+            results.commits.len(),
+            results.truncated
+        );
+
+        Ok(results)
+    }
+}
+
+/// Compiled pattern and path filters of a search command
+struct Searcher {
+    pattern: Regex,
+    include: Vec<PathGlob>,
+    exclude: Vec<PathGlob>,
+}
+
+impl Searcher {
+    fn new(command: &SearchRepository) -> Result<Self, GitDomainError> {
+        if command.pattern.is_empty() {
+            return Err(GitDomainError::ValidationError(
+                "Search pattern cannot be empty".to_string(),
+            ));
+        }
+
+        let pattern = RegexBuilder::new(&command.pattern)
+            .case_insensitive(!command.case_sensitive)
+            .build()
+            .map_err(|e| GitDomainError::ValidationError(format!("Invalid search pattern: {e}")))?;
+        let globs = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| PathGlob::new("", pattern))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(Self {
+            pattern,
+            include: globs(&command.include_patterns)?,
+            exclude: globs(&command.exclude_patterns)?,
+        })
+    }
+
+    /// Whether a path passes the include and exclude filters
+    fn wants(&self, path: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|glob| glob.matches(path)))
+            && !self.exclude.iter().any(|glob| glob.matches(path))
+    }
+
+    /// Line, column and snippet of every match in `content`
+    fn find<'a>(&'a self, content: &'a str) -> impl Iterator<Item = (usize, usize, String)> + 'a {
+        content.lines().enumerate().flat_map(move |(index, line)| {
+            self.pattern.find_iter(line).map(move |found| {
+                let column = line[..found.start()].chars().count() + 1;
+                let snippet = line.trim_end().chars().take(MAX_SNIPPET_CHARS).collect();
+                (index + 1, column, snippet)
+            })
+        })
+    }
+
+    /// Number of matches in `content`, counted the same way as [`Self::find`]
+    fn count(&self, content: &str) -> usize {
+        content
+            .lines()
+            .map(|line| self.pattern.find_iter(line).count())
+            .sum()
+    }
+}
+
+/// Binary overrides collected from the `.gitattributes` files of a tree
+struct Attributes {
+    /// Patterns in precedence order, later entries win
+    rules: Vec<(PathGlob, bool)>,
+}
+
+impl Attributes {
+    /// Read every `.gitattributes` file of `tree`
+    fn load(git_repo: &Git2Repository, tree: &Tree<'_>) -> Result<Self, GitDomainError> {
+        Self::parse(git_repo, &attribute_files(tree)?)
+    }
+
+    /// Parse the given `.gitattributes` files, shallowest first
+    fn parse(git_repo: &Git2Repository, files: &[(String, Oid)]) -> Result<Self, GitDomainError> {
+        let mut rules = Vec::new();
+        for (path, oid) in files {
+            let blob = git_repo
+                .find_blob(*oid)
+                .map_err(|e| git_error(&format!("read {path}"), &e))?;
+            let base = path.rsplit_once('/').map_or("", |(dir, _)| dir);
+            for line in String::from_utf8_lossy(blob.content()).lines() {
+                let mut fields = line.split_whitespace();
+                if let Some(pattern) = fields.next().filter(|field| !field.starts_with('#')) {
+                    // The last binary-related attribute on a line decides
+                    if let Some(binary) = fields.filter_map(binary_attribute).next_back() {
+                        rules.push((PathGlob::new(base, pattern)?, binary));
+                    }
+                }
+            }
+        }
+
+        Ok(Self { rules })
+    }
+
+    /// Whether the attributes force `path` to be binary or text
+    fn binary(&self, path: &str) -> Option<bool> {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(glob, _)| glob.matches(path))
+            .map(|(_, binary)| *binary)
+    }
+
+    /// Content of a blob if it should be searched as text
+    fn text<'blob>(&self, path: &str, blob: &'blob Blob<'_>) -> Option<&'blob str> {
+        let binary = self.binary(path).unwrap_or_else(|| blob.is_binary());
+        if binary {
+            None
+        } else {
+            std::str::from_utf8(blob.content()).ok()
+        }
+    }
+}
+
+/// Attributes of the trees of a history walk, parsed again only when one of
+/// the `.gitattributes` files changes
+#[derive(Default)]
+struct AttributesCache(Option<(Vec<(String, Oid)>, Attributes)>);
+
+impl AttributesCache {
+    fn get(
+        &mut self,
+        git_repo: &Git2Repository,
+        tree: &Tree<'_>,
+    ) -> Result<&Attributes, GitDomainError> {
+        let files = attribute_files(tree)?;
+        let cached = match self.0.take() {
+            Some((cached_files, attributes)) if cached_files == files => (cached_files, attributes),
+            _ => {
+                let attributes = Attributes::parse(git_repo, &files)?;
+                (files, attributes)
+            }
+        };
+        Ok(&self.0.insert(cached).1)
+    }
+}
+
+/// Paths and IDs of the `.gitattributes` files of a tree, shallowest first
+fn attribute_files(tree: &Tree<'_>) -> Result<Vec<(String, Oid)>, GitDomainError> {
+    let mut files = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() == Some(ObjectType::Blob) && entry.name() == Some(".gitattributes") {
+            files.push((format!("{dir}.gitattributes"), entry.id()));
+        }
+        TreeWalkResult::Ok
+    })
+    .map_err(|e| git_error("walk tree", &e))?;
+    files.sort_by_key(|(path, _)| path.matches('/').count());
+
+    Ok(files)
+}
+
+/// Interpret a single attribute as a binary (`true`) or text (`false`) marker
+fn binary_attribute(attribute: &str) -> Option<bool> {
+    match attribute {
+        "binary" | "-text" | "-diff" => Some(true),
+        "text" | "diff" => Some(false),
+        _ => None,
+    }
+}
+
+/// Search the tree of a single commit
+fn search_tree(
+    git_repo: &Git2Repository,
+    searcher: &Searcher,
+    commit: &Commit<'_>,
+    limit: usize,
+    results: &mut SearchResults,
+) -> Result<(), GitDomainError> {
+    let tree = commit.tree().map_err(|e| git_error("read tree", &e))?;
+    let attributes = Attributes::load(git_repo, &tree)?;
+
+    for (path, oid) in tree_blobs(&tree)? {
+        if !searcher.wants(&path) {
+            continue;
+        }
+
+        let blob = git_repo
+            .find_blob(oid)
+            .map_err(|e| git_error(&format!("read {path}"), &e))?;
+        if let Some(content) = attributes.text(&path, &blob) {
+            for (line, column, snippet) in searcher.find(content) {
+                if results.matches.len() == limit {
+                    results.truncated = true;
+                    return Ok(());
+                }
+                results.matches.push(SearchMatch {
+                    path: FilePath::new(path.as_str())?,
+                    line,
+                    column,
+                    snippet,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Walk the history of the revision or range, recording match count changes
+///
+/// Merge commits are skipped, as their changes belong to the merged commits.
+fn search_history(
+    git_repo: &Git2Repository,
+    searcher: &Searcher,
+    limit: usize,
+    results: &mut SearchResults,
+) -> Result<(), GitDomainError> {
+    let mut revwalk = git_repo
+        .revwalk()
+        .map_err(|e| git_error("create revwalk", &e))?;
+    revwalk
+        .set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+        .map_err(|e| git_error("walk history", &e))?;
+    if results.revision.contains("...") {
+        // libgit2 cannot walk symmetric differences
+        return Err(GitDomainError::ValidationError(format!(
+            "Unsupported range {}: use the a..b form",
+            results.revision
+        )));
+    } else if results.revision.contains("..") {
+        revwalk
+            .push_range(&results.revision)
+            .map_err(|e| git_error(&format!("resolve range {}", results.revision), &e))?;
+    } else {
+        let start = resolve_commit(git_repo, &results.revision)?;
+        revwalk
+            .push(start.id())
+            .map_err(|e| git_error("walk history", &e))?;
+    }
+
+    let mut attribute_cache = AttributesCache::default();
+    for oid in revwalk {
+        let commit = oid
+            .and_then(|oid| git_repo.find_commit(oid))
+            .map_err(|e| git_error("walk history", &e))?;
+        if commit.parent_count() > 1 {
+            continue;
+        }
+
+        let read_error = |e: git2::Error| git_error(&format!("read commit {}", commit.id()), &e);
+        let tree = commit.tree().map_err(read_error)?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree().map_err(read_error)?),
+            Err(e) if e.code() == ErrorCode::NotFound => None,
+            Err(e) => return Err(read_error(e)),
+        };
+
+        let changes = diff::file_changes(git_repo, parent_tree.as_ref(), &tree)
+            .map_err(|e| git_error(&format!("diff commit {}", commit.id()), &e))?;
+        let changes: Vec<_> = changes
+            .into_iter()
+            .filter(|change| searcher.wants(change.path.as_str()))
+            .collect();
+        if changes.is_empty() {
+            continue;
+        }
+
+        let attributes = attribute_cache.get(git_repo, &tree)?;
+        for change in changes {
+            let old_path = change.old_path.as_ref().unwrap_or(&change.path);
+            let matches_before = match &parent_tree {
+                Some(parent_tree) => {
+                    count_matches(git_repo, searcher, attributes, parent_tree, old_path)?
+                }
+                None => 0,
+            };
+            let matches_after = count_matches(git_repo, searcher, attributes, &tree, &change.path)?;
+
+            if matches_before != matches_after {
+                if results.commits.len() == limit {
+                    results.truncated = true;
+                    return Ok(());
+                }
+                results.commits.push(PickaxeHit {
+                    commit: to_commit_hash(commit.id())?,
+                    path: change.path,
+                    matches_before,
+                    matches_after,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Number of matches in a file of `tree`, zero if it is absent or binary
+fn count_matches(
+    git_repo: &Git2Repository,
+    searcher: &Searcher,
+    attributes: &Attributes,
+    tree: &Tree<'_>,
+    path: &FilePath,
+) -> Result<usize, GitDomainError> {
+    let entry = match tree.get_path(Path::new(path.as_str())) {
+        Ok(entry) => entry,
+        Err(e) if e.code() == ErrorCode::NotFound => return Ok(0),
+        Err(e) => return Err(git_error(&format!("read {path}"), &e)),
+    };
+    if entry.kind() != Some(ObjectType::Blob) {
+        return Ok(0);
+    }
+
+    let blob = git_repo
+        .find_blob(entry.id())
+        .map_err(|e| git_error(&format!("read {path}"), &e))?;
+
+    Ok(attributes
+        .text(path.as_str(), &blob)
+        .map_or(0, |content| searcher.count(content)))
+}
+
+/// Paths and IDs of every blob in a tree, in tree order
+fn tree_blobs(tree: &Tree<'_>) -> Result<Vec<(String, Oid)>, GitDomainError> {
+    let mut blobs = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() == Some(ObjectType::Blob) {
+            if let Some(name) = entry.name() {
+                blobs.push((format!("{dir}{name}"), entry.id()));
+            }
+        }
+        TreeWalkResult::Ok
+    })
+    .map_err(|e| git_error("walk tree", &e))?;
+
+    Ok(blobs)
+}
+
+/// Resolve a revision to the commit it names
+fn resolve_commit<'repo>(
+    git_repo: &'repo Git2Repository,
+    revision: &str,
+) -> Result<Commit<'repo>, GitDomainError> {
+    git_repo
+        .revparse_single(revision)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|e| git_error(&format!("resolve revision {revision}"), &e))
+}
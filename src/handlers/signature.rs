@@ -0,0 +1,149 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! Signature policy checks for the repository command handler
+//!
+//! Walks the protected branches and reports the commits whose signature does
+//! not satisfy the policy.
+
+use super::{git_error, to_commit_hash, RepositoryCommandHandler};
+use crate::commands::CheckSignaturePolicy;
+use crate::events::{GitDomainEvent, SignaturePolicyChecked};
+use crate::identity::{IdentityMapper, IdentityResolver};
+use crate::signature::{
+    commit_signature, SignaturePolicyReport, SignatureVerifier, SignatureViolation,
+};
+use crate::value_objects::BranchName;
+use crate::GitDomainError;
+use chrono::Utc;
+use git2::{BranchType, Oid, Repository as Git2Repository, Sort};
+use std::collections::HashMap;
+use tracing::{info, instrument, Span};
+
+impl RepositoryCommandHandler {
+    /// Check the commits on the protected branches of a known repository
+    ///
+    /// Protected branches are the local branches matching the policy's
+    /// patterns. A commit on several of them is checked and reported once.
+    /// The report is recorded as a [`SignaturePolicyChecked`] event.
+    #[instrument(skip(self, command), fields(repository_id = %command.repository_id))]
+    pub async fn check_signature_policy(
+        &self,
+        command: &CheckSignaturePolicy,
+    ) -> Result<(SignaturePolicyReport, Vec<GitDomainEvent>), GitDomainError> {
+        let (_, git_repo) = self.open_local(&command.repository_id)?;
+
+        // Verification starts an external process per commit, so the walk
+        // runs on a blocking thread
+        let report = {
+            let command = command.clone();
+            let resolver = self.identity_resolver.clone();
+            let verifier = self.signature_verifier.clone();
+            let span = Span::current();
+            tokio::task::spawn_blocking(move || {
+                let _entered = span.enter();
+                check_branches(
+                    &git_repo,
+                    &command,
+                    resolver.as_deref(),
+                    verifier.as_deref(),
+                )
+            })
+            .await
+            .map_err(|e| {
+                GitDomainError::GitOperationFailed(format!("Signature check task failed: {e}"))
+            })??
+        };
+
+        info!(
+            "Checked {} commits on {} protected branches: {} violations",
+            report.commits_checked,
+            report.branches.len(),
+            report.violations.len()
+        );
+
+        let event = GitDomainEvent::SignaturePolicyChecked(SignaturePolicyChecked {
+            repository_id: command.repository_id,
+            report: report.clone(),
+            timestamp: Utc::now(),
+        });
+
+        Ok((report, vec![event]))
+    }
+}
+
+/// Walk the protected branches of `git_repo` and report the commits the
+/// policy rejects
+fn check_branches(
+    git_repo: &Git2Repository,
+    command: &CheckSignaturePolicy,
+    resolver: Option<&dyn IdentityResolver>,
+    verifier: Option<&SignatureVerifier>,
+) -> Result<SignaturePolicyReport, GitDomainError> {
+    let protected = command.policy.protected()?;
+
+    let mut branches = Vec::new();
+    for branch in git_repo
+        .branches(Some(BranchType::Local))
+        .map_err(|e| git_error("list branches", &e))?
+    {
+        let (branch, _) = branch.map_err(|e| git_error("list branches", &e))?;
+        let name = branch
+            .name()
+            .ok()
+            .flatten()
+            .and_then(|name| BranchName::new(name).ok());
+        if let (Some(name), Some(tip)) = (name, branch.get().target()) {
+            if protected(&name) {
+                branches.push((name, tip));
+            }
+        }
+    }
+    branches.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+
+    let identities = IdentityMapper::new(git_repo, resolver);
+    // Index of the commit's violation, if it has one
+    let mut checked: HashMap<Oid, Option<usize>> = HashMap::new();
+    let mut violations: Vec<SignatureViolation> = Vec::new();
+    for (branch, tip) in &branches {
+        let mut revwalk = git_repo
+            .revwalk()
+            .map_err(|e| git_error("create revwalk", &e))?;
+        revwalk
+            .set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+            .and_then(|()| revwalk.push(*tip))
+            .map_err(|e| git_error("walk history", &e))?;
+
+        for oid in revwalk.take(command.max_commits.unwrap_or(usize::MAX)) {
+            let oid = oid.map_err(|e| git_error("walk history", &e))?;
+            if let Some(violation) = checked.get(&oid) {
+                if let Some(index) = violation {
+                    violations[*index].branches.push(branch.clone());
+                }
+                continue;
+            }
+
+            let signature = commit_signature(git_repo, oid, verifier);
+            if command.policy.accepts(signature.as_ref()) {
+                checked.insert(oid, None);
+                continue;
+            }
+            let commit = git_repo
+                .find_commit(oid)
+                .map_err(|e| git_error("find commit", &e))?;
+            checked.insert(oid, Some(violations.len()));
+            violations.push(SignatureViolation {
+                commit_hash: to_commit_hash(oid)?,
+                author: identities.resolve(&commit.author()).0,
+                branches: vec![branch.clone()],
+                signature,
+            });
+        }
+    }
+
+    Ok(SignaturePolicyReport {
+        policy: command.policy.clone(),
+        branches: branches.into_iter().map(|(name, _)| name).collect(),
+        commits_checked: checked.len(),
+        violations,
+    })
+}
@@ -0,0 +1,232 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! Author identity resolution
+//!
+//! The same person often commits under several names or email addresses.
+//! Identities are resolved when commits are read: the repository's own
+//! `.mailmap` is applied first, followed by an optional [`IdentityResolver`]
+//! configured on the command handler. Events carry the canonical identity,
+//! so analyzers count each person once, and keep the recorded identity
+//! alongside it whenever the two differ.
+
+use git2::{Mailmap, Repository as Git2Repository, Signature};
+use tracing::debug;
+
+use crate::value_objects::AuthorInfo;
+
+/// Maps a recorded author identity to the canonical identity of its person
+pub trait IdentityResolver: Send + Sync {
+    /// Canonical identity of `identity`, or `None` if it is not known
+    fn resolve(&self, identity: &AuthorInfo) -> Option<AuthorInfo>;
+}
+
+/// One alias rule, in the shape of a `.mailmap` entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AliasRule {
+    proper_name: Option<String>,
+    proper_email: Option<String>,
+    commit_name: Option<String>,
+    commit_email: String,
+}
+
+impl AliasRule {
+    fn matches(&self, identity: &AuthorInfo) -> bool {
+        self.commit_email.eq_ignore_ascii_case(&identity.email)
+            && self
+                .commit_name
+                .as_ref()
+                .is_none_or(|name| name.eq_ignore_ascii_case(&identity.name))
+    }
+}
+
+/// Alias rules with `.mailmap` semantics
+///
+/// A rule matches an identity by email, or by name and email, ignoring case,
+/// and replaces its name, its email or both. Rules naming both a name and an
+/// email take precedence, and later rules take precedence over earlier ones.
+///
+/// # Examples
+///
+/// ```
+/// use cim_domain_git::identity::{AliasRules, IdentityResolver};
+/// use cim_domain_git::value_objects::AuthorInfo;
+///
+/// let rules = AliasRules::from_mailmap("Jane Doe <jane@example.com> <jd@old.example.com>")
+///     .with_email_alias("jane@laptop.local", AuthorInfo::new("Jane Doe", "jane@example.com"));
+///
+/// let canonical = AuthorInfo::new("Jane Doe", "jane@example.com");
+/// let old = AuthorInfo::new("jd", "jd@old.example.com");
+/// assert_eq!(rules.resolve(&old), Some(canonical.clone()));
+/// let laptop = AuthorInfo::new("jane", "jane@laptop.local");
+/// assert_eq!(rules.resolve(&laptop), Some(canonical));
+/// assert_eq!(rules.resolve(&AuthorInfo::new("John", "john@example.com")), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AliasRules {
+    rules: Vec<AliasRule>,
+}
+
+impl AliasRules {
+    /// Create an empty rule set
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read rules from the contents of a `.mailmap` file
+    ///
+    /// Lines that are not valid entries are skipped, as git does.
+    #[must_use]
+    pub fn from_mailmap(content: &str) -> Self {
+        Self {
+            rules: content.lines().filter_map(parse_mailmap_line).collect(),
+        }
+    }
+
+    /// Map every identity using `email` to the canonical identity
+    #[must_use]
+    pub fn with_email_alias(mut self, email: impl Into<String>, canonical: AuthorInfo) -> Self {
+        self.rules.push(AliasRule {
+            proper_name: Some(canonical.name),
+            proper_email: Some(canonical.email),
+            commit_name: None,
+            commit_email: email.into(),
+        });
+        self
+    }
+
+    /// Map one exact name and email pair to the canonical identity
+    #[must_use]
+    pub fn with_identity_alias(mut self, alias: AuthorInfo, canonical: AuthorInfo) -> Self {
+        self.rules.push(AliasRule {
+            proper_name: Some(canonical.name),
+            proper_email: Some(canonical.email),
+            commit_name: Some(alias.name),
+            commit_email: alias.email,
+        });
+        self
+    }
+
+    /// Number of rules
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Whether there are no rules
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+impl IdentityResolver for AliasRules {
+    fn resolve(&self, identity: &AuthorInfo) -> Option<AuthorInfo> {
+        let rule = self
+            .rules
+            .iter()
+            .rev()
+            .find(|rule| rule.commit_name.is_some() && rule.matches(identity))
+            .or_else(|| {
+                self.rules
+                    .iter()
+                    .rev()
+                    .find(|rule| rule.commit_name.is_none() && rule.matches(identity))
+            })?;
+
+        Some(AuthorInfo::new(
+            rule.proper_name.as_ref().unwrap_or(&identity.name),
+            rule.proper_email.as_ref().unwrap_or(&identity.email),
+        ))
+    }
+}
+
+/// Parse one `.mailmap` line
+///
+/// Entries take the forms `Proper Name <commit@email>`,
+/// `<proper@email> <commit@email>`, `Proper Name <proper@email> <commit@email>`
+/// and `Proper Name <proper@email> Commit Name <commit@email>`.
+fn parse_mailmap_line(line: &str) -> Option<AliasRule> {
+    let line = line.split('#').next().unwrap_or_default();
+
+    // Each address is preceded by an optional name
+    let mut entries = Vec::new();
+    let mut rest = line;
+    while let Some((name, tail)) = rest.split_once('<') {
+        let (email, tail) = tail.split_once('>')?;
+        let name = name.trim();
+        entries.push(((!name.is_empty()).then(|| name.to_string()), email.trim()));
+        rest = tail;
+    }
+
+    match entries.as_slice() {
+        [(proper_name @ Some(_), commit_email)] => Some(AliasRule {
+            proper_name: proper_name.clone(),
+            proper_email: None,
+            commit_name: None,
+            commit_email: (*commit_email).to_string(),
+        }),
+        [(proper_name, proper_email), (commit_name, commit_email)] => Some(AliasRule {
+            proper_name: proper_name.clone(),
+            proper_email: (!proper_email.is_empty()).then(|| (*proper_email).to_string()),
+            commit_name: commit_name.clone(),
+            commit_email: (*commit_email).to_string(),
+        }),
+        _ => None,
+    }
+}
+
+/// Domain identity of a git signature, as recorded
+pub(crate) fn author_info(signature: &Signature<'_>) -> AuthorInfo {
+    AuthorInfo::new(
+        signature.name().unwrap_or("Unknown"),
+        signature.email().unwrap_or("unknown@example.com"),
+    )
+}
+
+/// Resolves the signatures of one repository to canonical identities
+pub(crate) struct IdentityMapper<'a> {
+    mailmap: Option<Mailmap>,
+    resolver: Option<&'a dyn IdentityResolver>,
+}
+
+impl<'a> IdentityMapper<'a> {
+    /// Load the mailmap of a repository, from `.mailmap`, `mailmap.file` or
+    /// `mailmap.blob`
+    pub(crate) fn new(
+        git_repo: &Git2Repository,
+        resolver: Option<&'a dyn IdentityResolver>,
+    ) -> Self {
+        let mailmap = git_repo
+            .mailmap()
+            .map_err(|e| debug!("Not applying mailmap: {}", e))
+            .ok();
+
+        Self { mailmap, resolver }
+    }
+
+    /// Canonical identity of a signature, and the recorded identity if it
+    /// differs
+    pub(crate) fn resolve(&self, signature: &Signature<'_>) -> (AuthorInfo, Option<AuthorInfo>) {
+        let recorded = author_info(signature);
+        let mapped = self
+            .mailmap
+            .as_ref()
+            .and_then(|mailmap| mailmap.resolve_signature(signature).ok())
+            .map_or_else(|| recorded.clone(), |resolved| author_info(&resolved));
+        let canonical = self.canonical(mapped);
+
+        if canonical == recorded {
+            (canonical, None)
+        } else {
+            (canonical, Some(recorded))
+        }
+    }
+
+    /// Apply the configured resolver to an identity the mailmap already saw
+    pub(crate) fn canonical(&self, identity: AuthorInfo) -> AuthorInfo {
+        self.resolver
+            .and_then(|resolver| resolver.resolve(&identity))
+            .unwrap_or(identity)
+    }
+}
@@ -126,10 +126,14 @@ pub mod commands;
 pub mod dependency_analysis;
 pub mod events;
 pub mod handlers;
+pub mod identity;
 pub mod nats;
+mod path_glob;
 pub mod projections;
 pub mod queries;
+pub mod release;
 pub mod security;
+pub mod signature;
 pub mod value_objects;
 
 // Re-export commonly used types
@@ -142,13 +146,14 @@ pub use value_objects::{AuthorInfo, BranchName, CommitHash};
 pub use projections::{
     BranchInfo, BranchStatusProjection, CommitHistoryEntry, CommitHistoryProjection, FileChange,
     FileChangeProjection, FileStatistics, ProjectionError, RepositoryListProjection,
-    RepositorySummary,
+    RepositorySummary, TagInfo, TagProjection,
 };
 
 // Re-export queries
 pub use queries::{
     BranchListResult, CommitHistoryResult, GetBranchList, GetCommitHistory, GetRepositoryDetails,
-    GitQueryHandler, ListRepositories, ListRepositoriesResult, QueryError, RepositoryDetailsResult,
+    GetTag, GitQueryHandler, ListRepositories, ListRepositoriesResult, ListTags, QueryError,
+    RepositoryDetailsResult, TagListResult, TagResult,
 };
 
 // Re-export NATS types
@@ -180,6 +185,54 @@ pub enum GitDomainError {
     #[error("Validation error: {0}")]
     ValidationError(String),
 
+    /// Clone destination already exists and is not empty
+    #[error("Clone target already exists: {0}")]
+    CloneTargetExists(String),
+
+    /// Repository to be cloned already has a local clone
+    #[error("Repository already cloned: {0}")]
+    RepositoryAlreadyCloned(RepositoryId),
+
+    /// Remote could not be reached or does not exist
+    #[error("Remote unreachable: {0}")]
+    RemoteUnreachable(String),
+
+    /// Authentication with the remote failed
+    #[error("Authentication failed: {0}")]
+    AuthenticationFailed(String),
+
+    /// Requested branch does not exist
+    #[error("Branch not found: {0}")]
+    BranchNotFound(String),
+
+    /// Requested remote is not configured
+    #[error("Remote not found: {0}")]
+    RemoteNotFound(String),
+
+    /// Shallow clone requested for a transport that cannot provide it
+    #[error("Shallow clone unsupported: {0}")]
+    ShallowCloneUnsupported(String),
+
+    /// Branch to be created already exists
+    #[error("Branch already exists: {0}")]
+    BranchAlreadyExists(String),
+
+    /// Branch to be deleted is checked out as HEAD
+    #[error("Cannot delete the current branch: {0}")]
+    CannotDeleteCurrentBranch(String),
+
+    /// Branch to be deleted is not merged into HEAD
+    #[error("Branch not fully merged: {0}")]
+    BranchNotMerged(String),
+
+    /// Tag to be created already exists
+    #[error("Tag already exists: {0}")]
+    TagAlreadyExists(String),
+
+    /// Requested tag does not exist
+    #[error("Tag not found: {0}")]
+    TagNotFound(String),
+
     /// Infrastructure error
     #[error("Infrastructure error: {0}")]
     InfrastructureError(#[from] anyhow::Error),
@@ -3,6 +3,9 @@
 //! Error types for NATS operations
 
 use thiserror::Error;
+use uuid::Uuid;
+
+use super::event_store::AppendedEvent;
 
 /// Errors that can occur during NATS operations
 #[derive(Debug, Error)]
@@ -47,6 +50,47 @@ pub enum NatsError {
     #[error("Configuration error: {0}")]
     ConfigurationError(String),
 
+    /// Another event was appended to an aggregate since the sequence an append expected
+    #[error(
+        "Concurrency conflict on aggregate {aggregate_id}: expected last sequence {expected}, found {actual}"
+    )]
+    ConcurrencyConflict {
+        /// Aggregate the events were appended to
+        aggregate_id: String,
+        /// Stream sequence of the aggregate's last event the append expected
+        expected: u64,
+        /// Stream sequence of the aggregate's last event in the event store
+        actual: u64,
+    },
+
+    /// An aggregate has a different number of events than the version an append expected
+    #[error(
+        "Version conflict on aggregate {aggregate_id}: expected version {expected}, found {actual}"
+    )]
+    VersionConflict {
+        /// Aggregate the events were appended to
+        aggregate_id: String,
+        /// Aggregate version the append expected
+        expected: u64,
+        /// Number of events of the aggregate in the event store
+        actual: u64,
+    },
+
+    /// A batch append stopped before its last event
+    #[error("Batch append failed at event {failed_event} after {} stored events: {reason}", appended.len())]
+    BatchIncomplete {
+        /// Events stored before the failure, in batch order
+        appended: Vec<AppendedEvent>,
+        /// Event that could not be appended
+        failed_event: Uuid,
+        /// Why the append failed
+        reason: String,
+    },
+
+    /// The connected server lacks a feature the operation relies on
+    #[error("Unsupported NATS server: {0}")]
+    UnsupportedServer(String),
+
     /// Other errors
     #[error("NATS error: {0}")]
     Other(String),
@@ -35,6 +35,42 @@ mod tests {
         let error = NatsError::HealthCheckError("service down".to_string());
         assert_eq!(error.to_string(), "Health check error: service down");
 
+        let error = NatsError::ConcurrencyConflict {
+            aggregate_id: "repo-1".to_string(),
+            expected: 3,
+            actual: 5,
+        };
+        assert_eq!(
+            error.to_string(),
+            "Concurrency conflict on aggregate repo-1: expected last sequence 3, found 5"
+        );
+
+        let error = NatsError::VersionConflict {
+            aggregate_id: "repo-1".to_string(),
+            expected: 2,
+            actual: 4,
+        };
+        assert_eq!(
+            error.to_string(),
+            "Version conflict on aggregate repo-1: expected version 2, found 4"
+        );
+
+        let error = NatsError::BatchIncomplete {
+            appended: Vec::new(),
+            failed_event: uuid::Uuid::nil(),
+            reason: "timed out".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Batch append failed at event 00000000-0000-0000-0000-000000000000 after 0 stored events: timed out"
+        );
+
+        let error = NatsError::UnsupportedServer("2.10.22 is too old".to_string());
+        assert_eq!(
+            error.to_string(),
+            "Unsupported NATS server: 2.10.22 is too old"
+        );
+
         let error = NatsError::Other("generic error".to_string());
         assert_eq!(error.to_string(), "NATS error: generic error");
     }
@@ -86,6 +122,16 @@ mod tests {
             NatsError::ConfigurationError("test".to_string()),
             NatsError::Timeout,
             NatsError::HealthCheckError("test".to_string()),
+            NatsError::ConcurrencyConflict {
+                aggregate_id: "test".to_string(),
+                expected: 0,
+                actual: 1,
+            },
+            NatsError::BatchIncomplete {
+                appended: Vec::new(),
+                failed_event: uuid::Uuid::nil(),
+                reason: "test".to_string(),
+            },
             NatsError::Other("test".to_string()),
         ];
 
@@ -6,20 +6,35 @@
 //! for event sourcing. JetStream provides the durability, ordering, and replay capabilities
 //! needed for event sourcing.
 
-use async_nats::jetstream::stream::Stream;
+use async_nats::header::{self, HeaderMap};
+use async_nats::jetstream::context::{PublishError, PublishErrorKind};
+use async_nats::jetstream::publish::PublishAck;
+use async_nats::jetstream::stream::{LastRawMessageErrorKind, Stream};
 use async_nats::jetstream::{consumer::Consumer, Context as JetStreamContext};
+use bytes::Bytes;
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
-use tracing::info;
+use tracing::{info, warn};
 use uuid::Uuid;
 
 use super::{
     error::{NatsError, Result},
     publisher::EventPublisher,
+    subject::GitSubject,
 };
 use crate::aggregate::RepositoryId;
-use crate::events::EventEnvelope;
+use crate::events::{EventEnvelope, UpcasterRegistry};
+
+/// Header scoping `Nats-Expected-Last-Subject-Sequence` to a subject filter
+const EXPECTED_LAST_SUBJECT_SEQUENCE_SUBJECT: &str = "Nats-Expected-Last-Subject-Sequence-Subject";
+
+/// First NATS server release honouring [`EXPECTED_LAST_SUBJECT_SEQUENCE_SUBJECT`]
+const SUBJECT_SEQUENCE_FILTER_VERSION: (u64, u64) = (2, 11);
+
+/// Delay before the first retry of a failed publish, growing with each attempt
+const RETRY_BACKOFF: Duration = Duration::from_millis(200);
 
 /// Event store configuration
 #[derive(Debug, Clone)]
@@ -35,6 +50,12 @@ pub struct EventStoreConfig {
 
     /// Number of replicas
     pub num_replicas: usize,
+
+    /// Window in which the stream drops events with an already stored ID
+    pub duplicate_window: Duration,
+
+    /// How often a publish is retried after a timeout or lost connection
+    pub publish_retries: u32,
 }
 
 impl Default for EventStoreConfig {
@@ -44,6 +65,8 @@ impl Default for EventStoreConfig {
             max_age: Duration::from_secs(365 * 24 * 60 * 60), // 1 year
             max_messages: 10_000_000,
             num_replicas: 1,
+            duplicate_window: Duration::from_secs(120), // 2 minutes
+            publish_retries: 3,
         }
     }
 }
@@ -57,12 +80,11 @@ impl Default for EventStoreConfig {
 /// - Consumer checkpoints for processing position
 /// - Subject-based filtering for aggregate events
 pub struct EventStore {
-    #[allow(dead_code)]
     jetstream: JetStreamContext,
     stream: Stream,
     publisher: EventPublisher,
-    #[allow(dead_code)]
     config: EventStoreConfig,
+    upcasters: Arc<UpcasterRegistry>,
 }
 
 impl EventStore {
@@ -80,9 +102,22 @@ impl EventStore {
             stream,
             publisher,
             config,
+            upcasters: Arc::new(UpcasterRegistry::new()),
         })
     }
 
+    /// Set the upcasters applied to events read from the stream
+    #[must_use]
+    pub fn with_upcasters(mut self, upcasters: Arc<UpcasterRegistry>) -> Self {
+        self.upcasters = upcasters;
+        self
+    }
+
+    /// Upcasters applied to events read from the stream
+    pub fn upcasters(&self) -> Arc<UpcasterRegistry> {
+        self.upcasters.clone()
+    }
+
     /// Ensure the event stream exists with proper configuration
     async fn ensure_stream(
         jetstream: &JetStreamContext,
@@ -97,6 +132,7 @@ impl EventStore {
             max_messages: config.max_messages,
             storage: StorageType::File,
             num_replicas: config.num_replicas,
+            duplicate_window: config.duplicate_window,
             retention: RetentionPolicy::Limits,
             ..Default::default()
         };
@@ -125,72 +161,370 @@ impl EventStore {
 
     /// Append an event to JetStream
     ///
-    /// Events are published through the event publisher which adds proper headers
-    /// and routes to the correct subject. JetStream automatically assigns a
-    /// sequence number and persists the event.
+    /// Events are published with the headers and subject of the event
+    /// publisher. Returns the stream sequence the server acknowledged the
+    /// event with; appending an event that is already stored returns the
+    /// sequence it was stored at.
     pub async fn append(&mut self, envelope: &EventEnvelope) -> Result<u64> {
-        // Publish via the event publisher (which handles headers and routing)
-        self.publisher.publish_envelope(envelope).await?;
+        let (subject, headers, payload) = self.publisher.envelope_message(envelope)?;
 
-        // Get the sequence number from the stream
-        let info = self
-            .stream
-            .info()
+        self.publish(subject, headers, payload)
             .await
-            .map_err(|e| NatsError::Other(format!("Failed to get stream info: {}", e)))?;
+            .map(|ack| ack.sequence)
+            .map_err(|e| NatsError::PublishError(e.to_string()))
+    }
+
+    /// Append multiple events in order
+    ///
+    /// Each event is published with its event ID as `Nats-Msg-Id`, so the
+    /// stream stores it once even if the batch is submitted again within the
+    /// duplicate window; events stored by an earlier submission are reported
+    /// as duplicates with their original sequence. Nothing is published if an
+    /// envelope cannot be mapped to a subject, and a failed append stops the
+    /// batch with [`NatsError::BatchIncomplete`] listing the events stored.
+    pub async fn append_batch(
+        &mut self,
+        envelopes: &[EventEnvelope],
+    ) -> Result<Vec<AppendedEvent>> {
+        let messages = envelopes
+            .iter()
+            .map(|envelope| self.publisher.envelope_message(envelope))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut appended = Vec::with_capacity(envelopes.len());
+        for (envelope, (subject, headers, payload)) in envelopes.iter().zip(messages) {
+            match self.publish(subject, headers, payload).await {
+                Ok(ack) => appended.push(AppendedEvent::new(envelope, &ack)),
+                Err(e) => {
+                    return Err(NatsError::BatchIncomplete {
+                        appended,
+                        failed_event: envelope.event_id(),
+                        reason: e.to_string(),
+                    })
+                }
+            }
+        }
 
-        Ok(info.state.messages)
+        Ok(appended)
     }
 
-    /// Append multiple events atomically
-    pub async fn append_batch(&mut self, envelopes: &[EventEnvelope]) -> Result<Vec<u64>> {
-        let mut sequences = Vec::with_capacity(envelopes.len());
+    /// Append an event if its aggregate's last event is still at
+    /// `expected_sequence`
+    ///
+    /// See [`EventStore::append_batch_expected`].
+    pub async fn append_expected(
+        &mut self,
+        envelope: &EventEnvelope,
+        expected_sequence: u64,
+    ) -> Result<u64> {
+        let appended = self
+            .append_batch_expected(std::slice::from_ref(envelope), expected_sequence)
+            .await?;
+
+        Ok(appended[0].sequence)
+    }
 
-        for envelope in envelopes {
-            let seq = self.append(envelope).await?;
-            sequences.push(seq);
+    /// Append events of one aggregate if its last event is still at
+    /// `expected_sequence`
+    ///
+    /// The sequence is the stream sequence of the last event of the aggregate
+    /// the caller has seen, such as [`StoredEvent::sequence`] of the last event
+    /// returned by [`EventStore::load_aggregate_events`] or
+    /// [`Snapshot::sequence`](super::snapshot::Snapshot::sequence), and 0 for
+    /// an aggregate without events. Each event is published with the stream
+    /// sequence of the aggregate's previous event as
+    /// `Nats-Expected-Last-Subject-Sequence`, so the server rejects it if
+    /// another writer appended to the aggregate in the meantime and the append
+    /// fails with [`NatsError::ConcurrencyConflict`]. Events appended before
+    /// a conflict stay in the stream; other failures stop the batch with
+    /// [`NatsError::BatchIncomplete`] as in [`EventStore::append_batch`].
+    ///
+    /// Checking across all subjects of the aggregate relies on the
+    /// `Nats-Expected-Last-Subject-Sequence-Subject` header of NATS 2.11.
+    /// Older servers would compare the sequence with the last event on the
+    /// event's own subject and reject every append following an event of
+    /// another type, so appends fail with [`NatsError::UnsupportedServer`]
+    /// before anything is published.
+    pub async fn append_batch_expected(
+        &mut self,
+        envelopes: &[EventEnvelope],
+        expected_sequence: u64,
+    ) -> Result<Vec<AppendedEvent>> {
+        let Some(first) = envelopes.first() else {
+            return Ok(Vec::new());
+        };
+        let aggregate_id = first.aggregate_id();
+        if let Some(other) = envelopes.iter().find(|e| e.aggregate_id() != aggregate_id) {
+            return Err(NatsError::Other(format!(
+                "Cannot append events of aggregates {aggregate_id} and {} with one expected sequence",
+                other.aggregate_id()
+            )));
         }
+        self.require_subject_sequence_filter()?;
+        let messages = envelopes
+            .iter()
+            .map(|envelope| self.publisher.envelope_message(envelope))
+            .collect::<Result<Vec<_>>>()?;
+
+        let filter = GitSubject::aggregate_events(&aggregate_id);
+        let mut last_sequence = expected_sequence;
+        let mut appended = Vec::with_capacity(envelopes.len());
+        for (envelope, (subject, mut headers, payload)) in envelopes.iter().zip(messages) {
+            headers.insert(
+                header::NATS_EXPECTED_LAST_SUBJECT_SEQUENCE,
+                last_sequence.to_string(),
+            );
+            headers.insert(EXPECTED_LAST_SUBJECT_SEQUENCE_SUBJECT, filter.as_str());
 
-        Ok(sequences)
+            match self.publish(subject, headers, payload).await {
+                Ok(ack) => {
+                    last_sequence = ack.sequence;
+                    appended.push(AppendedEvent::new(envelope, &ack));
+                }
+                Err(e) if e.kind() == PublishErrorKind::WrongLastSequence => {
+                    let actual = match reported_last_sequence(&e.to_string()) {
+                        Some(actual) => actual,
+                        None => self.last_sequence(&filter).await?,
+                    };
+                    return Err(NatsError::ConcurrencyConflict {
+                        aggregate_id,
+                        expected: last_sequence,
+                        actual,
+                    });
+                }
+                Err(e) => {
+                    return Err(NatsError::BatchIncomplete {
+                        appended,
+                        failed_event: envelope.event_id(),
+                        reason: e.to_string(),
+                    })
+                }
+            }
+        }
+
+        Ok(appended)
+    }
+
+    /// Append an event if its repository is still at `expected_version`
+    ///
+    /// See [`EventStore::append_batch_versioned`].
+    pub async fn append_versioned(
+        &mut self,
+        envelope: &EventEnvelope,
+        expected_version: u64,
+    ) -> Result<u64> {
+        let appended = self
+            .append_batch_versioned(std::slice::from_ref(envelope), expected_version)
+            .await?;
+
+        Ok(appended[0].sequence)
+    }
+
+    /// Append events of one repository if it is still at `expected_version`
+    ///
+    /// The version is the [`Repository::version`](crate::aggregate::Repository::version)
+    /// of a repository rebuilt from this store, such as one returned by
+    /// [`SnapshotStore::load_repository`](super::snapshot::SnapshotStore::load_repository):
+    /// the number of events of the aggregate it has applied. The append fails
+    /// with [`NatsError::VersionConflict`] if the aggregate has a different
+    /// number of events. Otherwise the version is mapped to the stream
+    /// sequence of the aggregate's last event and the events are appended as
+    /// by [`EventStore::append_batch_expected`], so a writer appending in the
+    /// meantime still causes a [`NatsError::ConcurrencyConflict`].
+    pub async fn append_batch_versioned(
+        &mut self,
+        envelopes: &[EventEnvelope],
+        expected_version: u64,
+    ) -> Result<Vec<AppendedEvent>> {
+        let Some(first) = envelopes.first() else {
+            return Ok(Vec::new());
+        };
+        let aggregate_id = first.aggregate_id();
+        let filter = GitSubject::aggregate_events(&aggregate_id);
+
+        // Read before counting: an event appended in between raises the count
+        // or moves the last sequence, and either fails the append
+        let sequence = self.last_sequence(&filter).await?;
+        let version = self.count_events(&filter).await?;
+        if version != expected_version {
+            return Err(NatsError::VersionConflict {
+                aggregate_id,
+                expected: expected_version,
+                actual: version,
+            });
+        }
+
+        self.append_batch_expected(envelopes, sequence).await
+    }
+
+    /// Fail unless the server checks expected sequences across a subject filter
+    fn require_subject_sequence_filter(&self) -> Result<()> {
+        let version = self.publisher.client().server_info().version;
+        if supports_subject_sequence_filter(&version) {
+            return Ok(());
+        }
+
+        let (major, minor) = SUBJECT_SEQUENCE_FILTER_VERSION;
+        Err(NatsError::UnsupportedServer(format!(
+            "NATS {version} cannot check an expected sequence across an aggregate's subjects; \
+             {major}.{minor} or later is required"
+        )))
+    }
+
+    /// Publish a message and wait for the JetStream acknowledgement
+    ///
+    /// Timeouts and lost connections are retried; the `Nats-Msg-Id` header
+    /// keeps a message the server did receive from being stored twice.
+    async fn publish(
+        &self,
+        subject: String,
+        headers: HeaderMap,
+        payload: Bytes,
+    ) -> std::result::Result<PublishAck, PublishError> {
+        let mut attempt = 0;
+        loop {
+            let result = match self
+                .jetstream
+                .publish_with_headers(subject.clone(), headers.clone(), payload.clone())
+                .await
+            {
+                Ok(ack) => ack.await,
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Err(e)
+                    if attempt < self.config.publish_retries
+                        && matches!(
+                            e.kind(),
+                            PublishErrorKind::TimedOut | PublishErrorKind::BrokenPipe
+                        ) =>
+                {
+                    attempt += 1;
+                    warn!("Retrying publish to {} ({}): {}", subject, attempt, e);
+                    tokio::time::sleep(RETRY_BACKOFF * attempt).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Stream sequence of the last event matching `filter`, 0 if there is none
+    async fn last_sequence(&self, filter: &str) -> Result<u64> {
+        match self.stream.get_last_raw_message_by_subject(filter).await {
+            Ok(message) => Ok(message.sequence),
+            Err(e) if e.kind() == LastRawMessageErrorKind::NoMessageFound => Ok(0),
+            Err(e) => Err(NatsError::Other(format!(
+                "Failed to get last aggregate event: {e}"
+            ))),
+        }
+    }
+
+    /// Number of events matching `filter`
+    async fn count_events(&self, filter: &str) -> Result<u64> {
+        let consumer: Consumer<async_nats::jetstream::consumer::pull::Config> = self
+            .stream
+            .create_consumer(async_nats::jetstream::consumer::pull::Config {
+                filter_subject: filter.to_string(),
+                ack_policy: async_nats::jetstream::consumer::AckPolicy::None,
+                inactive_threshold: Duration::from_secs(30),
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| NatsError::Other(format!("Failed to create consumer: {}", e)))?;
+
+        Ok(consumer.cached_info().num_pending)
     }
 
     /// Load all events for an aggregate from JetStream
     ///
-    /// Uses JetStream's replay capability to load all events for a specific aggregate.
-    /// Events are filtered by checking the aggregate ID in the event metadata.
+    /// Replays the aggregate's subjects through an ephemeral consumer, so the
+    /// server only delivers the events of this aggregate. The replay stops at
+    /// the last event stored when the consumer was created. An event that
+    /// cannot be decoded fails the replay with
+    /// [`NatsError::DeserializationError`] naming its stream sequence.
     pub async fn load_aggregate_events(
         &self,
         aggregate_id: &RepositoryId,
     ) -> Result<Vec<EventEnvelope>> {
-        // Use ephemeral consumer to replay all events
-        let _consumer_name = format!("aggregate_replay_{}", aggregate_id);
-        let filter = format!("{}.event.>", super::subject::DOMAIN);
+        let events = self
+            .replay_aggregate(
+                aggregate_id,
+                async_nats::jetstream::consumer::DeliverPolicy::All,
+            )
+            .await?;
+
+        Ok(events.into_iter().map(|event| event.envelope).collect())
+    }
+
+    /// Load the events of an aggregate stored after a stream sequence
+    ///
+    /// Used to replay the events a snapshot does not cover; each event comes
+    /// with its stream sequence.
+    pub async fn load_aggregate_events_after(
+        &self,
+        aggregate_id: &RepositoryId,
+        sequence: u64,
+    ) -> Result<Vec<StoredEvent>> {
+        self.replay_aggregate(
+            aggregate_id,
+            async_nats::jetstream::consumer::DeliverPolicy::ByStartSequence {
+                start_sequence: sequence + 1,
+            },
+        )
+        .await
+    }
+
+    /// Replay the aggregate's subjects up to the current end of the stream
+    async fn replay_aggregate(
+        &self,
+        aggregate_id: &RepositoryId,
+        deliver_policy: async_nats::jetstream::consumer::DeliverPolicy,
+    ) -> Result<Vec<StoredEvent>> {
+        let filter = GitSubject::aggregate_events(aggregate_id);
 
-        // Create ephemeral consumer that starts from the beginning
         let consumer: Consumer<async_nats::jetstream::consumer::pull::Config> = self
             .stream
             .create_consumer(async_nats::jetstream::consumer::pull::Config {
                 filter_subject: filter,
-                deliver_policy: async_nats::jetstream::consumer::DeliverPolicy::All,
+                deliver_policy,
                 ack_policy: async_nats::jetstream::consumer::AckPolicy::None,
+                inactive_threshold: Duration::from_secs(30),
                 ..Default::default()
             })
             .await
             .map_err(|e| NatsError::Other(format!("Failed to create consumer: {}", e)))?;
 
         let mut events = Vec::new();
+        if consumer.cached_info().num_pending == 0 {
+            return Ok(events);
+        }
+
         let mut messages = consumer
             .messages()
             .await
             .map_err(|e| NatsError::Other(format!("Failed to get messages: {}", e)))?;
 
-        while let Some(Ok(message)) = messages.next().await {
-            // Parse the envelope
-            if let Ok(envelope) = serde_json::from_slice::<EventEnvelope>(&message.payload) {
-                // Check if it belongs to our aggregate
-                if envelope.aggregate_id() == aggregate_id.to_string() {
-                    events.push(envelope);
-                }
+        while let Some(message) = messages.next().await {
+            let message =
+                message.map_err(|e| NatsError::Other(format!("Failed to replay events: {e}")))?;
+            let info = message
+                .info()
+                .map_err(|e| NatsError::Other(format!("Invalid JetStream message: {e}")))?;
+            let (sequence, pending) = (info.stream_sequence, info.pending);
+
+            // Skipping an event would rebuild the wrong state at the wrong version
+            let envelope = self.upcasters.decode(&message.payload).map_err(|e| {
+                NatsError::DeserializationError(format!(
+                    "Event at stream sequence {sequence} on {}: {e}",
+                    message.subject
+                ))
+            })?;
+            events.push(StoredEvent { sequence, envelope });
+
+            // Nothing left behind this message: the replay reached the tail
+            if pending == 0 {
+                break;
             }
         }
 
@@ -198,6 +532,75 @@ impl EventStore {
         Ok(events)
     }
 
+    /// Move events published without a repository ID onto their aggregate's
+    /// subjects
+    ///
+    /// Events stored on `git.event.{aggregate}.{action}` before subjects
+    /// carried the repository ID are not replayed by
+    /// [`EventStore::load_aggregate_events`]. Each is published again on its
+    /// aggregate's subject in stream order and then deleted, so a migration
+    /// that was interrupted can simply be run again. Moved events get new
+    /// stream sequences, so run it before writers using the new subjects
+    /// append to the same aggregates. Returns the number of events moved.
+    pub async fn migrate_unscoped_events(&mut self) -> Result<u64> {
+        let consumer: Consumer<async_nats::jetstream::consumer::pull::Config> = self
+            .stream
+            .create_consumer(async_nats::jetstream::consumer::pull::Config {
+                filter_subject: GitSubject::unscoped_events(),
+                ack_policy: async_nats::jetstream::consumer::AckPolicy::None,
+                inactive_threshold: Duration::from_secs(30),
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| NatsError::Other(format!("Failed to create consumer: {}", e)))?;
+        if consumer.cached_info().num_pending == 0 {
+            return Ok(0);
+        }
+
+        let mut messages = consumer
+            .messages()
+            .await
+            .map_err(|e| NatsError::Other(format!("Failed to get messages: {}", e)))?;
+        let mut moved = 0;
+        while let Some(message) = messages.next().await {
+            let message =
+                message.map_err(|e| NatsError::Other(format!("Failed to read events: {e}")))?;
+            let info = message
+                .info()
+                .map_err(|e| NatsError::Other(format!("Invalid JetStream message: {e}")))?;
+            let (sequence, pending) = (info.stream_sequence, info.pending);
+
+            let envelope = self.upcasters.decode(&message.payload).map_err(|e| {
+                NatsError::DeserializationError(format!(
+                    "Event at stream sequence {sequence} on {}: {e}",
+                    message.subject
+                ))
+            })?;
+            let (subject, mut headers, payload) = self.publisher.envelope_message(&envelope)?;
+            // The original ID may still be in the duplicate window, while a
+            // copy left behind by an interrupted migration must be dropped
+            headers.insert(
+                header::NATS_MESSAGE_ID,
+                format!("{}-migrated", envelope.event_id()),
+            );
+            self.publish(subject, headers, payload)
+                .await
+                .map_err(|e| NatsError::PublishError(e.to_string()))?;
+            self.stream
+                .delete_message(sequence)
+                .await
+                .map_err(|e| NatsError::Other(format!("Failed to delete event {sequence}: {e}")))?;
+            moved += 1;
+
+            if pending == 0 {
+                break;
+            }
+        }
+
+        info!("Moved {} events onto aggregate subjects", moved);
+        Ok(moved)
+    }
+
     /// Load events by correlation ID
     pub async fn load_by_correlation(&self, correlation_id: Uuid) -> Result<Vec<EventEnvelope>> {
         let filter = format!("{}.event.>", super::subject::DOMAIN);
@@ -226,9 +629,7 @@ impl EventStore {
             if let Some(headers) = &message.headers {
                 if let Some(corr_id) = headers.get("X-Correlation-ID") {
                     if corr_id.as_str() == correlation_id.to_string() {
-                        if let Ok(envelope) =
-                            serde_json::from_slice::<EventEnvelope>(&message.payload)
-                        {
+                        if let Ok(envelope) = self.upcasters.decode(&message.payload) {
                             events.push(envelope);
                         }
                     }
@@ -276,8 +677,7 @@ impl EventStore {
         while events.len() < max_events {
             match messages.next().await {
                 Some(Ok(message)) => {
-                    if let Ok(envelope) = serde_json::from_slice::<EventEnvelope>(&message.payload)
-                    {
+                    if let Ok(envelope) = self.upcasters.decode(&message.payload) {
                         events.push(envelope);
                     }
                 }
@@ -338,6 +738,59 @@ impl EventStore {
     }
 }
 
+/// Last sequence the server reported when rejecting a publish, read from an
+/// error like `wrong last sequence: 42 (code 400, error code 10071)`
+fn reported_last_sequence(error: &str) -> Option<u64> {
+    let (_, rest) = error.rsplit_once("wrong last sequence: ")?;
+    let digits = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    rest[..digits].parse().ok()
+}
+
+/// Whether a server `version` such as `2.11.3` honours
+/// [`EXPECTED_LAST_SUBJECT_SEQUENCE_SUBJECT`]
+fn supports_subject_sequence_filter(version: &str) -> bool {
+    let mut parts = version.split(['.', '-']).map(str::parse::<u64>);
+    match (parts.next(), parts.next()) {
+        (Some(Ok(major)), Some(Ok(minor))) => (major, minor) >= SUBJECT_SEQUENCE_FILTER_VERSION,
+        _ => false,
+    }
+}
+
+/// An event stored by a batch append
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppendedEvent {
+    /// Event ID, also the `Nats-Msg-Id` it was published with
+    pub event_id: Uuid,
+
+    /// Stream sequence of the event
+    pub sequence: u64,
+
+    /// Whether the event was already stored by an earlier append
+    pub duplicate: bool,
+}
+
+impl AppendedEvent {
+    fn new(envelope: &EventEnvelope, ack: &PublishAck) -> Self {
+        Self {
+            event_id: envelope.event_id(),
+            sequence: ack.sequence,
+            duplicate: ack.duplicate,
+        }
+    }
+}
+
+/// An event read back from the stream
+#[derive(Debug, Clone)]
+pub struct StoredEvent {
+    /// Stream sequence of the event
+    pub sequence: u64,
+
+    /// The event
+    pub envelope: EventEnvelope,
+}
+
 /// Consumer position tracking
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConsumerPosition {
@@ -377,8 +830,10 @@ pub struct StreamInfo {
 mod tests {
     use super::*;
     use crate::aggregate::RepositoryId;
-    use crate::events::{EventEnvelopeBuilder, GitDomainEvent, RepositoryCloned};
-    use crate::value_objects::RemoteUrl;
+    use crate::events::{
+        BranchCreated, CommitAnalyzed, EventEnvelopeBuilder, GitDomainEvent, RepositoryCloned,
+    };
+    use crate::value_objects::{AuthorInfo, BranchName, CommitHash, RemoteUrl};
 
     #[tokio::test]
     #[ignore = "requires NATS server with JetStream"]
@@ -415,4 +870,265 @@ mod tests {
         assert_eq!(events.len(), 1);
         assert_eq!(events[0].event_id(), envelope.event_id());
     }
+
+    #[tokio::test]
+    #[ignore = "requires NATS server with JetStream"]
+    async fn test_event_store_rejects_stale_sequence() {
+        let client = async_nats::connect("nats://localhost:4222").await.unwrap();
+        let jetstream = async_nats::jetstream::new(client.clone());
+        let publisher = EventPublisher::new(client, "git".to_string());
+
+        let config = EventStoreConfig {
+            stream_name: "TEST_GIT_EVENTS_SEQUENCE".to_string(),
+            ..Default::default()
+        };
+
+        let mut store = EventStore::new(jetstream, publisher, config).await.unwrap();
+
+        let repo_id = RepositoryId::new();
+        let cloned = || {
+            EventEnvelope::new(GitDomainEvent::RepositoryCloned(RepositoryCloned {
+                repository_id: repo_id,
+                remote_url: RemoteUrl::new("https://github.com/test/repo.git").unwrap(),
+                local_path: "/tmp/test".to_string(),
+                timestamp: chrono::Utc::now(),
+            }))
+        };
+
+        let first = store.append_expected(&cloned(), 0).await.unwrap();
+        let second = store.append_expected(&cloned(), first).await.unwrap();
+        assert!(second > first);
+
+        let stale = store.append_expected(&cloned(), first).await;
+        assert!(matches!(
+            stale,
+            Err(NatsError::ConcurrencyConflict {
+                expected,
+                actual,
+                ..
+            }) if expected == first && actual == second
+        ));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires NATS server 2.11+ with JetStream"]
+    async fn test_event_store_expected_sequence_spans_event_types() {
+        let client = async_nats::connect("nats://localhost:4222").await.unwrap();
+        let jetstream = async_nats::jetstream::new(client.clone());
+        let publisher = EventPublisher::new(client, "git".to_string());
+
+        let config = EventStoreConfig {
+            stream_name: "TEST_GIT_EVENTS_SEQUENCE_TYPES".to_string(),
+            ..Default::default()
+        };
+
+        let mut store = EventStore::new(jetstream, publisher, config).await.unwrap();
+
+        let repo_id = RepositoryId::new();
+        let head = CommitHash::new("a".repeat(40)).unwrap();
+        let cloned = EventEnvelope::new(GitDomainEvent::RepositoryCloned(RepositoryCloned {
+            repository_id: repo_id,
+            remote_url: RemoteUrl::new("https://github.com/test/repo.git").unwrap(),
+            local_path: "/tmp/test".to_string(),
+            timestamp: chrono::Utc::now(),
+        }));
+        let branch = || {
+            EventEnvelope::new(GitDomainEvent::BranchCreated(BranchCreated {
+                repository_id: repo_id,
+                branch_name: BranchName::new("main").unwrap(),
+                commit_hash: head.clone(),
+                source_branch: None,
+                checked_out: true,
+                timestamp: chrono::Utc::now(),
+            }))
+        };
+        let commit = EventEnvelope::new(GitDomainEvent::CommitAnalyzed(CommitAnalyzed {
+            repository_id: repo_id,
+            commit_hash: head.clone(),
+            parents: Vec::new(),
+            author: AuthorInfo::new("Alice", "alice@example.com"),
+            raw_author: None,
+            message: "Initial commit".to_string(),
+            parsed_message: Default::default(),
+            signature: None,
+            files_changed: Vec::new(),
+            parent_diffs: Vec::new(),
+            combined_files: Vec::new(),
+            commit_timestamp: chrono::Utc::now(),
+            timestamp: chrono::Utc::now(),
+        }));
+
+        // Each event follows one on another subject of the same aggregate
+        let first = store.append_expected(&cloned, 0).await.unwrap();
+        let second = store.append_expected(&branch(), first).await.unwrap();
+        let third = store.append_expected(&commit, second).await.unwrap();
+        assert!(first < second && second < third);
+
+        let stale = store.append_expected(&branch(), second).await;
+        assert!(matches!(
+            stale,
+            Err(NatsError::ConcurrencyConflict {
+                expected,
+                actual,
+                ..
+            }) if expected == second && actual == third
+        ));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires NATS server 2.11+ with JetStream"]
+    async fn test_event_store_rejects_stale_version() {
+        let client = async_nats::connect("nats://localhost:4222").await.unwrap();
+        let jetstream = async_nats::jetstream::new(client.clone());
+        let publisher = EventPublisher::new(client, "git".to_string());
+
+        let config = EventStoreConfig {
+            stream_name: "TEST_GIT_EVENTS_VERSION".to_string(),
+            ..Default::default()
+        };
+
+        let mut store = EventStore::new(jetstream, publisher, config).await.unwrap();
+
+        let mut repository = crate::aggregate::Repository::new("repo".to_string());
+        let cloned = repository
+            .clone_repository(
+                RemoteUrl::new("https://github.com/test/repo.git").unwrap(),
+                "/tmp/test".to_string(),
+            )
+            .unwrap();
+        let envelopes: Vec<_> = cloned.into_iter().map(EventEnvelope::new).collect();
+        store.append_batch_versioned(&envelopes, 0).await.unwrap();
+
+        let branch = repository
+            .create_branch(
+                BranchName::new("feature").unwrap(),
+                CommitHash::new("a".repeat(40)).unwrap(),
+                None,
+                false,
+            )
+            .unwrap();
+        let branch = EventEnvelope::new(branch[0].clone());
+        assert_eq!(repository.version, 2);
+        store.append_versioned(&branch, 1).await.unwrap();
+
+        let stale = store.append_versioned(&branch, 1).await;
+        assert!(matches!(
+            stale,
+            Err(NatsError::VersionConflict {
+                expected: 1,
+                actual: 2,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_reported_last_sequence() {
+        assert_eq!(
+            reported_last_sequence(
+                "wrong last sequence: wrong last sequence: 42 (code 400, error code 10071)"
+            ),
+            Some(42)
+        );
+        assert_eq!(reported_last_sequence("wrong last sequence"), None);
+        assert_eq!(reported_last_sequence("timed out"), None);
+    }
+
+    #[test]
+    fn test_supports_subject_sequence_filter() {
+        assert!(supports_subject_sequence_filter("2.11.0"));
+        assert!(supports_subject_sequence_filter("2.12.1-RC.2"));
+        assert!(supports_subject_sequence_filter("3.0.0"));
+        assert!(!supports_subject_sequence_filter("2.10.22"));
+        assert!(!supports_subject_sequence_filter("2.9.0"));
+        assert!(!supports_subject_sequence_filter(""));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires NATS server with JetStream"]
+    async fn test_event_store_replay_fails_on_undecodable_event() {
+        let client = async_nats::connect("nats://localhost:4222").await.unwrap();
+        let jetstream = async_nats::jetstream::new(client.clone());
+        let publisher = EventPublisher::new(client, "git".to_string());
+
+        let config = EventStoreConfig {
+            stream_name: "TEST_GIT_EVENTS_UNDECODABLE".to_string(),
+            ..Default::default()
+        };
+
+        let mut store = EventStore::new(jetstream.clone(), publisher, config)
+            .await
+            .unwrap();
+
+        let repo_id = RepositoryId::new();
+        store
+            .append(&EventEnvelope::new(GitDomainEvent::RepositoryCloned(
+                RepositoryCloned {
+                    repository_id: repo_id,
+                    remote_url: RemoteUrl::new("https://github.com/test/repo.git").unwrap(),
+                    local_path: "/tmp/test".to_string(),
+                    timestamp: chrono::Utc::now(),
+                },
+            )))
+            .await
+            .unwrap();
+        let corrupt = jetstream
+            .publish(
+                format!("git.event.repository.cloned.{repo_id}"),
+                "not an event".into(),
+            )
+            .await
+            .unwrap()
+            .await
+            .unwrap();
+
+        let replay = store.load_aggregate_events(&repo_id).await;
+        assert!(matches!(
+            replay,
+            Err(NatsError::DeserializationError(message))
+                if message.contains(&corrupt.sequence.to_string())
+        ));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires NATS server with JetStream"]
+    async fn test_event_store_resubmitted_batch_is_deduplicated() {
+        let client = async_nats::connect("nats://localhost:4222").await.unwrap();
+        let jetstream = async_nats::jetstream::new(client.clone());
+        let publisher = EventPublisher::new(client, "git".to_string());
+
+        let config = EventStoreConfig {
+            stream_name: "TEST_GIT_EVENTS_DEDUP".to_string(),
+            ..Default::default()
+        };
+
+        let mut store = EventStore::new(jetstream, publisher, config).await.unwrap();
+
+        let repo_id = RepositoryId::new();
+        let batch: Vec<EventEnvelope> = (0..3)
+            .map(|i| {
+                EventEnvelope::new(GitDomainEvent::RepositoryCloned(RepositoryCloned {
+                    repository_id: repo_id,
+                    remote_url: RemoteUrl::new("https://github.com/test/repo.git").unwrap(),
+                    local_path: format!("/tmp/test-{i}"),
+                    timestamp: chrono::Utc::now(),
+                }))
+            })
+            .collect();
+
+        let first = store.append_batch(&batch[..2]).await.unwrap();
+        assert!(first.iter().all(|appended| !appended.duplicate));
+
+        // Resubmitting the whole batch only stores the missing event
+        let retried = store.append_batch(&batch).await.unwrap();
+        assert_eq!(retried.len(), 3);
+        for (retry, original) in retried.iter().zip(&first) {
+            assert!(retry.duplicate);
+            assert_eq!(retry.sequence, original.sequence);
+        }
+        assert!(!retried[2].duplicate);
+
+        let events = store.load_aggregate_events(&repo_id).await.unwrap();
+        assert_eq!(events.len(), 3);
+    }
 }
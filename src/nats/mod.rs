@@ -13,6 +13,7 @@ pub mod event_store;
 pub mod health;
 pub mod projection;
 pub mod publisher;
+pub mod snapshot;
 pub mod subject;
 pub mod subscriber;
 
@@ -30,10 +31,13 @@ pub use client::NatsClient;
 pub use command_ack::{AckPublisher, AckStatus, AckSubscriber, CommandAck, CommandTracker};
 pub use config::{NatsAuth, NatsConfig, NatsTls};
 pub use error::{NatsError, Result};
-pub use event_store::{ConsumerPosition, EventStore, EventStoreConfig, StreamInfo};
+pub use event_store::{
+    AppendedEvent, ConsumerPosition, EventStore, EventStoreConfig, StoredEvent, StreamInfo,
+};
 pub use health::{HealthService, ServiceDiscovery, ServiceInfo, ServiceStatus};
 pub use projection::{Projection, ProjectionManager, ProjectionStatus, RepositoryStatsProjection};
 pub use publisher::{EventPublisher, EventPublishing};
+pub use snapshot::{Snapshot, SnapshotConfig, SnapshotStore, SNAPSHOT_SCHEMA_VERSION};
 pub use subject::{Aggregate, CommandAction, EventAction, GitSubject, QueryAction, SubjectMapper};
 pub use subscriber::{CommandHandler, CommandSubscriber, EventHandler, EventSubscriber};
 // pub use tracing::{TracingConfig, TracingManager, TraceContext, TracedCommand, TracedEvent};
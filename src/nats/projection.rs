@@ -19,7 +19,7 @@ use super::{
     error::{NatsError, Result},
     event_store::EventStore,
 };
-use crate::events::{EventEnvelope, GitDomainEvent};
+use crate::events::{EventEnvelope, GitDomainEvent, UpcasterRegistry};
 
 /// Trait for projections that process events to build read models
 #[async_trait]
@@ -109,10 +109,13 @@ impl ProjectionManager {
         let projections = self.projections.clone();
         let projection_name = name.to_string();
         let running_states = self.running_states.clone();
+        let upcasters = self.event_store.upcasters();
 
         // Spawn task to process events
         tokio::spawn(async move {
-            let result = Self::process_events(consumer, projections, projection_name.clone()).await;
+            let result =
+                Self::process_events(consumer, projections, projection_name.clone(), upcasters)
+                    .await;
 
             // Mark as stopped when done
             let mut states = running_states.write().await;
@@ -132,6 +135,7 @@ impl ProjectionManager {
         consumer: Consumer<async_nats::jetstream::consumer::pull::Config>,
         projections: Arc<RwLock<HashMap<String, Box<dyn Projection>>>>,
         projection_name: String,
+        upcasters: Arc<UpcasterRegistry>,
     ) -> Result<()> {
         let mut messages = consumer
             .messages()
@@ -144,8 +148,8 @@ impl ProjectionManager {
                 .map_err(|e| NatsError::Other(format!("Failed to get message info: {}", e)))?
                 .stream_sequence;
 
-            // Parse the event envelope
-            match serde_json::from_slice::<EventEnvelope>(&message.payload) {
+            // Parse the event envelope, upcast to the current schema
+            match upcasters.decode(&message.payload) {
                 Ok(envelope) => {
                     let event_type = envelope.event_type();
 
@@ -357,6 +361,9 @@ impl Projection for RepositoryStatsProjection {
             GitDomainEvent::TagCreated(_) => {
                 repo_stats.tag_count += 1;
             }
+            GitDomainEvent::TagDeleted(_) => {
+                repo_stats.tag_count = repo_stats.tag_count.saturating_sub(1);
+            }
             GitDomainEvent::FileAnalyzed(_) => {
                 repo_stats.total_files_analyzed += 1;
             }
@@ -379,7 +386,12 @@ impl Projection for RepositoryStatsProjection {
     fn handles_event_type(&self, event_type: &str) -> bool {
         matches!(
             event_type,
-            "CommitAnalyzed" | "BranchCreated" | "BranchDeleted" | "TagCreated" | "FileAnalyzed"
+            "CommitAnalyzed"
+                | "BranchCreated"
+                | "BranchDeleted"
+                | "TagCreated"
+                | "TagDeleted"
+                | "FileAnalyzed"
         )
     }
 }
@@ -405,8 +417,13 @@ mod tests {
                 name: "Test Author".to_string(),
                 email: "test@example.com".to_string(),
             },
+            raw_author: None,
             message: "Test commit".to_string(),
+            parsed_message: Default::default(),
+            signature: None,
             files_changed: vec![],
+            parent_diffs: vec![],
+            combined_files: vec![],
             commit_timestamp: Utc::now(),
             timestamp: Utc::now(),
         });
@@ -2,7 +2,7 @@
 
 //! Event publisher for the Git domain
 
-use async_nats::{Client, HeaderMap};
+use async_nats::{header, Client, HeaderMap};
 use bytes::Bytes;
 use chrono::{DateTime, Utc};
 use serde::Serialize;
@@ -55,6 +55,11 @@ impl EventPublisher {
         }
     }
 
+    /// NATS client events are published with
+    pub(crate) fn client(&self) -> &Client {
+        &self.client
+    }
+
     /// Publish a domain event
     pub async fn publish_event(&self, event: &GitDomainEvent) -> Result<()> {
         // Get event metadata based on the event type
@@ -65,6 +70,12 @@ impl EventPublisher {
                 e.repository_id.to_string(),
                 e.timestamp,
             ),
+            GitDomainEvent::CloneProgressUpdated(e) => (
+                "CloneProgressUpdated",
+                Uuid::new_v4(),
+                e.repository_id.to_string(),
+                e.timestamp,
+            ),
             GitDomainEvent::CommitAnalyzed(e) => (
                 "CommitAnalyzed",
                 Uuid::new_v4(),
@@ -83,12 +94,48 @@ impl EventPublisher {
                 e.repository_id.to_string(),
                 e.timestamp,
             ),
+            GitDomainEvent::BranchMoved(e) => (
+                "BranchMoved",
+                Uuid::new_v4(),
+                e.repository_id.to_string(),
+                e.timestamp,
+            ),
+            GitDomainEvent::BranchesCompared(e) => (
+                "BranchesCompared",
+                Uuid::new_v4(),
+                e.repository_id.to_string(),
+                e.timestamp,
+            ),
             GitDomainEvent::TagCreated(e) => (
                 "TagCreated",
                 Uuid::new_v4(),
                 e.repository_id.to_string(),
                 e.timestamp,
             ),
+            GitDomainEvent::TagDeleted(e) => (
+                "TagDeleted",
+                Uuid::new_v4(),
+                e.repository_id.to_string(),
+                e.timestamp,
+            ),
+            GitDomainEvent::ReleaseNotesGenerated(e) => (
+                "ReleaseNotesGenerated",
+                Uuid::new_v4(),
+                e.repository_id.to_string(),
+                e.timestamp,
+            ),
+            GitDomainEvent::SignaturePolicyChecked(e) => (
+                "SignaturePolicyChecked",
+                Uuid::new_v4(),
+                e.repository_id.to_string(),
+                e.timestamp,
+            ),
+            GitDomainEvent::RemoteFetched(e) => (
+                "RemoteFetched",
+                Uuid::new_v4(),
+                e.repository_id.to_string(),
+                e.timestamp,
+            ),
             GitDomainEvent::RepositoryMetadataUpdated(e) => (
                 "RepositoryMetadataUpdated",
                 Uuid::new_v4(),
@@ -101,12 +148,24 @@ impl EventPublisher {
                 e.repository_id.to_string(),
                 e.timestamp,
             ),
+            GitDomainEvent::CommitRelationshipDetected(e) => (
+                "CommitRelationshipDetected",
+                Uuid::new_v4(),
+                e.repository_id.to_string(),
+                e.timestamp,
+            ),
             GitDomainEvent::FileAnalyzed(e) => (
                 "FileAnalyzed",
                 Uuid::new_v4(),
                 e.repository_id.to_string(),
                 e.timestamp,
             ),
+            GitDomainEvent::FileHistoryAnalyzed(e) => (
+                "FileHistoryAnalyzed",
+                Uuid::new_v4(),
+                e.repository_id.to_string(),
+                e.timestamp,
+            ),
             GitDomainEvent::RepositoryAnalyzed(e) => (
                 "RepositoryAnalyzed",
                 Uuid::new_v4(),
@@ -116,9 +175,10 @@ impl EventPublisher {
         };
 
         // Map to NATS subject
-        let subject = SubjectMapper::event_subject(event_type).ok_or_else(|| {
-            NatsError::InvalidSubject(format!("Unknown event type: {}", event_type))
-        })?;
+        let subject = SubjectMapper::aggregate_event_subject(event_type, &aggregate_id)
+            .ok_or_else(|| {
+                NatsError::InvalidSubject(format!("Unknown event type: {}", event_type))
+            })?;
 
         let subject_str = subject.to_string();
         debug!("Publishing event {} to subject {}", event_type, subject_str);
@@ -158,12 +218,41 @@ impl EventPublisher {
 
     /// Publish an event envelope with full metadata
     pub async fn publish_envelope(&self, envelope: &EventEnvelope) -> Result<()> {
+        let (subject, headers, payload) = self.envelope_message(envelope)?;
+
+        // Publish with headers
+        let result = self
+            .client
+            .publish_with_headers(subject, headers, payload)
+            .await
+            .map_err(|e| NatsError::PublishError(e.to_string()));
+
+        if result.is_ok() {
+            info!(
+                "Published event {} with ID {} (correlation: {}, causation: {})",
+                envelope.event_type(),
+                envelope.event_id(),
+                envelope.correlation_id(),
+                envelope.causation_id()
+            );
+        }
+
+        result
+    }
+
+    /// Build the subject, headers and payload an envelope is published with
+    pub(crate) fn envelope_message(
+        &self,
+        envelope: &EventEnvelope,
+    ) -> Result<(String, HeaderMap, Bytes)> {
         let event_type = envelope.event_type();
+        let aggregate_id = envelope.aggregate_id();
 
         // Map to NATS subject
-        let subject = SubjectMapper::event_subject(event_type).ok_or_else(|| {
-            NatsError::InvalidSubject(format!("Unknown event type: {}", event_type))
-        })?;
+        let subject = SubjectMapper::aggregate_event_subject(event_type, &aggregate_id)
+            .ok_or_else(|| {
+                NatsError::InvalidSubject(format!("Unknown event type: {}", event_type))
+            })?;
 
         let subject_str = subject.to_string();
         debug!("Publishing event {} to subject {}", event_type, subject_str);
@@ -171,10 +260,12 @@ impl EventPublisher {
         // Create headers with event metadata
         let mut headers = HeaderMap::new();
         headers.insert("X-Event-ID", envelope.event_id().to_string());
+        // Lets JetStream drop the event if it is published again
+        headers.insert(header::NATS_MESSAGE_ID, envelope.event_id().to_string());
         headers.insert("X-Event-Type", event_type.to_string());
         headers.insert("X-Correlation-ID", envelope.correlation_id().to_string());
         headers.insert("X-Causation-ID", envelope.causation_id().to_string());
-        headers.insert("X-Aggregate-ID", envelope.aggregate_id());
+        headers.insert("X-Aggregate-ID", aggregate_id);
         headers.insert("X-Timestamp", envelope.occurred_at().to_rfc3339());
         headers.insert("X-Domain", self.subject_prefix.clone());
         headers.insert(
@@ -190,24 +281,7 @@ impl EventPublisher {
         let payload = serde_json::to_vec(&envelope)
             .map_err(|e| NatsError::SerializationError(e.to_string()))?;
 
-        // Publish with headers
-        let result = self
-            .client
-            .publish_with_headers(subject_str.clone(), headers, Bytes::from(payload))
-            .await
-            .map_err(|e| NatsError::PublishError(e.to_string()));
-
-        if result.is_ok() {
-            info!(
-                "Published event {} with ID {} (correlation: {}, causation: {})",
-                event_type,
-                envelope.event_id(),
-                envelope.correlation_id(),
-                envelope.causation_id()
-            );
-        }
-
-        result
+        Ok((subject_str, headers, Bytes::from(payload)))
     }
 
     /// Publish multiple event envelopes
@@ -218,14 +292,15 @@ impl EventPublisher {
         Ok(())
     }
 
-    /// Publish a raw event (for special cases)
+    /// Publish a raw event of a repository (for special cases)
     pub async fn publish_raw(
         &self,
         action: EventAction,
+        repository_id: &RepositoryId,
         headers: HeaderMap,
         payload: Bytes,
     ) -> Result<()> {
-        let subject = GitSubject::event(action);
+        let subject = GitSubject::event(action).for_aggregate(repository_id);
         let subject_str = subject.to_string();
 
         self.client
@@ -243,9 +318,11 @@ impl EventPublisher {
         repository_id: &RepositoryId,
         data: serde_json::Value,
     ) -> Result<()> {
-        let subject = SubjectMapper::event_subject(event_type).ok_or_else(|| {
-            NatsError::InvalidSubject(format!("Unknown event type: {}", event_type))
-        })?;
+        let subject =
+            SubjectMapper::aggregate_event_subject(event_type, &repository_id.to_string())
+                .ok_or_else(|| {
+                    NatsError::InvalidSubject(format!("Unknown event type: {}", event_type))
+                })?;
 
         let subject_str = subject.to_string();
 
@@ -283,6 +360,17 @@ mod tests {
         assert_eq!(subject.unwrap().to_string(), "git.event.repository.cloned");
     }
 
+    #[test]
+    fn test_aggregate_event_subject_mapping() {
+        let repository_id = RepositoryId::new();
+        let subject =
+            SubjectMapper::aggregate_event_subject("TagCreated", &repository_id.to_string());
+        assert_eq!(
+            subject.unwrap().to_string(),
+            format!("git.event.tag.created.{repository_id}")
+        );
+    }
+
     #[test]
     fn test_unknown_event_type() {
         let subject = SubjectMapper::event_subject("UnknownEvent");
@@ -0,0 +1,338 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! Repository snapshots in a NATS key-value bucket
+//!
+//! Rebuilding a repository from its events replays every commit it analyzed.
+//! A snapshot stores the aggregate state together with the stream sequence of
+//! the last event it covers, so a rebuild only replays the events after it.
+//! Snapshots carry a schema version; one written with another version is
+//! ignored and the aggregate is rebuilt from its full history.
+
+use async_nats::jetstream::kv::{Config as KvConfig, Store};
+use async_nats::jetstream::stream::StorageType;
+use async_nats::jetstream::Context as JetStreamContext;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use super::error::{NatsError, Result};
+use super::event_store::EventStore;
+use crate::aggregate::{Repository, RepositoryId};
+
+/// Schema version of the snapshots written by this version of the crate
+///
+/// Raise it whenever a change to [`Repository`] means older snapshots no
+/// longer describe the state their events produce.
+pub const SNAPSHOT_SCHEMA_VERSION: u32 = 1;
+
+/// Snapshot store configuration
+#[derive(Debug, Clone)]
+pub struct SnapshotConfig {
+    /// Key-value bucket holding the snapshots
+    pub bucket: String,
+
+    /// Number of events replayed after a snapshot before a new one is taken
+    pub interval: u64,
+
+    /// Number of replicas
+    pub num_replicas: usize,
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            bucket: "GIT_SNAPSHOTS".to_string(),
+            interval: 500,
+            num_replicas: 1,
+        }
+    }
+}
+
+/// Repository state as of a stream sequence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Schema version the snapshot was written with
+    pub schema_version: u32,
+
+    /// Stream sequence of the last event applied to the state
+    pub sequence: u64,
+
+    /// When the snapshot was taken
+    pub taken_at: DateTime<Utc>,
+
+    /// Aggregate state
+    pub state: Repository,
+}
+
+impl Snapshot {
+    /// Snapshot a repository whose last applied event has stream sequence `sequence`
+    #[must_use]
+    pub fn new(state: Repository, sequence: u64) -> Self {
+        Self {
+            schema_version: SNAPSHOT_SCHEMA_VERSION,
+            sequence,
+            taken_at: Utc::now(),
+            state,
+        }
+    }
+
+    /// Decode a stored snapshot, discarding unreadable or outdated ones
+    fn decode(value: &[u8]) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct Header {
+            schema_version: u32,
+        }
+
+        let header: Header = serde_json::from_slice(value)
+            .map_err(|e| warn!("Ignoring unreadable snapshot: {}", e))
+            .ok()?;
+        if header.schema_version != SNAPSHOT_SCHEMA_VERSION {
+            info!(
+                "Ignoring snapshot with schema version {} (current {})",
+                header.schema_version, SNAPSHOT_SCHEMA_VERSION
+            );
+            return None;
+        }
+
+        serde_json::from_slice(value)
+            .map_err(|e| warn!("Ignoring unreadable snapshot: {}", e))
+            .ok()
+    }
+}
+
+/// Snapshot store for Repository aggregates
+pub struct SnapshotStore {
+    store: Store,
+    config: SnapshotConfig,
+}
+
+impl SnapshotStore {
+    /// Create a snapshot store, creating its bucket if needed
+    pub async fn new(jetstream: &JetStreamContext, config: SnapshotConfig) -> Result<Self> {
+        let store = if let Ok(store) = jetstream.get_key_value(&config.bucket).await {
+            info!("Using existing snapshot bucket: {}", config.bucket);
+            store
+        } else {
+            let store = jetstream
+                .create_key_value(KvConfig {
+                    bucket: config.bucket.clone(),
+                    description: "Git domain aggregate snapshots".to_string(),
+                    history: 1,
+                    storage: StorageType::File,
+                    num_replicas: config.num_replicas,
+                    ..Default::default()
+                })
+                .await
+                .map_err(|e| NatsError::Other(format!("Failed to create snapshot bucket: {e}")))?;
+            info!("Created snapshot bucket: {}", config.bucket);
+            store
+        };
+
+        Ok(Self { store, config })
+    }
+
+    /// Store a snapshot of a repository, replacing the previous one
+    pub async fn save(&self, repository: &Repository, sequence: u64) -> Result<()> {
+        let snapshot = Snapshot::new(repository.clone(), sequence);
+        let payload = serde_json::to_vec(&snapshot)?;
+
+        self.store
+            .put(snapshot_key(&repository.id), Bytes::from(payload))
+            .await
+            .map_err(|e| NatsError::Other(format!("Failed to store snapshot: {e}")))?;
+
+        debug!(
+            "Stored snapshot of repository {} at version {} (sequence {})",
+            repository.id, repository.version, sequence
+        );
+        Ok(())
+    }
+
+    /// Latest usable snapshot of a repository
+    pub async fn load(&self, repository_id: &RepositoryId) -> Result<Option<Snapshot>> {
+        let value = self
+            .store
+            .get(snapshot_key(repository_id))
+            .await
+            .map_err(|e| NatsError::Other(format!("Failed to load snapshot: {e}")))?;
+
+        Ok(value.and_then(|value| Snapshot::decode(&value)))
+    }
+
+    /// Remove the snapshot of a repository
+    pub async fn delete(&self, repository_id: &RepositoryId) -> Result<()> {
+        self.store
+            .delete(snapshot_key(repository_id))
+            .await
+            .map_err(|e| NatsError::Other(format!("Failed to delete snapshot: {e}")))
+    }
+
+    /// Rebuild a repository from its latest snapshot and the events after it
+    ///
+    /// Without a usable snapshot the whole history is replayed. A new
+    /// snapshot is stored once the replayed tail reaches the configured
+    /// interval. Returns `None` for a repository without events.
+    pub async fn load_repository(
+        &self,
+        event_store: &EventStore,
+        repository_id: &RepositoryId,
+    ) -> Result<Option<Repository>> {
+        let (mut repository, sequence) = if let Some(snapshot) = self.load(repository_id).await? {
+            (snapshot.state, snapshot.sequence)
+        } else {
+            // RepositoryAnalyzed restores the name
+            let mut repository = Repository::new(String::new());
+            repository.id = *repository_id;
+            (repository, 0)
+        };
+
+        let tail = event_store
+            .load_aggregate_events_after(repository_id, sequence)
+            .await?;
+        if sequence == 0 && tail.is_empty() {
+            return Ok(None);
+        }
+
+        for event in &tail {
+            repository
+                .apply_event(&event.envelope.event)
+                .map_err(|e| NatsError::Other(format!("Failed to apply event: {e}")))?;
+        }
+        debug!(
+            "Rebuilt repository {} from sequence {} with {} events",
+            repository_id,
+            sequence,
+            tail.len()
+        );
+
+        if let Some(last) = tail.last() {
+            if self.is_due(tail.len() as u64) {
+                self.save(&repository, last.sequence).await?;
+            }
+        }
+
+        Ok(Some(repository))
+    }
+
+    /// Whether replaying `events` events after a snapshot calls for a new one
+    #[must_use]
+    pub fn is_due(&self, events: u64) -> bool {
+        events >= self.config.interval.max(1)
+    }
+}
+
+/// Key of a repository's snapshot in the bucket
+fn snapshot_key(repository_id: &RepositoryId) -> String {
+    format!("repository.{repository_id}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{GitDomainEvent, RepositoryCloned};
+    use crate::value_objects::RemoteUrl;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut repository = Repository::new("snapshot-repo".to_string());
+        repository
+            .clone_repository(
+                RemoteUrl::new("https://github.com/test/repo.git").unwrap(),
+                "/tmp/snapshot-repo".to_string(),
+            )
+            .unwrap();
+
+        let payload = serde_json::to_vec(&Snapshot::new(repository.clone(), 42)).unwrap();
+        let snapshot = Snapshot::decode(&payload).unwrap();
+
+        assert_eq!(snapshot.sequence, 42);
+        assert_eq!(snapshot.state.id, repository.id);
+        assert_eq!(snapshot.state.version, 1);
+        assert_eq!(snapshot.state.local_path, repository.local_path);
+    }
+
+    #[test]
+    fn test_snapshot_with_other_schema_version_is_ignored() {
+        let mut snapshot = Snapshot::new(Repository::new("old".to_string()), 7);
+        snapshot.schema_version = SNAPSHOT_SCHEMA_VERSION + 1;
+        let payload = serde_json::to_vec(&snapshot).unwrap();
+
+        assert!(Snapshot::decode(&payload).is_none());
+        assert!(Snapshot::decode(b"not a snapshot").is_none());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires NATS server with JetStream"]
+    async fn test_load_repository_replays_tail_after_snapshot() {
+        use crate::events::EventEnvelope;
+        use crate::nats::{EventPublisher, EventStoreConfig};
+
+        let client = async_nats::connect("nats://localhost:4222").await.unwrap();
+        let jetstream = async_nats::jetstream::new(client.clone());
+        let publisher = EventPublisher::new(client, "git".to_string());
+
+        let mut event_store = EventStore::new(
+            jetstream.clone(),
+            publisher,
+            EventStoreConfig {
+                stream_name: "TEST_GIT_EVENTS_SNAPSHOT".to_string(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let snapshots = SnapshotStore::new(
+            &jetstream,
+            SnapshotConfig {
+                bucket: "TEST_GIT_SNAPSHOTS".to_string(),
+                interval: 2,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let repo_id = RepositoryId::new();
+        let cloned = |path: &str| {
+            EventEnvelope::new(GitDomainEvent::RepositoryCloned(RepositoryCloned {
+                repository_id: repo_id,
+                remote_url: RemoteUrl::new("https://github.com/test/repo.git").unwrap(),
+                local_path: path.to_string(),
+                timestamp: Utc::now(),
+            }))
+        };
+
+        assert!(snapshots
+            .load_repository(&event_store, &repo_id)
+            .await
+            .unwrap()
+            .is_none());
+
+        event_store.append(&cloned("/tmp/first")).await.unwrap();
+        let second = event_store.append(&cloned("/tmp/second")).await.unwrap();
+
+        // Two replayed events reach the interval and store a snapshot
+        let repository = snapshots
+            .load_repository(&event_store, &repo_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(repository.version, 2);
+        assert_eq!(
+            snapshots.load(&repo_id).await.unwrap().unwrap().sequence,
+            second
+        );
+
+        event_store.append(&cloned("/tmp/third")).await.unwrap();
+        let repository = snapshots
+            .load_repository(&event_store, &repo_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(repository.version, 3);
+        assert_eq!(repository.local_path.as_deref(), Some("/tmp/third"));
+
+        snapshots.delete(&repo_id).await.unwrap();
+    }
+}
@@ -4,7 +4,7 @@
 //!
 //! Defines all NATS subjects used by the Git domain following CIM conventions:
 //! - Commands: git.cmd.{aggregate}.{action}
-//! - Events: git.event.{aggregate}.{action}
+//! - Events: git.event.{aggregate}.{action}.{id}
 //! - Queries: git.query.{aggregate}.{action}
 
 use std::fmt;
@@ -159,18 +159,30 @@ pub enum EventAction {
     RepositoryDeleted,
     /// A repository was analyzed for metadata
     RepositoryAnalyzed,
+    /// Progress was made while cloning a repository
+    CloneProgressUpdated,
+    /// Release notes were generated for a release
+    ReleaseNotesGenerated,
+    /// Protected branches were checked for unsigned commits
+    SignaturePolicyChecked,
 
     // Commit events
     /// A commit was analyzed for metadata
     CommitAnalyzed,
+    /// A relationship between two commits was detected
+    CommitRelationshipDetected,
 
     // Branch events
     /// A branch was created
     BranchCreated,
     /// A branch was deleted
     BranchDeleted,
+    /// A branch was moved to a different commit
+    BranchMoved,
     /// A branch was merged into another
     BranchMerged,
+    /// Two branches were compared
+    BranchesCompared,
 
     // Tag events
     /// A tag was created
@@ -191,6 +203,8 @@ pub enum EventAction {
     // File events
     /// A file was analyzed for changes
     FileAnalyzed,
+    /// The history of a single file was analyzed
+    FileHistoryAnalyzed,
 
     // Merge events
     /// A merge operation was detected
@@ -205,14 +219,20 @@ impl EventAction {
             EventAction::RepositoryCloned => "cloned",
             EventAction::RepositoryDeleted => "deleted",
             EventAction::RepositoryAnalyzed => "analyzed",
+            EventAction::CloneProgressUpdated => "clone_progress",
+            EventAction::ReleaseNotesGenerated => "release_notes_generated",
+            EventAction::SignaturePolicyChecked => "signature_policy_checked",
 
             // Commit events
             EventAction::CommitAnalyzed => "analyzed",
+            EventAction::CommitRelationshipDetected => "relationship_detected",
 
             // Branch events
             EventAction::BranchCreated => "created",
             EventAction::BranchDeleted => "deleted",
+            EventAction::BranchMoved => "moved",
             EventAction::BranchMerged => "merged",
+            EventAction::BranchesCompared => "compared",
 
             // Tag events
             EventAction::TagCreated => "created",
@@ -226,6 +246,7 @@ impl EventAction {
 
             // File events
             EventAction::FileAnalyzed => "analyzed",
+            EventAction::FileHistoryAnalyzed => "history_analyzed",
 
             // Merge events
             EventAction::MergeDetected => "detected",
@@ -237,15 +258,22 @@ impl EventAction {
         match self {
             EventAction::RepositoryCloned
             | EventAction::RepositoryDeleted
-            | EventAction::RepositoryAnalyzed => Aggregate::Repository,
+            | EventAction::RepositoryAnalyzed
+            | EventAction::CloneProgressUpdated
+            | EventAction::ReleaseNotesGenerated
+            | EventAction::SignaturePolicyChecked => Aggregate::Repository,
 
             EventAction::CommitAnalyzed
+            | EventAction::CommitRelationshipDetected
             | EventAction::FileAnalyzed
+            | EventAction::FileHistoryAnalyzed
             | EventAction::MergeDetected => Aggregate::Commit,
 
-            EventAction::BranchCreated | EventAction::BranchDeleted | EventAction::BranchMerged => {
-                Aggregate::Branch
-            }
+            EventAction::BranchCreated
+            | EventAction::BranchDeleted
+            | EventAction::BranchMoved
+            | EventAction::BranchMerged
+            | EventAction::BranchesCompared => Aggregate::Branch,
 
             EventAction::TagCreated | EventAction::TagDeleted => Aggregate::Tag,
 
@@ -341,6 +369,7 @@ pub struct GitSubject {
     message_type: MessageType,
     aggregate: Aggregate,
     action: String,
+    aggregate_id: Option<String>,
 }
 
 impl GitSubject {
@@ -350,6 +379,7 @@ impl GitSubject {
             message_type: MessageType::Command,
             aggregate: action.aggregate(),
             action: action.as_str().to_string(),
+            aggregate_id: None,
         }
     }
 
@@ -359,6 +389,7 @@ impl GitSubject {
             message_type: MessageType::Event,
             aggregate: action.aggregate(),
             action: action.as_str().to_string(),
+            aggregate_id: None,
         }
     }
 
@@ -368,9 +399,20 @@ impl GitSubject {
             message_type: MessageType::Query,
             aggregate: action.aggregate(),
             action: action.as_str().to_string(),
+            aggregate_id: None,
         }
     }
 
+    /// Scope the subject to a single aggregate instance
+    ///
+    /// The aggregate ID becomes the last token, so consumers can filter the
+    /// events of one repository on the server.
+    #[must_use]
+    pub fn for_aggregate(mut self, aggregate_id: impl fmt::Display) -> Self {
+        self.aggregate_id = Some(aggregate_id.to_string());
+        self
+    }
+
     /// Create a wildcard subject for subscriptions
     pub fn wildcard(message_type: MessageType) -> String {
         format!("{}.{}.>", DOMAIN, message_type)
@@ -380,6 +422,16 @@ impl GitSubject {
     pub fn aggregate_wildcard(message_type: MessageType, aggregate: Aggregate) -> String {
         format!("{}.{}.{}.>", DOMAIN, message_type, aggregate)
     }
+
+    /// Create a wildcard matching every event of one aggregate instance
+    pub fn aggregate_events(aggregate_id: impl fmt::Display) -> String {
+        format!("{}.{}.*.*.{}", DOMAIN, MessageType::Event, aggregate_id)
+    }
+
+    /// Create a wildcard matching events published without an aggregate ID
+    pub fn unscoped_events() -> String {
+        format!("{}.{}.*.*", DOMAIN, MessageType::Event)
+    }
 }
 
 impl fmt::Display for GitSubject {
@@ -388,7 +440,11 @@ impl fmt::Display for GitSubject {
             f,
             "{}.{}.{}.{}",
             DOMAIN, self.message_type, self.aggregate, self.action
-        )
+        )?;
+        if let Some(aggregate_id) = &self.aggregate_id {
+            write!(f, ".{aggregate_id}")?;
+        }
+        Ok(())
     }
 }
 
@@ -402,10 +458,20 @@ impl SubjectMapper {
             "RepositoryCloned" => Some(GitSubject::event(EventAction::RepositoryCloned)),
             "RepositoryDeleted" => Some(GitSubject::event(EventAction::RepositoryDeleted)),
             "RepositoryAnalyzed" => Some(GitSubject::event(EventAction::RepositoryAnalyzed)),
+            "CloneProgressUpdated" => Some(GitSubject::event(EventAction::CloneProgressUpdated)),
+            "ReleaseNotesGenerated" => Some(GitSubject::event(EventAction::ReleaseNotesGenerated)),
+            "SignaturePolicyChecked" => {
+                Some(GitSubject::event(EventAction::SignaturePolicyChecked))
+            }
             "CommitAnalyzed" => Some(GitSubject::event(EventAction::CommitAnalyzed)),
+            "CommitRelationshipDetected" => {
+                Some(GitSubject::event(EventAction::CommitRelationshipDetected))
+            }
             "BranchCreated" => Some(GitSubject::event(EventAction::BranchCreated)),
             "BranchDeleted" => Some(GitSubject::event(EventAction::BranchDeleted)),
+            "BranchMoved" => Some(GitSubject::event(EventAction::BranchMoved)),
             "BranchMerged" => Some(GitSubject::event(EventAction::BranchMerged)),
+            "BranchesCompared" => Some(GitSubject::event(EventAction::BranchesCompared)),
             "TagCreated" => Some(GitSubject::event(EventAction::TagCreated)),
             "TagDeleted" => Some(GitSubject::event(EventAction::TagDeleted)),
             "RemoteAdded" => Some(GitSubject::event(EventAction::RemoteAdded)),
@@ -413,11 +479,17 @@ impl SubjectMapper {
             "RemoteFetched" => Some(GitSubject::event(EventAction::RemoteFetched)),
             "RemotePushed" => Some(GitSubject::event(EventAction::RemotePushed)),
             "FileAnalyzed" => Some(GitSubject::event(EventAction::FileAnalyzed)),
+            "FileHistoryAnalyzed" => Some(GitSubject::event(EventAction::FileHistoryAnalyzed)),
             "MergeDetected" => Some(GitSubject::event(EventAction::MergeDetected)),
             _ => None,
         }
     }
 
+    /// Map an event type string to the subject of one aggregate instance
+    pub fn aggregate_event_subject(event_type: &str, aggregate_id: &str) -> Option<GitSubject> {
+        Self::event_subject(event_type).map(|subject| subject.for_aggregate(aggregate_id))
+    }
+
     /// Map a command type to a subject
     pub fn command_subject(command_type: &str) -> Option<GitSubject> {
         match command_type {
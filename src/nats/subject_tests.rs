@@ -42,6 +42,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_aggregate_event_subjects() {
+        assert_eq!(
+            GitSubject::event(EventAction::BranchMoved)
+                .for_aggregate("0b7f5e6a-6c39-4d8e-9a57-1d2c3b4a5f60")
+                .to_string(),
+            "git.event.branch.moved.0b7f5e6a-6c39-4d8e-9a57-1d2c3b4a5f60"
+        );
+
+        assert_eq!(
+            GitSubject::aggregate_events("0b7f5e6a-6c39-4d8e-9a57-1d2c3b4a5f60"),
+            "git.event.*.*.0b7f5e6a-6c39-4d8e-9a57-1d2c3b4a5f60"
+        );
+        assert_eq!(GitSubject::unscoped_events(), "git.event.*.*");
+    }
+
     #[test]
     fn test_query_subjects() {
         assert_eq!(
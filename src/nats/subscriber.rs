@@ -17,6 +17,7 @@ use super::{
     error::{NatsError, Result},
     subject::{GitSubject, MessageType},
 };
+use crate::events::UpcasterRegistry;
 
 /// Trait for handling commands
 #[async_trait::async_trait]
@@ -240,6 +241,7 @@ impl CommandSubscriber {
 pub struct EventSubscriber {
     client: Client,
     handlers: Arc<RwLock<Vec<Box<dyn EventHandler<Event = serde_json::Value> + Send + Sync>>>>,
+    upcasters: Arc<UpcasterRegistry>,
 }
 
 impl EventSubscriber {
@@ -248,9 +250,17 @@ impl EventSubscriber {
         Self {
             client,
             handlers: Arc::new(RwLock::new(Vec::new())),
+            upcasters: Arc::new(UpcasterRegistry::new()),
         }
     }
 
+    /// Set the upcasters applied to event envelopes before they reach handlers
+    #[must_use]
+    pub fn with_upcasters(mut self, upcasters: Arc<UpcasterRegistry>) -> Self {
+        self.upcasters = upcasters;
+        self
+    }
+
     /// Register an event handler
     pub async fn register_handler<H>(&self, handler: H)
     where
@@ -280,10 +290,11 @@ impl EventSubscriber {
     async fn process_messages(&self, mut subscriber: Subscriber) -> Result<()> {
         while let Some(message) = subscriber.next().await {
             let handlers = self.handlers.clone();
+            let upcasters = self.upcasters.clone();
 
             // Spawn a task to handle the message
             tokio::spawn(async move {
-                if let Err(e) = Self::handle_message(message, handlers).await {
+                if let Err(e) = Self::handle_message(message, handlers, upcasters).await {
                     error!("Error handling event: {}", e);
                 }
             });
@@ -296,6 +307,7 @@ impl EventSubscriber {
     async fn handle_message(
         message: Message,
         handlers: Arc<RwLock<Vec<Box<dyn EventHandler<Event = serde_json::Value> + Send + Sync>>>>,
+        upcasters: Arc<UpcasterRegistry>,
     ) -> Result<()> {
         let subject = message.subject.as_str();
         debug!("Received event on subject: {}", subject);
@@ -315,9 +327,16 @@ impl EventSubscriber {
             });
 
         // Deserialize the event
-        let event: serde_json::Value = serde_json::from_slice(&message.payload)
+        let mut event: serde_json::Value = serde_json::from_slice(&message.payload)
             .map_err(|e| NatsError::DeserializationError(e.to_string()))?;
 
+        // Envelopes carry a schema version; bring older ones to the current shape
+        if event.get("metadata").is_some() {
+            upcasters
+                .upcast(&mut event)
+                .map_err(|e| NatsError::DeserializationError(e.to_string()))?;
+        }
+
         // Find handlers for this event type
         let handlers = handlers.read().await;
         let mut handled = false;
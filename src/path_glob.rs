@@ -0,0 +1,84 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! Path patterns shared by search filters, `.gitattributes` and CODEOWNERS
+
+use crate::GitDomainError;
+use regex::Regex;
+
+/// A gitignore-style path pattern
+///
+/// Patterns without a slash match a file or directory name at any depth;
+/// patterns with one are anchored to `base`. A trailing slash only matches
+/// directories, and a matched directory matches everything below it.
+pub(crate) struct PathGlob(Regex);
+
+impl PathGlob {
+    pub(crate) fn new(base: &str, pattern: &str) -> Result<Self, GitDomainError> {
+        Self::compile(base, pattern, true)
+    }
+
+    /// A CODEOWNERS pattern
+    ///
+    /// Differs from gitignore in that a wildcard in the last component only
+    /// matches at that level, so `docs/*` does not match `docs/api/index.md`.
+    pub(crate) fn codeowners(pattern: &str) -> Result<Self, GitDomainError> {
+        let last = pattern.rsplit('/').next().unwrap_or_default();
+        let nested = !last.contains('*') || last.contains("**");
+        Self::compile("", pattern, nested)
+    }
+
+    /// A pattern for a whole name such as a branch name
+    ///
+    /// Wildcards do not cross a slash, so `release/*` matches `release/1.0`
+    /// but neither `release/1.0/hotfix` nor `old/release/1.0`.
+    pub(crate) fn full_name(pattern: &str) -> Result<Self, GitDomainError> {
+        Self::compile("", &format!("/{pattern}"), false)
+    }
+
+    fn compile(base: &str, pattern: &str, nested: bool) -> Result<Self, GitDomainError> {
+        let dir_only = pattern.ends_with('/');
+        let trimmed = pattern.trim_end_matches('/');
+        let anchored = trimmed.contains('/');
+        let trimmed = trimmed.trim_start_matches('/');
+
+        let mut expression = String::from("^");
+        if !base.is_empty() {
+            expression.push_str(&regex::escape(base));
+            expression.push('/');
+        }
+        if !anchored {
+            expression.push_str("(?:.*/)?");
+        }
+
+        let mut chars = trimmed.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    if chars.peek() == Some(&'/') {
+                        chars.next();
+                        expression.push_str("(?:.*/)?");
+                    } else {
+                        expression.push_str(".*");
+                    }
+                }
+                '*' => expression.push_str("[^/]*"),
+                '?' => expression.push_str("[^/]"),
+                c => expression.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        expression.push_str(match (dir_only, nested) {
+            (true, _) => "/.*$",
+            (false, true) => "(?:/.*)?$",
+            (false, false) => "$",
+        });
+
+        Regex::new(&expression).map(Self).map_err(|e| {
+            GitDomainError::ValidationError(format!("Invalid path pattern {pattern}: {e}"))
+        })
+    }
+
+    pub(crate) fn matches(&self, path: &str) -> bool {
+        self.0.is_match(path)
+    }
+}
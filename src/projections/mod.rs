@@ -8,9 +8,13 @@
 use crate::aggregate::RepositoryId;
 use crate::events::FileChangeType;
 use crate::events::GitDomainEvent;
-use crate::value_objects::{AuthorInfo, BranchName, CommitHash, FilePath, RemoteUrl};
+use crate::signature::SignatureInfo;
+use crate::value_objects::{
+    AuthorInfo, BranchName, CommitHash, FilePath, RemoteUrl, SemanticVersion, TagName,
+};
 use chrono::{DateTime, Utc};
-use std::collections::{HashMap, HashSet};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::{Arc, RwLock};
 
 /// Repository summary for list views
@@ -181,6 +185,12 @@ pub struct CommitHistoryEntry {
     pub timestamp: DateTime<Utc>,
     /// Number of files changed
     pub files_changed: usize,
+    /// Conventional Commit type, if the message follows Conventional Commits
+    #[serde(default)]
+    pub change_type: Option<String>,
+    /// Whether the commit is marked as a breaking change
+    #[serde(default)]
+    pub breaking: bool,
 }
 
 /// Projection that maintains commit history for repositories
@@ -216,6 +226,8 @@ impl CommitHistoryProjection {
                 message: e.message.clone(),
                 timestamp: e.commit_timestamp,
                 files_changed: e.files_changed.len(),
+                change_type: e.parsed_message.change_type().map(str::to_string),
+                breaking: e.parsed_message.breaking,
             });
 
             // Keep commits sorted by timestamp (newest first)
@@ -248,6 +260,46 @@ impl CommitHistoryProjection {
         }
     }
 
+    /// Get commit history grouped by Conventional Commit type, newest first
+    ///
+    /// Commits that do not follow Conventional Commits are left out.
+    pub fn get_history_by_type(
+        &self,
+        repository_id: &RepositoryId,
+    ) -> Result<BTreeMap<String, Vec<CommitHistoryEntry>>, ProjectionError> {
+        let commits = self
+            .commits
+            .read()
+            .map_err(|_| ProjectionError::LockPoisoned)?;
+
+        let mut by_type: BTreeMap<String, Vec<CommitHistoryEntry>> = BTreeMap::new();
+        for entry in commits.get(repository_id).into_iter().flatten() {
+            if let Some(change_type) = &entry.change_type {
+                by_type
+                    .entry(change_type.clone())
+                    .or_default()
+                    .push(entry.clone());
+            }
+        }
+        Ok(by_type)
+    }
+
+    /// Get the commits marked as breaking changes, newest first
+    pub fn get_breaking_changes(
+        &self,
+        repository_id: &RepositoryId,
+    ) -> Result<Vec<CommitHistoryEntry>, ProjectionError> {
+        let commits = self
+            .commits
+            .read()
+            .map_err(|_| ProjectionError::LockPoisoned)?;
+
+        Ok(commits
+            .get(repository_id)
+            .map(|history| history.iter().filter(|c| c.breaking).cloned().collect())
+            .unwrap_or_default())
+    }
+
     /// Get a specific commit
     pub fn get_commit(
         &self,
@@ -303,23 +355,40 @@ impl BranchStatusProjection {
 
     /// Handle a domain event to update the projection
     pub fn handle_event(&self, event: &GitDomainEvent) -> Result<(), ProjectionError> {
-        if let GitDomainEvent::BranchCreated(e) = event {
-            let mut branches = self
-                .branches
-                .write()
-                .map_err(|_| ProjectionError::LockPoisoned)?;
+        let mut branches = self
+            .branches
+            .write()
+            .map_err(|_| ProjectionError::LockPoisoned)?;
 
-            let repo_branches = branches.entry(e.repository_id).or_insert_with(HashMap::new);
-
-            repo_branches.insert(
-                e.branch_name.clone(),
-                BranchInfo {
-                    name: e.branch_name.clone(),
-                    head: e.commit_hash.clone(),
-                    is_default: e.branch_name.is_default(),
-                    last_updated: e.timestamp,
-                },
-            );
+        match event {
+            GitDomainEvent::BranchCreated(e) => {
+                let repo_branches = branches.entry(e.repository_id).or_insert_with(HashMap::new);
+
+                repo_branches.insert(
+                    e.branch_name.clone(),
+                    BranchInfo {
+                        name: e.branch_name.clone(),
+                        head: e.commit_hash.clone(),
+                        is_default: e.branch_name.is_default(),
+                        last_updated: e.timestamp,
+                    },
+                );
+            }
+            GitDomainEvent::BranchMoved(e) => {
+                if let Some(branch) = branches
+                    .get_mut(&e.repository_id)
+                    .and_then(|repo_branches| repo_branches.get_mut(&e.branch_name))
+                {
+                    branch.head = e.new_commit.clone();
+                    branch.last_updated = e.timestamp;
+                }
+            }
+            GitDomainEvent::BranchDeleted(e) => {
+                if let Some(repo_branches) = branches.get_mut(&e.repository_id) {
+                    repo_branches.remove(&e.branch_name);
+                }
+            }
+            _ => {} // Other events don't affect branch status
         }
 
         Ok(())
@@ -367,6 +436,127 @@ impl Default for BranchStatusProjection {
     }
 }
 
+/// Tag information for tag views
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TagInfo {
+    /// Tag name
+    pub name: TagName,
+    /// Commit the tag points to
+    pub commit_hash: CommitHash,
+    /// Whether the tag is annotated
+    pub annotated: bool,
+    /// Tag message (if annotated)
+    pub message: Option<String>,
+    /// Tagger (if annotated)
+    pub tagger: Option<AuthorInfo>,
+    /// Signature (if signed)
+    #[serde(default)]
+    pub signature: Option<SignatureInfo>,
+    /// Semantic version the tag names, if any
+    pub version: Option<SemanticVersion>,
+    /// When the tag was recorded
+    pub created_at: DateTime<Utc>,
+}
+
+/// Projection that maintains the tags of repositories
+pub struct TagProjection {
+    /// Map of repository ID to tags
+    tags: Arc<RwLock<HashMap<RepositoryId, HashMap<TagName, TagInfo>>>>,
+}
+
+impl TagProjection {
+    /// Create a new tag projection
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            tags: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Handle a domain event to update the projection
+    pub fn handle_event(&self, event: &GitDomainEvent) -> Result<(), ProjectionError> {
+        let mut tags = self
+            .tags
+            .write()
+            .map_err(|_| ProjectionError::LockPoisoned)?;
+
+        match event {
+            GitDomainEvent::TagCreated(e) => {
+                tags.entry(e.repository_id).or_default().insert(
+                    e.tag_name.clone(),
+                    TagInfo {
+                        name: e.tag_name.clone(),
+                        commit_hash: e.commit_hash.clone(),
+                        annotated: e.annotated,
+                        message: e.message.clone(),
+                        tagger: e.tagger.clone(),
+                        signature: e.signature.clone(),
+                        version: e.tag_name.semver(),
+                        created_at: e.timestamp,
+                    },
+                );
+            }
+            GitDomainEvent::TagDeleted(e) => {
+                if let Some(repo_tags) = tags.get_mut(&e.repository_id) {
+                    repo_tags.remove(&e.tag_name);
+                }
+            }
+            _ => {} // Other events don't affect tags
+        }
+
+        Ok(())
+    }
+
+    /// Get all tags for a repository
+    ///
+    /// Semantic version tags come first, highest version first, followed by
+    /// the other tags by name.
+    pub fn get_tags(&self, repository_id: &RepositoryId) -> Result<Vec<TagInfo>, ProjectionError> {
+        let tags = self
+            .tags
+            .read()
+            .map_err(|_| ProjectionError::LockPoisoned)?;
+
+        let mut repo_tags: Vec<TagInfo> = tags
+            .get(repository_id)
+            .map(|repo_tags| repo_tags.values().cloned().collect())
+            .unwrap_or_default();
+        repo_tags.sort_by(|a, b| match (&a.version, &b.version) {
+            (Some(a_version), Some(b_version)) => b_version
+                .cmp(a_version)
+                .then_with(|| a.name.as_str().cmp(b.name.as_str())),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => a.name.as_str().cmp(b.name.as_str()),
+        });
+
+        Ok(repo_tags)
+    }
+
+    /// Get a specific tag
+    pub fn get_tag(
+        &self,
+        repository_id: &RepositoryId,
+        name: &TagName,
+    ) -> Result<Option<TagInfo>, ProjectionError> {
+        let tags = self
+            .tags
+            .read()
+            .map_err(|_| ProjectionError::LockPoisoned)?;
+
+        Ok(tags
+            .get(repository_id)
+            .and_then(|repo_tags| repo_tags.get(name))
+            .cloned())
+    }
+}
+
+impl Default for TagProjection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// File change tracking projection
 ///
 /// This projection tracks file changes across commits
@@ -454,34 +644,57 @@ impl FileChangeProjection {
                         timestamp: event.commit_timestamp,
                     };
 
-                    // Track by file path
-                    file_changes
-                        .entry(file_change_info.path.clone())
-                        .or_insert_with(Vec::new)
-                        .push(change.clone());
-
-                    // Track renames
-                    if let FileChangeType::Renamed = &file_change_info.change_type {
-                        // In a real implementation, we'd need the old path from the event
-                        // For now, we'll just note that this is a renamed file
-                        let rename = RenameInfo {
-                            old_path: file_change_info.path.clone(), // Would need old path
-                            new_path: file_change_info.path.clone(),
-                            commit_hash: event.commit_hash.clone(),
-                            timestamp: event.commit_timestamp,
-                        };
-                        rename_history
-                            .entry(file_change_info.path.clone())
-                            .or_insert_with(Vec::new)
-                            .push(rename);
-                    }
-
+                    record_file_change(
+                        &mut file_changes,
+                        &mut rename_history,
+                        &change,
+                        file_change_info.old_path.as_ref(),
+                    );
                     changes_for_commit.push(change);
                 }
 
                 // Track by commit
                 commit_changes.insert(event.commit_hash.clone(), changes_for_commit);
             }
+            GitDomainEvent::FileHistoryAnalyzed(event) => {
+                let mut file_changes = self
+                    .file_changes
+                    .write()
+                    .map_err(|_| ProjectionError::LockError)?;
+                let mut commit_changes = self
+                    .commit_changes
+                    .write()
+                    .map_err(|_| ProjectionError::LockError)?;
+                let mut rename_history = self
+                    .rename_history
+                    .write()
+                    .map_err(|_| ProjectionError::LockError)?;
+
+                // Newest first, in the order commit analysis records changes
+                for revision in &event.revisions {
+                    let change = FileChange {
+                        path: revision.change.path.clone(),
+                        commit_hash: revision.commit_hash.clone(),
+                        change_type: revision.change.change_type,
+                        additions: revision.change.additions,
+                        deletions: revision.change.deletions,
+                        author: revision.author.clone(),
+                        timestamp: revision.commit_timestamp,
+                    };
+
+                    if record_file_change(
+                        &mut file_changes,
+                        &mut rename_history,
+                        &change,
+                        revision.change.old_path.as_ref(),
+                    ) {
+                        commit_changes
+                            .entry(revision.commit_hash.clone())
+                            .or_default()
+                            .push(change);
+                    }
+                }
+            }
             _ => {} // Other events don't affect file changes
         }
 
@@ -559,6 +772,42 @@ impl FileChangeProjection {
     }
 }
 
+/// Index a file change by path and record renames under both paths
+///
+/// Returns `false` if the path already has a change for the same commit, as
+/// a commit may be seen through both repository and file history analysis.
+fn record_file_change(
+    file_changes: &mut HashMap<FilePath, Vec<FileChange>>,
+    rename_history: &mut HashMap<FilePath, Vec<RenameInfo>>,
+    change: &FileChange,
+    old_path: Option<&FilePath>,
+) -> bool {
+    let changes = file_changes.entry(change.path.clone()).or_default();
+    if changes.iter().any(|c| c.commit_hash == change.commit_hash) {
+        return false;
+    }
+    changes.push(change.clone());
+
+    if let (FileChangeType::Renamed, Some(old_path)) = (change.change_type, old_path) {
+        let rename = RenameInfo {
+            old_path: old_path.clone(),
+            new_path: change.path.clone(),
+            commit_hash: change.commit_hash.clone(),
+            timestamp: change.timestamp,
+        };
+        rename_history
+            .entry(old_path.clone())
+            .or_default()
+            .push(rename.clone());
+        rename_history
+            .entry(change.path.clone())
+            .or_default()
+            .push(rename);
+    }
+
+    true
+}
+
 /// File statistics summary
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct FileStatistics {
@@ -603,7 +852,10 @@ pub enum ProjectionError {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::events::{BranchCreated, CommitAnalyzed, FileChangeInfo, RepositoryAnalyzed};
+    use crate::events::{
+        BranchCreated, BranchDeleted, BranchMoved, CommitAnalyzed, FileChangeInfo,
+        RepositoryAnalyzed, TagCreated, TagDeleted,
+    };
     use crate::value_objects::{AuthorInfo, FilePath};
 
     #[test]
@@ -618,6 +870,8 @@ mod tests {
             name: "test-repo".to_string(),
             branch_count: 2,
             commit_count: 10,
+            ref_tips: HashMap::new(),
+            head_branch: None,
             timestamp: Utc::now(),
         });
 
@@ -644,8 +898,13 @@ mod tests {
             commit_hash: CommitHash::new("abc123def").unwrap(),
             parents: vec![],
             author: AuthorInfo::new("Test Author", "test@example.com"),
+            raw_author: None,
             message: "Test commit".to_string(),
+            parsed_message: Default::default(),
+            signature: None,
             files_changed: vec![],
+            parent_diffs: vec![],
+            combined_files: vec![],
             commit_timestamp: Utc::now(),
             timestamp: Utc::now(),
         });
@@ -661,6 +920,53 @@ mod tests {
         assert_eq!(commit.author_name, "Test Author");
     }
 
+    #[test]
+    fn test_commit_history_by_change_type() {
+        let projection = CommitHistoryProjection::new();
+        let repo_id = RepositoryId::new();
+
+        let messages = [
+            ("abc123def", "feat(api): Add search endpoint"),
+            (
+                "def456abc",
+                "fix: Handle empty queries\n\nBREAKING CHANGE: empty queries now fail",
+            ),
+            ("789abc012", "Update README"),
+            ("012def345", "feat!: Drop v1 routes"),
+        ];
+        for (hash, message) in messages {
+            let parsed = crate::value_objects::CommitMessage::new(message)
+                .unwrap()
+                .parse();
+            let event = GitDomainEvent::CommitAnalyzed(CommitAnalyzed {
+                repository_id: repo_id,
+                commit_hash: CommitHash::new(hash).unwrap(),
+                parents: vec![],
+                author: AuthorInfo::new("Test Author", "test@example.com"),
+                raw_author: None,
+                message: message.to_string(),
+                parsed_message: parsed,
+                signature: None,
+                files_changed: vec![],
+                parent_diffs: vec![],
+                combined_files: vec![],
+                commit_timestamp: Utc::now(),
+                timestamp: Utc::now(),
+            });
+            projection.handle_event(&event).unwrap();
+        }
+
+        let by_type = projection.get_history_by_type(&repo_id).unwrap();
+        assert_eq!(by_type.keys().collect::<Vec<_>>(), vec!["feat", "fix"]);
+        assert_eq!(by_type["feat"].len(), 2);
+        assert_eq!(by_type["fix"].len(), 1);
+
+        let breaking = projection.get_breaking_changes(&repo_id).unwrap();
+        let mut hashes: Vec<&str> = breaking.iter().map(|c| c.hash.as_str()).collect();
+        hashes.sort_unstable();
+        assert_eq!(hashes, vec!["012def345", "def456abc"]);
+    }
+
     #[test]
     fn test_branch_status_projection() {
         let projection = BranchStatusProjection::new();
@@ -672,6 +978,7 @@ mod tests {
             branch_name: BranchName::new("main").unwrap(),
             commit_hash: CommitHash::new("abc123def").unwrap(),
             source_branch: None,
+            checked_out: false,
             timestamp: Utc::now(),
         });
 
@@ -686,6 +993,112 @@ mod tests {
         assert!(branch.is_default);
     }
 
+    #[test]
+    fn test_branch_status_follows_moves_and_deletes() {
+        let projection = BranchStatusProjection::new();
+        let repo_id = RepositoryId::new();
+        let main = BranchName::new("main").unwrap();
+
+        projection
+            .handle_event(&GitDomainEvent::BranchCreated(BranchCreated {
+                repository_id: repo_id,
+                branch_name: main.clone(),
+                commit_hash: CommitHash::new("abc123def").unwrap(),
+                source_branch: None,
+                checked_out: false,
+                timestamp: Utc::now(),
+            }))
+            .unwrap();
+
+        projection
+            .handle_event(&GitDomainEvent::BranchMoved(BranchMoved {
+                repository_id: repo_id,
+                branch_name: main.clone(),
+                old_commit: CommitHash::new("abc123def").unwrap(),
+                new_commit: CommitHash::new("def456abc").unwrap(),
+                fast_forward: true,
+                timestamp: Utc::now(),
+            }))
+            .unwrap();
+
+        let branch = projection.get_branch(&repo_id, &main).unwrap().unwrap();
+        assert_eq!(branch.head.as_str(), "def456abc");
+
+        projection
+            .handle_event(&GitDomainEvent::BranchDeleted(BranchDeleted {
+                repository_id: repo_id,
+                branch_name: main.clone(),
+                last_commit: CommitHash::new("def456abc").unwrap(),
+                timestamp: Utc::now(),
+            }))
+            .unwrap();
+
+        assert!(projection.get_branch(&repo_id, &main).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_tag_projection_sorts_by_version() {
+        let projection = TagProjection::new();
+        let repo_id = RepositoryId::new();
+
+        for name in [
+            "v1.2.0",
+            "nightly",
+            "v1.10.0",
+            "v2.0.0-rc.1",
+            "archive",
+            "v1.2.0-beta",
+        ] {
+            projection
+                .handle_event(&GitDomainEvent::TagCreated(TagCreated {
+                    repository_id: repo_id,
+                    tag_name: TagName::new(name).unwrap(),
+                    commit_hash: CommitHash::new("abc123def").unwrap(),
+                    message: None,
+                    tagger: None,
+                    annotated: false,
+                    signature: None,
+                    timestamp: Utc::now(),
+                }))
+                .unwrap();
+        }
+
+        let names: Vec<String> = projection
+            .get_tags(&repo_id)
+            .unwrap()
+            .iter()
+            .map(|tag| tag.name.to_string())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "v2.0.0-rc.1",
+                "v1.10.0",
+                "v1.2.0",
+                "v1.2.0-beta",
+                "archive",
+                "nightly"
+            ]
+        );
+
+        let nightly = TagName::new("nightly").unwrap();
+        projection
+            .handle_event(&GitDomainEvent::TagDeleted(TagDeleted {
+                repository_id: repo_id,
+                tag_name: nightly.clone(),
+                last_commit: CommitHash::new("abc123def").unwrap(),
+                timestamp: Utc::now(),
+            }))
+            .unwrap();
+
+        assert!(projection.get_tag(&repo_id, &nightly).unwrap().is_none());
+        let latest = projection
+            .get_tag(&repo_id, &TagName::new("v1.10.0").unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(latest.version, Some(SemanticVersion::new(1, 10, 0)));
+    }
+
     #[tokio::test]
     async fn test_file_change_projection() {
         let projection = FileChangeProjection::new();
@@ -697,13 +1110,20 @@ mod tests {
             commit_hash: CommitHash::new("abc123def456789").unwrap(),
             parents: vec![],
             author: AuthorInfo::new("Test Author".to_string(), "test@example.com".to_string()),
+            raw_author: None,
             message: "Test commit".to_string(),
+            parsed_message: Default::default(),
+            signature: None,
             files_changed: vec![FileChangeInfo {
                 path: file_path.clone(),
                 additions: 10,
                 deletions: 5,
                 change_type: FileChangeType::Modified,
+                old_path: None,
+                binary: false,
             }],
+            parent_diffs: vec![],
+            combined_files: vec![],
             commit_timestamp: Utc::now(),
             timestamp: Utc::now(),
         });
@@ -8,9 +8,9 @@
 use crate::aggregate::RepositoryId;
 use crate::projections::{
     BranchInfo, BranchStatusProjection, CommitHistoryEntry, CommitHistoryProjection,
-    RepositoryListProjection, RepositorySummary,
+    RepositoryListProjection, RepositorySummary, TagInfo, TagProjection,
 };
-use crate::value_objects::BranchName;
+use crate::value_objects::{BranchName, TagName};
 use cim_domain::Query;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -73,6 +73,46 @@ pub struct BranchListResult {
     pub default_branch: Option<BranchName>,
 }
 
+/// Query to get a single tag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetTag {
+    /// Repository ID
+    pub repository_id: RepositoryId,
+    /// Tag name
+    pub tag_name: TagName,
+}
+
+impl Query for GetTag {}
+
+/// Result of tag query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagResult {
+    /// The tag, if the repository has it
+    pub tag: Option<TagInfo>,
+}
+
+/// Query to list the tags of a repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListTags {
+    /// Repository ID
+    pub repository_id: RepositoryId,
+    /// Maximum number of tags to return
+    pub limit: Option<usize>,
+}
+
+impl Query for ListTags {}
+
+/// Result of tag list query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagListResult {
+    /// Tags, highest semantic version first and other tags by name after them
+    pub tags: Vec<TagInfo>,
+    /// Total number of tags (before limit)
+    pub total_count: usize,
+    /// Highest semantic version tag that is not a pre-release
+    pub latest_release: Option<TagName>,
+}
+
 /// Query to list all repositories
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ListRepositories {
@@ -94,6 +134,7 @@ pub struct GitQueryHandler {
     repository_projection: Arc<RepositoryListProjection>,
     commit_projection: Arc<CommitHistoryProjection>,
     branch_projection: Arc<BranchStatusProjection>,
+    tag_projection: Arc<TagProjection>,
 }
 
 impl GitQueryHandler {
@@ -108,9 +149,19 @@ impl GitQueryHandler {
             repository_projection,
             commit_projection,
             branch_projection,
+            tag_projection: Arc::new(TagProjection::new()),
         }
     }
 
+    /// Answer tag queries from the given projection
+    ///
+    /// Without one, tag queries see no tags.
+    #[must_use]
+    pub fn with_tag_projection(mut self, tag_projection: Arc<TagProjection>) -> Self {
+        self.tag_projection = tag_projection;
+        self
+    }
+
     /// Handle `GetRepositoryDetails` query
     pub async fn handle_get_repository_details(
         &self,
@@ -191,6 +242,42 @@ impl GitQueryHandler {
         })
     }
 
+    /// Handle `GetTag` query
+    pub async fn handle_get_tag(&self, query: GetTag) -> Result<TagResult, QueryError> {
+        let tag = self
+            .tag_projection
+            .get_tag(&query.repository_id, &query.tag_name)
+            .map_err(|e| QueryError::ProjectionError(e.to_string()))?;
+
+        Ok(TagResult { tag })
+    }
+
+    /// Handle `ListTags` query
+    pub async fn handle_list_tags(&self, query: ListTags) -> Result<TagListResult, QueryError> {
+        let mut tags = self
+            .tag_projection
+            .get_tags(&query.repository_id)
+            .map_err(|e| QueryError::ProjectionError(e.to_string()))?;
+
+        let total_count = tags.len();
+
+        // Tags are sorted by version, so the first release is the latest
+        let latest_release = tags
+            .iter()
+            .find(|tag| tag.version.as_ref().is_some_and(|v| !v.is_pre_release()))
+            .map(|tag| tag.name.clone());
+
+        if let Some(limit) = query.limit {
+            tags.truncate(limit);
+        }
+
+        Ok(TagListResult {
+            tags,
+            total_count,
+            latest_release,
+        })
+    }
+
     /// Handle `ListRepositories` query
     pub async fn handle_list_repositories(
         &self,
@@ -237,9 +324,12 @@ pub enum QueryError {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::events::{BranchCreated, CommitAnalyzed, GitDomainEvent, RepositoryAnalyzed};
+    use crate::events::{
+        BranchCreated, CommitAnalyzed, GitDomainEvent, RepositoryAnalyzed, TagCreated,
+    };
     use crate::value_objects::{AuthorInfo, CommitHash};
     use chrono::Utc;
+    use std::collections::HashMap;
 
     #[tokio::test]
     async fn test_get_repository_details() {
@@ -265,6 +355,8 @@ mod tests {
             name: "test-repo".to_string(),
             branch_count: 1,
             commit_count: 1,
+            ref_tips: HashMap::new(),
+            head_branch: None,
             timestamp: Utc::now(),
         });
         repo_projection.handle_event(&repo_event).unwrap();
@@ -275,8 +367,13 @@ mod tests {
             commit_hash: CommitHash::new("abc123def").unwrap(),
             parents: vec![],
             author: AuthorInfo::new("Test Author", "test@example.com"),
+            raw_author: None,
             message: "Initial commit".to_string(),
+            parsed_message: Default::default(),
+            signature: None,
             files_changed: vec![],
+            parent_diffs: vec![],
+            combined_files: vec![],
             commit_timestamp: Utc::now(),
             timestamp: Utc::now(),
         });
@@ -288,6 +385,7 @@ mod tests {
             branch_name: BranchName::new("main").unwrap(),
             commit_hash: CommitHash::new("abc123def").unwrap(),
             source_branch: None,
+            checked_out: false,
             timestamp: Utc::now(),
         });
         branch_projection.handle_event(&branch_event).unwrap();
@@ -327,6 +425,8 @@ mod tests {
                 name: format!("repo-{i}"),
                 branch_count: 1,
                 commit_count: 10,
+                ref_tips: HashMap::new(),
+                head_branch: None,
                 timestamp: Utc::now(),
             });
             repo_projection.handle_event(&event).unwrap();
@@ -341,4 +441,54 @@ mod tests {
         // Verify results
         assert_eq!(result.repositories.len(), 3);
     }
+
+    #[tokio::test]
+    async fn test_list_tags() {
+        let tag_projection = Arc::new(TagProjection::new());
+        let handler = GitQueryHandler::new(
+            Arc::new(RepositoryListProjection::new()),
+            Arc::new(CommitHistoryProjection::new()),
+            Arc::new(BranchStatusProjection::new()),
+        )
+        .with_tag_projection(tag_projection.clone());
+        let repo_id = RepositoryId::new();
+
+        for name in ["v0.9.0", "v1.0.0", "v1.1.0-rc.1", "latest"] {
+            let event = GitDomainEvent::TagCreated(TagCreated {
+                repository_id: repo_id,
+                tag_name: TagName::new(name).unwrap(),
+                commit_hash: CommitHash::new("abc123def").unwrap(),
+                message: Some(format!("Release {name}")),
+                tagger: Some(AuthorInfo::new("Release Bot", "release@example.com")),
+                annotated: true,
+                signature: None,
+                timestamp: Utc::now(),
+            });
+            tag_projection.handle_event(&event).unwrap();
+        }
+
+        let result = handler
+            .handle_list_tags(ListTags {
+                repository_id: repo_id,
+                limit: Some(2),
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.total_count, 4);
+        assert_eq!(result.tags.len(), 2);
+        assert_eq!(result.tags[0].name.as_str(), "v1.1.0-rc.1");
+        assert_eq!(result.latest_release, Some(TagName::new("v1.0.0").unwrap()));
+
+        let result = handler
+            .handle_get_tag(GetTag {
+                repository_id: repo_id,
+                tag_name: TagName::new("latest").unwrap(),
+            })
+            .await
+            .unwrap();
+        let tag = result.tag.unwrap();
+        assert!(tag.annotated);
+        assert!(tag.version.is_none());
+        assert_eq!(tag.message.as_deref(), Some("Release latest"));
+    }
 }
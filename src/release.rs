@@ -0,0 +1,302 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! Release notes
+//!
+//! The commits of a release are grouped into changelog sections by their
+//! Conventional Commits type, and the most significant change proposes the
+//! next version: breaking changes bump the major version, features the minor
+//! version and fixes and performance improvements the patch version. Before
+//! 1.0.0 a breaking change only bumps the minor version. Other commits are
+//! listed without calling for a release.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+
+use crate::value_objects::{
+    AuthorInfo, CommitHash, CommitMessage, IssueReference, SemanticVersion, TagName,
+};
+
+/// Changelog sections by Conventional Commits type, in rendering order
+const SECTIONS: [(&str, &str); 6] = [
+    ("feat", "Features"),
+    ("fix", "Bug Fixes"),
+    ("perf", "Performance Improvements"),
+    ("revert", "Reverts"),
+    ("refactor", "Code Refactoring"),
+    ("docs", "Documentation"),
+];
+
+/// Section listing breaking changes, rendered first
+const BREAKING_SECTION: &str = "Breaking Changes";
+
+/// Section for commits of any other type or without one, rendered last
+const OTHER_SECTION: &str = "Other Changes";
+
+/// Version component a release has to raise
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum VersionBump {
+    /// No change calls for a release
+    None,
+    /// Fixes only
+    Patch,
+    /// New functionality
+    Minor,
+    /// Incompatible changes
+    Major,
+}
+
+impl VersionBump {
+    /// The version that follows `version` under this bump
+    ///
+    /// Lower components are reset and pre-release and build metadata dropped.
+    #[must_use]
+    pub fn apply(self, version: &SemanticVersion) -> SemanticVersion {
+        match self {
+            Self::None => version.clone(),
+            Self::Patch => SemanticVersion::new(version.major, version.minor, version.patch + 1),
+            Self::Minor => SemanticVersion::new(version.major, version.minor + 1, 0),
+            Self::Major => SemanticVersion::new(version.major + 1, 0, 0),
+        }
+    }
+}
+
+/// One commit in the release notes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseNoteEntry {
+    /// Commit hash
+    pub commit_hash: CommitHash,
+
+    /// Commit author
+    pub author: AuthorInfo,
+
+    /// Conventional Commits type, if the message follows Conventional Commits
+    pub change_type: Option<String>,
+
+    /// Conventional Commits scope
+    pub scope: Option<String>,
+
+    /// Conventional Commits description, or the summary line otherwise
+    pub description: String,
+
+    /// Whether the commit is a breaking change
+    pub breaking: bool,
+
+    /// Text of the `BREAKING CHANGE:` footer
+    pub breaking_change: Option<String>,
+
+    /// Issues the message refers to
+    pub issue_references: Vec<IssueReference>,
+}
+
+impl ReleaseNoteEntry {
+    /// Create the entry for a commit from its message
+    #[must_use]
+    pub fn new(commit_hash: CommitHash, author: AuthorInfo, message: &CommitMessage) -> Self {
+        let parsed = message.parse();
+        let (change_type, scope, description) = match parsed.conventional {
+            Some(conventional) => (
+                Some(conventional.commit_type),
+                conventional.scope,
+                conventional.description,
+            ),
+            None => (None, None, message.summary().to_string()),
+        };
+
+        Self {
+            commit_hash,
+            author,
+            change_type,
+            scope,
+            description,
+            breaking: parsed.breaking,
+            breaking_change: message.breaking_change().map(str::to_string),
+            issue_references: parsed.issue_references,
+        }
+    }
+
+    /// The version component this commit raises
+    #[must_use]
+    pub fn bump(&self) -> VersionBump {
+        if self.breaking {
+            return VersionBump::Major;
+        }
+        match self.change_type.as_deref() {
+            Some("feat") => VersionBump::Minor,
+            Some("fix" | "perf") => VersionBump::Patch,
+            _ => VersionBump::None,
+        }
+    }
+
+    fn render(&self, out: &mut String, text: &str) {
+        out.push_str("- ");
+        if let Some(scope) = &self.scope {
+            let _ = write!(out, "**{scope}:** ");
+        }
+        out.push_str(text);
+        if !self.issue_references.is_empty() {
+            let references: Vec<String> = self
+                .issue_references
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            let _ = write!(out, " ({})", references.join(", "));
+        }
+        let hash = self.commit_hash.as_str();
+        let _ = writeln!(out, " ({})", &hash[..hash.len().min(7)]);
+    }
+}
+
+/// Entries of one changelog section, newest first
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseSection {
+    /// Section heading
+    pub title: String,
+
+    /// Commits in the section
+    pub entries: Vec<ReleaseNoteEntry>,
+}
+
+/// Release notes for the commits between two revisions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseNotes {
+    /// Tag of the previous release, if there is one
+    pub from_tag: Option<TagName>,
+
+    /// Tag of the release, if it is already tagged
+    pub to_tag: Option<TagName>,
+
+    /// Commit of the previous release
+    pub from_commit: Option<CommitHash>,
+
+    /// Last commit of the release
+    pub to_commit: CommitHash,
+
+    /// Version of the previous release
+    pub previous_version: Option<SemanticVersion>,
+
+    /// Proposed version of the release
+    ///
+    /// `None` when no change calls for a release, or when the previous
+    /// release is tagged without a semantic version.
+    pub next_version: Option<SemanticVersion>,
+
+    /// Version component the release raises
+    pub bump: VersionBump,
+
+    /// Changelog sections, breaking changes first and without empty sections
+    pub sections: Vec<ReleaseSection>,
+}
+
+impl ReleaseNotes {
+    /// Classify the commits of a release, given newest first
+    ///
+    /// Without a previous release, the proposed version is the bump applied
+    /// to 0.0.0.
+    #[must_use]
+    pub fn new(
+        from_tag: Option<TagName>,
+        to_tag: Option<TagName>,
+        from_commit: Option<CommitHash>,
+        to_commit: CommitHash,
+        entries: Vec<ReleaseNoteEntry>,
+    ) -> Self {
+        let previous_version = from_tag.as_ref().and_then(TagName::semver);
+        let base = match &from_tag {
+            Some(_) => previous_version.clone(),
+            None => Some(SemanticVersion::new(0, 0, 0)),
+        };
+
+        let mut bump = entries
+            .iter()
+            .map(ReleaseNoteEntry::bump)
+            .max()
+            .unwrap_or(VersionBump::None);
+        if bump == VersionBump::Major && base.as_ref().is_some_and(|base| base.major == 0) {
+            bump = VersionBump::Minor;
+        }
+        let next_version = base
+            .filter(|_| bump != VersionBump::None)
+            .map(|base| bump.apply(&base));
+
+        let mut sections = vec![ReleaseSection {
+            title: BREAKING_SECTION.to_string(),
+            entries: entries
+                .iter()
+                .filter(|entry| entry.breaking)
+                .cloned()
+                .collect(),
+        }];
+        sections.extend(SECTIONS.iter().map(|(_, title)| ReleaseSection {
+            title: (*title).to_string(),
+            entries: Vec::new(),
+        }));
+        let other = sections.len();
+        sections.push(ReleaseSection {
+            title: OTHER_SECTION.to_string(),
+            entries: Vec::new(),
+        });
+        for entry in entries {
+            let index = SECTIONS
+                .iter()
+                .position(|(change_type, _)| entry.change_type.as_deref() == Some(*change_type))
+                .map_or(other, |index| index + 1);
+            sections[index].entries.push(entry);
+        }
+        sections.retain(|section| !section.entries.is_empty());
+
+        Self {
+            from_tag,
+            to_tag,
+            from_commit,
+            to_commit,
+            previous_version,
+            next_version,
+            bump,
+            sections,
+        }
+    }
+
+    /// Whether the release has no commits
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    /// Render the notes as a Markdown changelog entry
+    ///
+    /// The heading is the release tag, the proposed version or `Unreleased`,
+    /// in that order of preference.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let heading = match (&self.to_tag, &self.next_version) {
+            (Some(tag), _) => tag.to_string(),
+            (None, Some(version)) => version.to_string(),
+            (None, None) => "Unreleased".to_string(),
+        };
+
+        let mut out = format!("## {heading}\n");
+        if self.is_empty() {
+            out.push_str("\nNo changes.\n");
+        }
+        for section in &self.sections {
+            let _ = write!(out, "\n### {}\n\n", section.title);
+            for entry in &section.entries {
+                let text = if section.title == BREAKING_SECTION {
+                    entry
+                        .breaking_change
+                        .as_deref()
+                        .unwrap_or(&entry.description)
+                } else {
+                    &entry.description
+                };
+                entry.render(&mut out, text);
+            }
+        }
+        out
+    }
+
+    /// Render the notes as JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
@@ -0,0 +1,617 @@
+// Copyright 2025 Cowboy AI, LLC.
+
+//! Commit and tag signatures
+//!
+//! Signatures are read from the `gpgsig` header of commits and from the end
+//! of annotated tag objects, and verified the way `git verify-commit` does:
+//! PGP signatures with `gpg`, X.509 signatures with `gpgsm` and SSH
+//! signatures with `ssh-keygen` against an `allowed_signers` file. A
+//! [`SignaturePolicy`] names the branches whose commits have to be signed.
+
+use crate::path_glob::PathGlob;
+use crate::value_objects::{AuthorInfo, BranchName, CommitHash};
+use crate::GitDomainError;
+use git2::{Oid, Repository as Git2Repository};
+use serde::{Deserialize, Serialize};
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use tracing::debug;
+use uuid::Uuid;
+
+/// Armor lines that open a signature, by format
+const SIGNATURE_HEADERS: [(&str, SignatureFormat); 4] = [
+    ("-----BEGIN PGP SIGNATURE-----", SignatureFormat::OpenPgp),
+    ("-----BEGIN PGP MESSAGE-----", SignatureFormat::OpenPgp),
+    ("-----BEGIN SIGNED MESSAGE-----", SignatureFormat::X509),
+    ("-----BEGIN SSH SIGNATURE-----", SignatureFormat::Ssh),
+];
+
+/// Namespace git uses for SSH signatures on commits and tags
+const SSH_NAMESPACE: &str = "git";
+
+/// Prefix of the machine-readable status lines of `gpg` and `gpgsm`
+const GPG_STATUS: &str = "[GNUPG:] ";
+
+/// Kind of key a signature was made with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SignatureFormat {
+    /// PGP, verified with `gpg`
+    OpenPgp,
+    /// X.509 certificate, verified with `gpgsm`
+    X509,
+    /// SSH key, verified with `ssh-keygen`
+    Ssh,
+}
+
+impl SignatureFormat {
+    /// Detect the format from the armor line that opens a signature
+    #[must_use]
+    pub fn detect(signature: &[u8]) -> Option<Self> {
+        let first = signature.split(|&byte| byte == b'\n').next()?;
+        let first = std::str::from_utf8(first).ok()?.trim_end();
+        SIGNATURE_HEADERS
+            .iter()
+            .find(|(header, _)| first == *header)
+            .map(|(_, format)| *format)
+    }
+}
+
+/// Outcome of verifying a signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SignatureStatus {
+    /// Made by a known key over exactly the signed content
+    Good,
+    /// Does not match the signed content
+    Bad,
+    /// Made by a key missing from the keyring or the allowed signers
+    UnknownKey,
+    /// Made by a key that has expired, or the signature itself expired
+    ExpiredKey,
+    /// Made by a key that has been revoked
+    RevokedKey,
+    /// Not checked, as no verifier or no allowed signers are configured
+    Unverified,
+    /// The verification program could not be run or gave no verdict
+    Error,
+}
+
+/// Signature found on a commit or tag
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureInfo {
+    /// Kind of key the signature was made with
+    pub format: SignatureFormat,
+
+    /// Outcome of the verification
+    pub status: SignatureStatus,
+
+    /// User ID, certificate subject or allowed signers principal of the key
+    pub signer: Option<String>,
+
+    /// Key ID or fingerprint
+    pub key: Option<String>,
+}
+
+impl SignatureInfo {
+    fn new(format: SignatureFormat, status: SignatureStatus) -> Self {
+        Self {
+            format,
+            status,
+            signer: None,
+            key: None,
+        }
+    }
+
+    /// Whether the signature verified with a known key
+    #[must_use]
+    pub fn is_good(&self) -> bool {
+        self.status == SignatureStatus::Good
+    }
+}
+
+/// Verifies signatures with the programs git uses for them
+///
+/// PGP and X.509 signatures are checked against the default keyring, or
+/// the one in [`with_gpg_home`](Self::with_gpg_home). SSH signatures are only
+/// checked when an allowed signers file is given; they are reported as
+/// [`SignatureStatus::Unverified`] otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureVerifier {
+    gpg_program: String,
+    gpgsm_program: String,
+    ssh_program: String,
+    gpg_home: Option<PathBuf>,
+    allowed_signers: Option<PathBuf>,
+}
+
+impl SignatureVerifier {
+    /// Create a verifier using `gpg`, `gpgsm` and `ssh-keygen` from `PATH`
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            gpg_program: "gpg".to_string(),
+            gpgsm_program: "gpgsm".to_string(),
+            ssh_program: "ssh-keygen".to_string(),
+            gpg_home: None,
+            allowed_signers: None,
+        }
+    }
+
+    /// Create a verifier from git configuration
+    ///
+    /// Reads `gpg.program` (or `gpg.openpgp.program`), `gpg.x509.program`,
+    /// `gpg.ssh.program` and `gpg.ssh.allowedSignersFile`.
+    #[must_use]
+    pub fn from_config(config: &git2::Config) -> Self {
+        let mut verifier = Self::new();
+        if let Ok(program) = config
+            .get_string("gpg.openpgp.program")
+            .or_else(|_| config.get_string("gpg.program"))
+        {
+            verifier.gpg_program = program;
+        }
+        if let Ok(program) = config.get_string("gpg.x509.program") {
+            verifier.gpgsm_program = program;
+        }
+        if let Ok(program) = config.get_string("gpg.ssh.program") {
+            verifier.ssh_program = program;
+        }
+        verifier.allowed_signers = config.get_path("gpg.ssh.allowedSignersFile").ok();
+        verifier
+    }
+
+    /// Use the keyring in the given `GNUPGHOME` directory
+    #[must_use]
+    pub fn with_gpg_home(mut self, path: impl Into<PathBuf>) -> Self {
+        self.gpg_home = Some(path.into());
+        self
+    }
+
+    /// Check SSH signatures against the given `allowed_signers` file
+    #[must_use]
+    pub fn with_allowed_signers(mut self, path: impl Into<PathBuf>) -> Self {
+        self.allowed_signers = Some(path.into());
+        self
+    }
+
+    /// Verify signatures of the given format with another program
+    #[must_use]
+    pub fn with_program(mut self, format: SignatureFormat, program: impl Into<String>) -> Self {
+        let program = program.into();
+        match format {
+            SignatureFormat::OpenPgp => self.gpg_program = program,
+            SignatureFormat::X509 => self.gpgsm_program = program,
+            SignatureFormat::Ssh => self.ssh_program = program,
+        }
+        self
+    }
+
+    /// Verify a signature over the content it signs
+    ///
+    /// Returns `None` if the signature is in none of the known formats.
+    #[must_use]
+    pub fn verify(&self, signature: &[u8], payload: &[u8]) -> Option<SignatureInfo> {
+        let format = SignatureFormat::detect(signature)?;
+        let result = match format {
+            SignatureFormat::OpenPgp => {
+                self.verify_gpg(&self.gpg_program, format, signature, payload)
+            }
+            SignatureFormat::X509 => {
+                self.verify_gpg(&self.gpgsm_program, format, signature, payload)
+            }
+            SignatureFormat::Ssh => self.verify_ssh(signature, payload),
+        };
+
+        Some(result.unwrap_or_else(|e| {
+            debug!("Failed to verify {:?} signature: {}", format, e);
+            SignatureInfo::new(format, SignatureStatus::Error)
+        }))
+    }
+
+    fn verify_gpg(
+        &self,
+        program: &str,
+        format: SignatureFormat,
+        signature: &[u8],
+        payload: &[u8],
+    ) -> std::io::Result<SignatureInfo> {
+        let file = SignatureFile::write(signature)?;
+        let mut command = Command::new(program);
+        command
+            .arg("--status-fd=1")
+            .arg("--verify")
+            .arg(&file.0)
+            .arg("-");
+        if let Some(home) = &self.gpg_home {
+            command.env("GNUPGHOME", home);
+        }
+
+        let output = run(command, payload)?;
+        Ok(parse_gpg_status(
+            format,
+            &String::from_utf8_lossy(&output.stdout),
+        ))
+    }
+
+    fn verify_ssh(&self, signature: &[u8], payload: &[u8]) -> std::io::Result<SignatureInfo> {
+        let Some(allowed_signers) = &self.allowed_signers else {
+            return Ok(SignatureInfo::new(
+                SignatureFormat::Ssh,
+                SignatureStatus::Unverified,
+            ));
+        };
+        let file = SignatureFile::write(signature)?;
+
+        let mut find = Command::new(&self.ssh_program);
+        find.args(["-Y", "find-principals", "-f"])
+            .arg(allowed_signers)
+            .arg("-s")
+            .arg(&file.0);
+        let found = run(find, &[])?;
+        let principal = String::from_utf8_lossy(&found.stdout)
+            .lines()
+            .next()
+            .map(str::to_string)
+            .filter(|_| found.status.success());
+
+        let mut info = SignatureInfo::new(SignatureFormat::Ssh, SignatureStatus::Bad);
+        let output = if let Some(principal) = &principal {
+            let mut verify = Command::new(&self.ssh_program);
+            verify
+                .args(["-Y", "verify", "-f"])
+                .arg(allowed_signers)
+                .args(["-I", principal, "-n", SSH_NAMESPACE, "-s"])
+                .arg(&file.0);
+            let output = run(verify, payload)?;
+            if output.status.success() {
+                info.status = SignatureStatus::Good;
+            }
+            output
+        } else {
+            // The key is not allowed, but the signature may still be intact
+            let mut check = Command::new(&self.ssh_program);
+            check
+                .args(["-Y", "check-novalidate", "-n", SSH_NAMESPACE, "-s"])
+                .arg(&file.0);
+            let output = run(check, payload)?;
+            if output.status.success() {
+                info.status = SignatureStatus::UnknownKey;
+            }
+            output
+        };
+
+        info.signer = principal;
+        // e.g. `Good "git" signature for dev@example.com with ED25519 key SHA256:...`
+        info.key = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(|line| {
+                line.rsplit_once(" key ")
+                    .map(|(_, key)| key.trim().to_string())
+            });
+        Ok(info)
+    }
+}
+
+impl Default for SignatureVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Signature of a commit, if it is signed
+///
+/// Without a verifier the signature is reported as
+/// [`SignatureStatus::Unverified`].
+#[must_use]
+pub fn commit_signature(
+    git_repo: &Git2Repository,
+    commit: Oid,
+    verifier: Option<&SignatureVerifier>,
+) -> Option<SignatureInfo> {
+    let (signature, payload) = git_repo.extract_signature(&commit, None).ok()?;
+    inspect(&signature, &payload, verifier)
+}
+
+/// Signature of an annotated tag, if it is signed
+///
+/// Without a verifier the signature is reported as
+/// [`SignatureStatus::Unverified`].
+#[must_use]
+pub fn tag_signature(
+    git_repo: &Git2Repository,
+    tag: Oid,
+    verifier: Option<&SignatureVerifier>,
+) -> Option<SignatureInfo> {
+    let odb = git_repo.odb().ok()?;
+    let object = odb.read(tag).ok()?;
+    let data = object.data();
+    let start = signature_start(data)?;
+    inspect(&data[start..], &data[..start], verifier)
+}
+
+/// A tag message without the signature appended to it
+#[must_use]
+pub fn strip_signature(message: &str) -> &str {
+    signature_start(message.as_bytes()).map_or(message, |start| &message[..start])
+}
+
+fn inspect(
+    signature: &[u8],
+    payload: &[u8],
+    verifier: Option<&SignatureVerifier>,
+) -> Option<SignatureInfo> {
+    match verifier {
+        Some(verifier) => verifier.verify(signature, payload),
+        None => SignatureFormat::detect(signature)
+            .map(|format| SignatureInfo::new(format, SignatureStatus::Unverified)),
+    }
+}
+
+/// Offset of the last line that opens a signature, as git finds tag signatures
+fn signature_start(data: &[u8]) -> Option<usize> {
+    let mut start = None;
+    let mut offset = 0;
+    for line in data.split_inclusive(|&byte| byte == b'\n') {
+        if SignatureFormat::detect(line).is_some() {
+            start = Some(offset);
+        }
+        offset += line.len();
+    }
+    start
+}
+
+/// Read the verdict from the status lines of `gpg` or `gpgsm`
+fn parse_gpg_status(format: SignatureFormat, status: &str) -> SignatureInfo {
+    let mut info = SignatureInfo::new(format, SignatureStatus::Error);
+    for line in status.lines() {
+        let Some(line) = line.strip_prefix(GPG_STATUS) else {
+            continue;
+        };
+        let mut fields = line.splitn(3, ' ');
+        let keyword = fields.next().unwrap_or_default();
+        let key = fields.next().map(str::to_string);
+
+        let verdict = match keyword {
+            "GOODSIG" => SignatureStatus::Good,
+            "BADSIG" => SignatureStatus::Bad,
+            "EXPSIG" | "EXPKEYSIG" => SignatureStatus::ExpiredKey,
+            "REVKEYSIG" => SignatureStatus::RevokedKey,
+            "NO_PUBKEY" => {
+                info.status = SignatureStatus::UnknownKey;
+                continue;
+            }
+            // The full fingerprint, after the verdict for a known key
+            "ERRSIG" | "VALIDSIG" => {
+                info.key = key;
+                continue;
+            }
+            _ => continue,
+        };
+        info.status = verdict;
+        info.key = key;
+        info.signer = fields.next().map(str::to_string);
+    }
+    info
+}
+
+/// A signature written out for a verification program, removed on drop
+struct SignatureFile(PathBuf);
+
+impl SignatureFile {
+    fn write(signature: &[u8]) -> std::io::Result<Self> {
+        let path = std::env::temp_dir().join(format!("cim-domain-git-{}.sig", Uuid::new_v4()));
+        std::fs::write(&path, signature)?;
+        Ok(Self(path))
+    }
+}
+
+impl Drop for SignatureFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Run a verification program with the signed content on standard input
+fn run(mut command: Command, payload: &[u8]) -> std::io::Result<Output> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // Programs that reject the signature may exit before reading it all
+        let _ = stdin.write_all(payload);
+    }
+    child.wait_with_output()
+}
+
+/// Branches whose commits have to be signed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignaturePolicy {
+    /// Protected branch names or patterns, where `*` does not match `/`
+    pub protected_branches: Vec<String>,
+
+    /// Require signatures to verify, rather than only to be present and not
+    /// found to be bad, expired or revoked
+    pub require_verified: bool,
+}
+
+impl SignaturePolicy {
+    /// Protect the branches matching the given names or patterns
+    #[must_use]
+    pub fn new(protected_branches: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            protected_branches: protected_branches.into_iter().map(Into::into).collect(),
+            require_verified: false,
+        }
+    }
+
+    /// Require signatures to verify with a known key
+    #[must_use]
+    pub fn require_verified(mut self) -> Self {
+        self.require_verified = true;
+        self
+    }
+
+    /// Whether a commit with the given signature satisfies the policy
+    ///
+    /// Without `require_verified`, signatures that could not be checked
+    /// against a known key are accepted, but not ones found to be invalid.
+    #[must_use]
+    pub fn accepts(&self, signature: Option<&SignatureInfo>) -> bool {
+        signature.is_some_and(|signature| match signature.status {
+            SignatureStatus::Good => true,
+            SignatureStatus::Unverified | SignatureStatus::UnknownKey => !self.require_verified,
+            SignatureStatus::Bad
+            | SignatureStatus::ExpiredKey
+            | SignatureStatus::RevokedKey
+            | SignatureStatus::Error => false,
+        })
+    }
+
+    /// Matcher for the protected branch names
+    pub(crate) fn protected(&self) -> Result<impl Fn(&BranchName) -> bool, GitDomainError> {
+        let patterns = self
+            .protected_branches
+            .iter()
+            .map(|pattern| PathGlob::full_name(pattern))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(move |branch: &BranchName| {
+            patterns
+                .iter()
+                .any(|pattern| pattern.matches(branch.as_str()))
+        })
+    }
+}
+
+/// A commit on a protected branch that does not satisfy the policy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureViolation {
+    /// Commit hash
+    pub commit_hash: CommitHash,
+
+    /// Commit author
+    pub author: AuthorInfo,
+
+    /// Protected branches the commit is on
+    pub branches: Vec<BranchName>,
+
+    /// The commit's signature, `None` for an unsigned commit
+    pub signature: Option<SignatureInfo>,
+}
+
+/// Result of checking the protected branches against a signature policy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignaturePolicyReport {
+    /// The policy checked
+    pub policy: SignaturePolicy,
+
+    /// Protected branches found in the repository
+    pub branches: Vec<BranchName>,
+
+    /// Number of distinct commits checked
+    pub commits_checked: usize,
+
+    /// Commits that do not satisfy the policy, newest first per branch
+    pub violations: Vec<SignatureViolation>,
+}
+
+impl SignaturePolicyReport {
+    /// Whether every checked commit satisfies the policy
+    #[must_use]
+    pub fn is_compliant(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_format_detection() {
+        assert_eq!(
+            SignatureFormat::detect(b"-----BEGIN PGP SIGNATURE-----\n\niQ...\n"),
+            Some(SignatureFormat::OpenPgp)
+        );
+        assert_eq!(
+            SignatureFormat::detect(b"-----BEGIN SSH SIGNATURE-----\r\nU1NI\n"),
+            Some(SignatureFormat::Ssh)
+        );
+        assert_eq!(
+            SignatureFormat::detect(b"-----BEGIN SIGNED MESSAGE-----\n"),
+            Some(SignatureFormat::X509)
+        );
+        assert_eq!(SignatureFormat::detect(b"not a signature\n"), None);
+    }
+
+    #[test]
+    fn test_strip_tag_signature() {
+        let message =
+            "Release 1.0\n\n-----BEGIN SSH SIGNATURE-----\nU1NI\n-----END SSH SIGNATURE-----\n";
+        assert_eq!(strip_signature(message), "Release 1.0\n\n");
+        assert_eq!(strip_signature("Release 1.0\n"), "Release 1.0\n");
+    }
+
+    #[test]
+    fn test_parse_gpg_status() {
+        let good = "[GNUPG:] NEWSIG\n\
+            [GNUPG:] GOODSIG 7BF1D4F088C4B528 Dev <dev@example.com>\n\
+            [GNUPG:] VALIDSIG 1263A8A91D338DD8EA70CA957BF1D4F088C4B528 2026-10-16 1792180112 0 4 0 22 8 00 1263A8A91D338DD8EA70CA957BF1D4F088C4B528\n\
+            [GNUPG:] TRUST_ULTIMATE 0 pgp\n";
+        let info = parse_gpg_status(SignatureFormat::OpenPgp, good);
+        assert!(info.is_good());
+        assert_eq!(info.signer.as_deref(), Some("Dev <dev@example.com>"));
+        assert_eq!(
+            info.key.as_deref(),
+            Some("1263A8A91D338DD8EA70CA957BF1D4F088C4B528")
+        );
+
+        let bad = "[GNUPG:] NEWSIG\n[GNUPG:] BADSIG 7BF1D4F088C4B528 Dev <dev@example.com>\n";
+        assert_eq!(
+            parse_gpg_status(SignatureFormat::OpenPgp, bad).status,
+            SignatureStatus::Bad
+        );
+
+        let unknown = "[GNUPG:] NEWSIG\n\
+            [GNUPG:] ERRSIG 7BF1D4F088C4B528 22 8 00 1792180112 9 1263A8A91D338DD8EA70CA957BF1D4F088C4B528\n\
+            [GNUPG:] NO_PUBKEY 7BF1D4F088C4B528\n";
+        let info = parse_gpg_status(SignatureFormat::OpenPgp, unknown);
+        assert_eq!(info.status, SignatureStatus::UnknownKey);
+        assert_eq!(info.key.as_deref(), Some("7BF1D4F088C4B528"));
+        assert!(info.signer.is_none());
+
+        assert_eq!(
+            parse_gpg_status(SignatureFormat::X509, "").status,
+            SignatureStatus::Error
+        );
+    }
+
+    #[test]
+    fn test_signature_policy() {
+        let policy = SignaturePolicy::new(["main", "release/*"]);
+        let protected = policy.protected().unwrap();
+        assert!(protected(&BranchName::new("main").unwrap()));
+        assert!(protected(&BranchName::new("release/1.0").unwrap()));
+        assert!(!protected(&BranchName::new("release/1.0/hotfix").unwrap()));
+        assert!(!protected(&BranchName::new("feature/main").unwrap()));
+
+        let unverified = SignatureInfo::new(SignatureFormat::Ssh, SignatureStatus::Unverified);
+        assert!(!policy.accepts(None));
+        assert!(policy.accepts(Some(&unverified)));
+        assert!(!policy.clone().require_verified().accepts(Some(&unverified)));
+
+        // Invalid signatures are violations even when only presence is required
+        for status in [
+            SignatureStatus::Bad,
+            SignatureStatus::ExpiredKey,
+            SignatureStatus::RevokedKey,
+            SignatureStatus::Error,
+        ] {
+            let signature = SignatureInfo::new(SignatureFormat::Ssh, status);
+            assert!(!policy.accepts(Some(&signature)));
+        }
+        let unknown = SignatureInfo::new(SignatureFormat::Ssh, SignatureStatus::UnknownKey);
+        assert!(policy.accepts(Some(&unknown)));
+    }
+}
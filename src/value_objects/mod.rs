@@ -5,8 +5,11 @@
 //! Value objects are immutable and represent concepts in the Git domain
 //! that are defined by their attributes rather than identity.
 
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
+use std::sync::LazyLock;
 
 #[cfg(test)]
 mod tests;
@@ -235,6 +238,23 @@ impl RemoteUrl {
     pub fn is_github(&self) -> bool {
         self.0.contains("github.com")
     }
+
+    /// Check if this URL refers to a repository on the local filesystem
+    ///
+    /// Follows Git's own rule: anything without a scheme is a path unless it
+    /// is an scp-like `host:path`, where the colon comes before any slash and
+    /// is not part of a Windows drive such as `C:\`.
+    #[must_use]
+    pub fn is_local(&self) -> bool {
+        if let Some((scheme, _)) = self.0.split_once("://") {
+            return scheme == "file";
+        }
+        let Some(colon) = self.0.find(':') else {
+            return true;
+        };
+        let drive = colon == 1 && self.0.starts_with(|c: char| c.is_ascii_alphabetic());
+        drive || self.0.find(['/', '\\']).is_some_and(|slash| slash < colon)
+    }
 }
 
 impl fmt::Display for RemoteUrl {
@@ -339,6 +359,12 @@ impl TagName {
     pub fn is_semver(&self) -> bool {
         self.0.starts_with('v') && self.0[1..].chars().next().is_some_and(char::is_numeric)
     }
+    
+    /// Parse the semantic version the tag names, such as `v1.2.3` or `1.2.3`
+    #[must_use]
+    pub fn semver(&self) -> Option<SemanticVersion> {
+        self.0.parse().ok()
+    }
 }
 
 impl fmt::Display for TagName {
@@ -347,6 +373,161 @@ impl fmt::Display for TagName {
     }
 }
 
+/// A semantic version, as named by release tags
+///
+/// Versions are ordered by Semantic Versioning precedence: a pre-release
+/// sorts before its release, and pre-release identifiers compare numerically
+/// when both are numbers. Build metadata has no precedence and only breaks
+/// ties, so that ordering agrees with equality.
+///
+/// # Examples
+///
+/// ```
+/// use cim_domain_git::value_objects::SemanticVersion;
+///
+/// let rc: SemanticVersion = "v1.2.3-rc.1".parse().unwrap();
+/// assert_eq!((rc.major, rc.minor, rc.patch), (1, 2, 3));
+/// assert_eq!(rc.to_string(), "1.2.3-rc.1");
+///
+/// let release: SemanticVersion = "1.2.3".parse().unwrap();
+/// assert!(rc < release);
+/// assert!(release < SemanticVersion::new(1, 10, 0));
+/// assert!("1.2".parse::<SemanticVersion>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SemanticVersion {
+    /// Major version, raised for incompatible changes
+    pub major: u64,
+    
+    /// Minor version, raised for new functionality
+    pub minor: u64,
+    
+    /// Patch version, raised for fixes
+    pub patch: u64,
+    
+    /// Dot-separated pre-release identifiers, such as `rc.1`
+    pub pre_release: Option<String>,
+    
+    /// Dot-separated build metadata
+    pub build: Option<String>,
+}
+
+impl SemanticVersion {
+    /// Create a release version
+    #[must_use]
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            pre_release: None,
+            build: None,
+        }
+    }
+    
+    /// Whether this is a pre-release version
+    #[must_use]
+    pub fn is_pre_release(&self) -> bool {
+        self.pre_release.is_some()
+    }
+}
+
+impl std::str::FromStr for SemanticVersion {
+    type Err = crate::GitDomainError;
+    
+    /// Parse `MAJOR.MINOR.PATCH[-PRE][+BUILD]`, optionally prefixed with `v`
+    fn from_str(version: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            crate::GitDomainError::ValidationError(format!("Invalid semantic version: {version}"))
+        };
+        let identifiers = |part: &str| {
+            part.split('.').all(|identifier| {
+                !identifier.is_empty()
+                    && identifier.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            })
+        };
+        
+        let rest = version.strip_prefix('v').unwrap_or(version);
+        let (rest, build) = match rest.split_once('+') {
+            Some((rest, build)) if identifiers(build) => (rest, Some(build.to_string())),
+            Some(_) => return Err(invalid()),
+            None => (rest, None),
+        };
+        let (core, pre_release) = match rest.split_once('-') {
+            Some((core, pre)) if identifiers(pre) => (core, Some(pre.to_string())),
+            Some(_) => return Err(invalid()),
+            None => (rest, None),
+        };
+        
+        let numbers = core
+            .split('.')
+            .map(|number| {
+                let leading_zero = number.len() > 1 && number.starts_with('0');
+                if leading_zero || !number.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                number.parse::<u64>().ok()
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(invalid)?;
+        let [major, minor, patch] = numbers[..] else {
+            return Err(invalid());
+        };
+        
+        Ok(Self {
+            major,
+            minor,
+            patch,
+            pre_release,
+            build,
+        })
+    }
+}
+
+impl Ord for SemanticVersion {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        use std::cmp::Ordering;
+        
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.pre_release, &other.pre_release) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(ours), Some(theirs)) => {
+                    let identifier = |identifier: &str| match identifier.parse::<u64>() {
+                        // Numeric identifiers sort before alphanumeric ones
+                        Ok(number) => (false, number, String::new()),
+                        Err(_) => (true, 0, identifier.to_string()),
+                    };
+                    ours.split('.')
+                        .map(identifier)
+                        .cmp(theirs.split('.').map(identifier))
+                }
+            })
+            .then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+impl PartialOrd for SemanticVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for SemanticVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre_release) = &self.pre_release {
+            write!(f, "-{pre_release}")?;
+        }
+        if let Some(build) = &self.build {
+            write!(f, "+{build}")?;
+        }
+        Ok(())
+    }
+}
+
 /// File path within a Git repository
 ///
 /// File paths are normalized to use forward slashes regardless of platform.
@@ -544,6 +725,131 @@ impl CommitMessage {
     pub fn as_str(&self) -> &str {
         &self.0
     }
+    
+    /// Get the trailers at the end of the message, in order
+    ///
+    /// Trailers are read from the last paragraph of the body, and only if
+    /// every line of it is a trailer, an indented continuation of one or a
+    /// `(cherry picked from commit ...)` note. Besides git's `Key: value`,
+    /// the Conventional Commits footers `BREAKING CHANGE: text` and
+    /// `Closes #12` are trailers; the value of the latter keeps its `#`.
+    #[must_use]
+    pub fn trailers(&self) -> Vec<Trailer> {
+        let Some(body) = self.body() else {
+            return Vec::new();
+        };
+        let mut paragraph: Vec<&str> = body
+            .lines()
+            .rev()
+            .skip_while(|line| line.trim().is_empty())
+            .take_while(|line| !line.trim().is_empty())
+            .collect();
+        paragraph.reverse();
+        
+        let mut trailers: Vec<Trailer> = Vec::new();
+        for line in paragraph {
+            if line.starts_with(char::is_whitespace) {
+                match trailers.last_mut() {
+                    Some(trailer) => {
+                        trailer.value.push(' ');
+                        trailer.value.push_str(line.trim());
+                    }
+                    None => return Vec::new(),
+                }
+            } else if !line.starts_with("(cherry picked from commit ") {
+                // `git cherry-pick -x` notes may sit among the trailers
+                let Some(captures) = TRAILER_LINE.captures(line) else {
+                    return Vec::new();
+                };
+                let value = captures.get(2).or_else(|| captures.get(3));
+                trailers.push(Trailer {
+                    key: captures[1].to_string(),
+                    value: value.map_or("", |value| value.as_str()).trim().to_string(),
+                });
+            }
+        }
+        
+        trailers
+    }
+    
+    /// Parse the Conventional Commits header of the summary line, if it has one
+    ///
+    /// The commit is breaking when its type or scope is followed by `!` or its
+    /// body has a `BREAKING CHANGE:` footer.
+    #[must_use]
+    pub fn conventional(&self) -> Option<ConventionalCommit> {
+        let header = self.0.lines().next().unwrap_or_default();
+        let captures = CONVENTIONAL_HEADER.captures(header)?;
+        
+        Some(ConventionalCommit {
+            commit_type: captures[1].to_lowercase(),
+            scope: captures.get(2).map(|scope| scope.as_str().trim().to_string()),
+            breaking: captures.get(3).is_some() || self.breaking_change().is_some(),
+            description: captures[4].trim().to_string(),
+        })
+    }
+    
+    /// Get the text of the `BREAKING CHANGE:` footer, if the body has one
+    #[must_use]
+    pub fn breaking_change(&self) -> Option<&str> {
+        let captures = BREAKING_CHANGE.captures(self.body()?)?;
+        captures.get(1).map(|text| text.as_str().trim())
+    }
+    
+    /// Get the issues the message refers to, in order of first mention
+    ///
+    /// Recognises `#123` for the repository's own tracker and `JIRA-42` style
+    /// keys of external trackers. Keys of well-known standards and encodings,
+    /// such as `UTF-8` or `ISO-8601`, are not taken for issues.
+    #[must_use]
+    pub fn issue_references(&self) -> Vec<IssueReference> {
+        let numbers = ISSUE_NUMBER.captures_iter(&self.0).filter_map(|captures| {
+            let number = captures.get(1)?;
+            Some((number.start(), IssueReference::Number(number.as_str().parse().ok()?)))
+        });
+        let keys = ISSUE_KEY.captures_iter(&self.0).filter_map(|captures| {
+            let project = captures.get(1)?;
+            if NOT_ISSUE_PROJECTS.contains(&project.as_str()) {
+                return None;
+            }
+            Some((
+                project.start(),
+                IssueReference::Key {
+                    project: project.as_str().to_string(),
+                    number: captures[2].parse().ok()?,
+                },
+            ))
+        });
+        
+        let mut found: Vec<(usize, IssueReference)> = numbers.chain(keys).collect();
+        found.sort_by_key(|(position, _)| *position);
+        
+        let mut references: Vec<IssueReference> = Vec::new();
+        for (_, reference) in found {
+            if !references.contains(&reference) {
+                references.push(reference);
+            }
+        }
+        references
+    }
+    
+    /// Parse the message into its structured parts
+    #[must_use]
+    pub fn parse(&self) -> ParsedCommitMessage {
+        let mut trailers: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for trailer in self.trailers() {
+            trailers.entry(trailer.key).or_default().push(trailer.value);
+        }
+        
+        let conventional = self.conventional();
+        ParsedCommitMessage {
+            breaking: self.breaking_change().is_some()
+                || conventional.as_ref().is_some_and(|conventional| conventional.breaking),
+            conventional,
+            trailers,
+            issue_references: self.issue_references(),
+        }
+    }
 }
 
 impl fmt::Display for CommitMessage {
@@ -552,3 +858,168 @@ impl fmt::Display for CommitMessage {
     }
 }
 
+/// A `Key: value` trailer at the end of a commit message
+///
+/// # Examples
+///
+/// ```
+/// use cim_domain_git::value_objects::CommitMessage;
+///
+/// let msg = CommitMessage::new(
+///     "Fix parser\n\nHandle empty input.\n\n\
+///      Reviewed-by: Jane Doe <jane@example.com>\n\
+///      Signed-off-by: John Smith <john@example.com>",
+/// )
+/// .unwrap();
+///
+/// let trailers = msg.trailers();
+/// assert_eq!(trailers.len(), 2);
+/// assert!(trailers[0].is("reviewed-by"));
+/// assert_eq!(trailers[0].identity().unwrap().email, "jane@example.com");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Trailer {
+    /// Trailer key, such as `Reviewed-by`
+    pub key: String,
+    
+    /// Trailer value, with continuation lines joined by spaces
+    pub value: String,
+}
+
+impl Trailer {
+    /// Whether the trailer has the given key, ignoring case
+    #[must_use]
+    pub fn is(&self, key: &str) -> bool {
+        self.key.eq_ignore_ascii_case(key)
+    }
+    
+    /// The person a value like `Jane Doe <jane@example.com>` names
+    #[must_use]
+    pub fn identity(&self) -> Option<AuthorInfo> {
+        let (name, email) = self.value.rsplit_once('<')?;
+        let email = email.strip_suffix('>')?.trim();
+        if email.is_empty() {
+            return None;
+        }
+        
+        let name = name.trim();
+        Some(AuthorInfo::new(if name.is_empty() { email } else { name }, email))
+    }
+}
+
+/// `type(scope)!: description` header of a Conventional Commit
+static CONVENTIONAL_HEADER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^([A-Za-z][A-Za-z0-9-]*)(?:\(([^()]+)\))?(!)?: (\S.*)$").expect("Invalid regex")
+});
+
+/// `Key: value` trailer or `Key #value` footer
+static TRAILER_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(BREAKING[ -]CHANGE|[A-Za-z0-9-]+)(?::(.*)| (#.*))$").expect("Invalid regex")
+});
+
+/// `BREAKING CHANGE:` footer, also spelt `BREAKING-CHANGE:`
+static BREAKING_CHANGE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^BREAKING[ -]CHANGE: (.+)$").expect("Invalid regex"));
+
+/// `#123` not preceded by a word, path or entity
+static ISSUE_NUMBER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|[^\w/&#])#(\d+)\b").expect("Invalid regex"));
+
+/// `PROJECT-123` issue key
+static ISSUE_KEY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b([A-Z][A-Z0-9_]+)-(\d+)\b").expect("Invalid regex"));
+
+/// Prefixes that look like issue keys but name standards and encodings
+const NOT_ISSUE_PROJECTS: [&str; 5] = ["CVE", "ISO", "RFC", "SHA", "UTF"];
+
+/// Header of a Conventional Commit, such as `feat(parser)!: drop v1 syntax`
+///
+/// # Examples
+///
+/// ```
+/// use cim_domain_git::value_objects::CommitMessage;
+///
+/// let msg = CommitMessage::new("feat(parser)!: Drop v1 syntax\n\nCloses #12").unwrap();
+/// let conventional = msg.conventional().unwrap();
+/// assert_eq!(conventional.commit_type, "feat");
+/// assert_eq!(conventional.scope.as_deref(), Some("parser"));
+/// assert!(conventional.breaking);
+/// assert_eq!(conventional.description, "Drop v1 syntax");
+///
+/// assert!(CommitMessage::new("Fix typo").unwrap().conventional().is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ConventionalCommit {
+    /// Change type, lowercased, such as `feat` or `fix`
+    pub commit_type: String,
+    
+    /// Scope given in parentheses after the type
+    pub scope: Option<String>,
+    
+    /// Whether the commit is marked as a breaking change
+    pub breaking: bool,
+    
+    /// Description following the colon
+    pub description: String,
+}
+
+/// An issue a commit message refers to
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IssueReference {
+    /// `#123`, an issue or pull request of the repository's own tracker
+    Number(u64),
+    
+    /// `JIRA-42`, an issue of a project in an external tracker
+    Key {
+        /// Project key
+        project: String,
+        
+        /// Issue number within the project
+        number: u64,
+    },
+}
+
+impl fmt::Display for IssueReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(number) => write!(f, "#{number}"),
+            Self::Key { project, number } => write!(f, "{project}-{number}"),
+        }
+    }
+}
+
+/// Structured representation of a commit message
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParsedCommitMessage {
+    /// Conventional Commits header, if the summary line has one
+    pub conventional: Option<ConventionalCommit>,
+    
+    /// Whether the commit is a breaking change, by its header or a
+    /// `BREAKING CHANGE:` footer
+    pub breaking: bool,
+    
+    /// Trailer values by key, in message order
+    pub trailers: BTreeMap<String, Vec<String>>,
+    
+    /// Issues the message refers to
+    pub issue_references: Vec<IssueReference>,
+}
+
+impl ParsedCommitMessage {
+    /// Conventional Commit type, if the message follows Conventional Commits
+    #[must_use]
+    pub fn change_type(&self) -> Option<&str> {
+        self.conventional.as_ref().map(|conventional| conventional.commit_type.as_str())
+    }
+    
+    /// Values of a trailer, with the key matched ignoring case
+    #[must_use]
+    pub fn trailer(&self, key: &str) -> Vec<&str> {
+        self.trailers
+            .iter()
+            .filter(|(candidate, _)| candidate.eq_ignore_ascii_case(key))
+            .flat_map(|(_, values)| values.iter().map(String::as_str))
+            .collect()
+    }
+}
+
@@ -37,6 +37,29 @@ mod tests {
         assert!(RemoteUrl::new("://no-protocol.com").is_err());
     }
 
+    #[test]
+    fn test_remote_url_is_local() {
+        for url in ["file:///path/to/repo", "/path/to/repo", "../repo", "./repo"] {
+            assert!(RemoteUrl::new(url).unwrap().is_local(), "{url}");
+        }
+        for url in [
+            "https://github.com/user/repo.git",
+            "ssh://git@github.com/user/repo.git",
+            "git@github.com:user/repo.git",
+            "git://github.com/user/repo.git",
+        ] {
+            assert!(!RemoteUrl::new(url).unwrap().is_local(), "{url}");
+        }
+
+        // Paths the constructor rejects can still arrive deserialized
+        let url = |url: &str| serde_json::from_value::<RemoteUrl>(url.into()).unwrap();
+        assert!(url("repo").is_local());
+        assert!(url("C:\\repos\\project").is_local());
+        assert!(url("c:/repos/project").is_local());
+        assert!(url("repos/a:b").is_local());
+        assert!(!url("host:repo.git").is_local());
+    }
+
     #[test]
     fn test_commit_hash_valid() {
         // Short hashes (minimum 7 characters)
@@ -114,6 +137,57 @@ mod tests {
         assert!(TagName::new("tag~tilde").is_err());
     }
 
+    #[test]
+    fn test_tag_name_semver() {
+        let version = TagName::new("v1.2.3").unwrap().semver().unwrap();
+        assert_eq!(version, SemanticVersion::new(1, 2, 3));
+        
+        let version = TagName::new("2.0.0-beta.2+build.5").unwrap().semver().unwrap();
+        assert_eq!(version.pre_release.as_deref(), Some("beta.2"));
+        assert_eq!(version.build.as_deref(), Some("build.5"));
+        assert_eq!(version.to_string(), "2.0.0-beta.2+build.5");
+        
+        assert!(TagName::new("release-1.0.0").unwrap().semver().is_none());
+        assert!(TagName::new("v1.02.3").unwrap().semver().is_none());
+        assert!(TagName::new("v1.2.3-").unwrap().semver().is_none());
+        assert!(TagName::new("v1.2.3.4").unwrap().semver().is_none());
+    }
+
+    #[test]
+    fn test_semantic_version_ordering() {
+        let mut versions: Vec<SemanticVersion> = [
+            "1.0.0",
+            "1.0.0-rc.1",
+            "1.0.0-alpha",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta.11",
+            "1.0.0-beta.2",
+            "1.0.0-alpha.1",
+            "0.10.0",
+            "0.9.9",
+        ]
+        .iter()
+        .map(|v| v.parse().unwrap())
+        .collect();
+        versions.sort();
+        
+        let sorted: Vec<String> = versions.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            sorted,
+            [
+                "0.9.9",
+                "0.10.0",
+                "1.0.0-alpha",
+                "1.0.0-alpha.1",
+                "1.0.0-alpha.beta",
+                "1.0.0-beta.2",
+                "1.0.0-beta.11",
+                "1.0.0-rc.1",
+                "1.0.0",
+            ]
+        );
+    }
+
     #[test]
     fn test_file_path_valid() {
         assert!(FilePath::new("src/main.rs").is_ok());
@@ -187,6 +261,110 @@ mod tests {
         assert_eq!(msg.body(), None); // Empty body
     }
 
+    #[test]
+    fn test_commit_message_trailers() {
+        let msg = CommitMessage::new(
+            "Fix parser\n\nReviewed-by: Should not count\n\nLonger explanation.\n\n\
+             Reviewed-by: Jane Doe <jane@example.com>\n\
+             Link: https://example.com/\n  issues/42\n\
+             (cherry picked from commit abc123def456789)\n\
+             Signed-off-by: <john@example.com>",
+        )
+        .unwrap();
+        let trailers = msg.trailers();
+        assert_eq!(trailers.len(), 3);
+        assert_eq!(trailers[0].key, "Reviewed-by");
+        assert_eq!(
+            trailers[0].identity(),
+            Some(AuthorInfo::new("Jane Doe", "jane@example.com"))
+        );
+        assert_eq!(trailers[1].value, "https://example.com/ issues/42");
+        assert_eq!(trailers[1].identity(), None);
+        assert!(trailers[2].is("SIGNED-OFF-BY"));
+        assert_eq!(
+            trailers[2].identity(),
+            Some(AuthorInfo::new("john@example.com", "john@example.com"))
+        );
+
+        // The last paragraph must consist of trailers only
+        let msg =
+            CommitMessage::new("Subject\n\nAcked-by: Jane <jane@example.com>\nThanks!").unwrap();
+        assert!(msg.trailers().is_empty());
+
+        // The summary is never a trailer
+        let msg = CommitMessage::new("fix: typo").unwrap();
+        assert!(msg.trailers().is_empty());
+    }
+
+    #[test]
+    fn test_commit_message_conventional_footers_are_trailers() {
+        let msg = CommitMessage::new(
+            "feat: x\n\nBREAKING CHANGE: y\nCloses #12\nReviewed-by: Z <z@x>",
+        )
+        .unwrap();
+        let trailers = msg.trailers();
+        assert_eq!(trailers.len(), 3);
+        assert_eq!(trailers[0].key, "BREAKING CHANGE");
+        assert_eq!(trailers[0].value, "y");
+        assert_eq!(trailers[1].key, "Closes");
+        assert_eq!(trailers[1].value, "#12");
+        assert!(trailers[2].is("reviewed-by"));
+        assert_eq!(trailers[2].identity(), Some(AuthorInfo::new("Z", "z@x")));
+
+        let parsed = msg.parse();
+        assert_eq!(parsed.trailer("Closes"), vec!["#12"]);
+        assert!(parsed.breaking);
+    }
+
+    #[test]
+    fn test_commit_message_conventional() {
+        let msg = CommitMessage::new("Feat(core): Add streaming API").unwrap();
+        let conventional = msg.conventional().unwrap();
+        assert_eq!(conventional.commit_type, "feat");
+        assert_eq!(conventional.scope.as_deref(), Some("core"));
+        assert!(!conventional.breaking);
+        assert_eq!(conventional.description, "Add streaming API");
+        
+        let msg = CommitMessage::new(
+            "refactor: Rename events\n\nBREAKING-CHANGE: subjects use the new names",
+        )
+        .unwrap();
+        assert!(msg.conventional().unwrap().breaking);
+        assert_eq!(msg.breaking_change(), Some("subjects use the new names"));
+        
+        assert!(CommitMessage::new("Merge branch 'main'").unwrap().conventional().is_none());
+        assert!(CommitMessage::new("fix:no space").unwrap().conventional().is_none());
+        assert!(CommitMessage::new("fix(): empty scope").unwrap().conventional().is_none());
+    }
+
+    #[test]
+    fn test_commit_message_issue_references() {
+        let msg = CommitMessage::new(
+            "Fix crash on UTF-8 input (#12)\n\nSee PROJ-42, https://example.com/a#7 and &#38;.\n\n\
+             Refs: #12, #3\nCloses: PROJ-42",
+        )
+        .unwrap();
+        assert_eq!(
+            msg.issue_references(),
+            vec![
+                IssueReference::Number(12),
+                IssueReference::Key {
+                    project: "PROJ".to_string(),
+                    number: 42,
+                },
+                IssueReference::Number(3),
+            ]
+        );
+        assert_eq!(msg.issue_references()[1].to_string(), "PROJ-42");
+        
+        let parsed = msg.parse();
+        assert!(parsed.conventional.is_none());
+        assert!(!parsed.breaking);
+        assert_eq!(parsed.trailer("refs"), vec!["#12, #3"]);
+        assert_eq!(parsed.trailers["Closes"], vec!["PROJ-42".to_string()]);
+        assert_eq!(parsed.issue_references.len(), 3);
+    }
+
     #[test]
     fn test_author_info() {
         let author = AuthorInfo {
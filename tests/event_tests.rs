@@ -16,8 +16,9 @@ use chrono::Utc;
 use cim_domain_git::{
     aggregate::{Repository, RepositoryId},
     events::*,
-    value_objects::{AuthorInfo, BranchName, CommitHash, FilePath, RemoteUrl},
+    value_objects::{AuthorInfo, BranchName, CommitHash, FilePath, IssueReference, RemoteUrl},
 };
+use std::collections::HashMap;
 
 #[test]
 fn test_repository_analyzed_event() {
@@ -27,6 +28,8 @@ fn test_repository_analyzed_event() {
         name: "test-repo".to_string(),
         branch_count: 5,
         commit_count: 100,
+        ref_tips: HashMap::new(),
+        head_branch: None,
         timestamp: Utc::now(),
     };
 
@@ -66,6 +69,7 @@ fn test_branch_created_event() {
         branch_name: BranchName::new("feature/test").unwrap(),
         commit_hash: CommitHash::new("abc123def456").unwrap(),
         source_branch: Some(BranchName::new("main").unwrap()),
+        checked_out: false,
         timestamp: Utc::now(),
     };
 
@@ -86,13 +90,20 @@ fn test_commit_analyzed_event() {
         commit_hash: CommitHash::new("abc123def456").unwrap(),
         parents: vec![CommitHash::new("def456abc789").unwrap()],
         author: AuthorInfo::new("John Doe".to_string(), "john@example.com".to_string()),
+        raw_author: None,
         message: "Test commit".to_string(),
+        parsed_message: Default::default(),
+        signature: None,
         files_changed: vec![FileChangeInfo {
             path: FilePath::new("src/main.rs").unwrap(),
             change_type: FileChangeType::Modified,
             additions: 10,
             deletions: 5,
+            old_path: None,
+            binary: false,
         }],
+        parent_diffs: vec![],
+        combined_files: vec![],
         commit_timestamp: Utc::now(),
         timestamp: Utc::now(),
     };
@@ -150,6 +161,7 @@ fn test_aggregate_event_application() {
         branch_name: BranchName::new("feature/new").unwrap(),
         commit_hash: CommitHash::new("abc123def456789").unwrap(),
         source_branch: None,
+        checked_out: false,
         timestamp: Utc::now(),
     });
 
@@ -164,8 +176,13 @@ fn test_aggregate_event_application() {
         commit_hash: CommitHash::new("abc123def456789").unwrap(),
         parents: vec![],
         author: AuthorInfo::new("Test Author".to_string(), "test@example.com".to_string()),
+        raw_author: None,
         message: "Test commit".to_string(),
+        parsed_message: Default::default(),
+        signature: None,
         files_changed: vec![],
+        parent_diffs: vec![],
+        combined_files: vec![],
         commit_timestamp: Utc::now(),
         timestamp: Utc::now(),
     });
@@ -188,6 +205,8 @@ fn test_tag_created_event() {
             "Jane Doe".to_string(),
             "jane@example.com".to_string(),
         )),
+        annotated: true,
+        signature: None,
         timestamp: Utc::now(),
     };
 
@@ -202,6 +221,26 @@ fn test_tag_created_event() {
     assert_eq!(deserialized.tagger, event.tagger);
 }
 
+#[test]
+fn test_repository_analyzed_restores_name() {
+    // Aggregates rebuilt from events start without a name
+    let mut repo = Repository::new(String::new());
+    repo.apply_event(&GitDomainEvent::RepositoryAnalyzed(RepositoryAnalyzed {
+        repository_id: repo.id,
+        path: "/home/user/repo".to_string(),
+        name: "repo".to_string(),
+        branch_count: 0,
+        commit_count: 0,
+        ref_tips: HashMap::new(),
+        head_branch: None,
+        timestamp: Utc::now(),
+    }))
+    .unwrap();
+
+    assert_eq!(repo.metadata.name, "repo");
+    assert_eq!(repo.local_path.as_deref(), Some("/home/user/repo"));
+}
+
 #[test]
 fn test_event_enum_serialization() {
     let event = GitDomainEvent::RepositoryAnalyzed(RepositoryAnalyzed {
@@ -210,6 +249,8 @@ fn test_event_enum_serialization() {
         name: "test".to_string(),
         branch_count: 1,
         commit_count: 1,
+        ref_tips: HashMap::new(),
+        head_branch: None,
         timestamp: Utc::now(),
     });
 
@@ -224,3 +265,61 @@ fn test_event_enum_serialization() {
         _ => panic!("Wrong event type deserialized"),
     }
 }
+
+#[test]
+fn test_v1_events_upcast_to_current_schema() {
+    let upcasters = UpcasterRegistry::new();
+
+    let tag = upcasters
+        .decode(include_bytes!("fixtures/events/v1/tag_created.json"))
+        .unwrap();
+    assert_eq!(tag.metadata.schema_version, CURRENT_SCHEMA_VERSION);
+    match tag.event {
+        GitDomainEvent::TagCreated(e) => {
+            assert!(e.annotated);
+            assert_eq!(e.message.as_deref(), Some("Release 1.0.0"));
+        }
+        _ => panic!("Wrong event type deserialized"),
+    }
+
+    let commit = upcasters
+        .decode(include_bytes!("fixtures/events/v1/commit_analyzed.json"))
+        .unwrap();
+    match commit.event {
+        GitDomainEvent::CommitAnalyzed(e) => {
+            assert_eq!(e.parsed_message.change_type(), Some("feat"));
+            assert_eq!(
+                e.parsed_message.issue_references,
+                vec![IssueReference::Number(12)]
+            );
+            assert!(e.signature.is_none());
+            assert!(e.parent_diffs.is_empty());
+        }
+        _ => panic!("Wrong event type deserialized"),
+    }
+}
+
+#[test]
+fn test_v1_events_replay_into_repository() {
+    let upcasters = UpcasterRegistry::new();
+    let fixtures: [&[u8]; 3] = [
+        include_bytes!("fixtures/events/v1/repository_cloned.json"),
+        include_bytes!("fixtures/events/v1/branch_created.json"),
+        include_bytes!("fixtures/events/v1/tag_created.json"),
+    ];
+
+    let mut repo = Repository::new("fixture-repo".to_string());
+    for fixture in fixtures {
+        let envelope = upcasters.decode(fixture).unwrap();
+        if let GitDomainEvent::BranchCreated(e) = &envelope.event {
+            assert!(!e.checked_out);
+        }
+        repo.apply_event(&envelope.event).unwrap();
+    }
+
+    assert_eq!(repo.version, 3);
+    assert_eq!(repo.local_path.as_deref(), Some("/tmp/repo"));
+    assert!(repo
+        .branches
+        .contains_key(&BranchName::new("release/1.0").unwrap()));
+}
@@ -17,6 +17,7 @@ use cim_domain_git::{
     aggregate::RepositoryId,
     commands::*,
     handlers::*,
+    signature::SignaturePolicy,
     value_objects::{BranchName, CommitHash, FilePath, RemoteUrl, TagName},
 };
 
@@ -203,6 +204,42 @@ fn test_compare_branches_handler() {
     assert_eq!(ack.reason, Some("Repository not found".to_string()));
 }
 
+#[test]
+fn test_generate_release_notes_handler() {
+    let repo_handler = RepositoryCommandHandler::new();
+    let mut handler = GenerateReleaseNotesHandler::new(repo_handler);
+
+    let command = GenerateReleaseNotes {
+        repository_id: RepositoryId::new(),
+        from_tag: Some(TagName::new("v1.0.0").unwrap()),
+        to_tag: None,
+    };
+
+    let envelope = create_test_envelope(command);
+    let ack = handler.handle(envelope);
+
+    assert_eq!(ack.status, CommandStatus::Rejected);
+    assert_eq!(ack.reason, Some("Repository not found".to_string()));
+}
+
+#[test]
+fn test_check_signature_policy_handler() {
+    let repo_handler = RepositoryCommandHandler::new();
+    let mut handler = CheckSignaturePolicyHandler::new(repo_handler);
+
+    let command = CheckSignaturePolicy {
+        repository_id: RepositoryId::new(),
+        policy: SignaturePolicy::new(["main"]),
+        max_commits: None,
+    };
+
+    let envelope = create_test_envelope(command);
+    let ack = handler.handle(envelope);
+
+    assert_eq!(ack.status, CommandStatus::Rejected);
+    assert_eq!(ack.reason, Some("Repository not found".to_string()));
+}
+
 #[test]
 fn test_search_repository_handler() {
     let repo_handler = RepositoryCommandHandler::new();
@@ -215,6 +252,8 @@ fn test_search_repository_handler() {
         exclude_patterns: vec!["target/".to_string()],
         case_sensitive: false,
         max_results: Some(100),
+        revision: None,
+        pickaxe: false,
     };
 
     let envelope = create_test_envelope(command);
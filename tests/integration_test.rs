@@ -2,12 +2,26 @@
 
 //! Integration tests for Git domain with real repositories
 
+use chrono::{DateTime, Utc};
 use cim_domain_git::{
+    aggregate::RepositoryId,
+    analyzers::{BlameOwnershipOptions, CodeOwners, CollaborationAnalyzer},
     commands::*,
-    events::{FileChangeType, GitDomainEvent},
-    handlers::RepositoryCommandHandler,
+    events::{
+        collaboration_events::{CodeOwnershipCalculated, CommitRelationshipType},
+        FileChangeType, GitDomainEvent, RefUpdateType,
+    },
+    handlers::{AnalysisOptions, RepositoryCommandHandler},
+    identity::AliasRules,
+    projections::{FileChangeProjection, TagProjection},
+    release::VersionBump,
+    signature::{SignatureFormat, SignaturePolicy, SignatureStatus, SignatureVerifier},
+    value_objects::{AuthorInfo, BranchName, CommitHash, FilePath, RemoteUrl, TagName},
+    GitDomainError,
 };
-use git2::{Repository, Signature};
+use futures::StreamExt;
+use git2::{Oid, Repository, Signature, Time};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 use tempfile::TempDir;
@@ -213,7 +227,7 @@ async fn test_branch_operations() {
     let handler = RepositoryCommandHandler::new();
 
     // Analyze repository
-    let (_repo_id, initial_events) = handler
+    let (repo_id, initial_events) = handler
         .analyze_repository_at_path(temp_dir.path().to_str().unwrap())
         .await
         .unwrap();
@@ -233,7 +247,7 @@ async fn test_branch_operations() {
     }
 
     // Re-analyze repository
-    let (_repo_id2, new_events) = handler
+    let (repo_id2, new_events) = handler
         .analyze_repository_at_path(temp_dir.path().to_str().unwrap())
         .await
         .unwrap();
@@ -243,7 +257,13 @@ async fn test_branch_operations() {
         .filter(|e| matches!(e, GitDomainEvent::BranchCreated(_)))
         .count();
 
-    assert_eq!(new_branch_count, initial_branch_count + 1);
+    // Only the new branch is reported for the already known repository
+    assert_eq!(repo_id2, repo_id);
+    assert_eq!(new_branch_count, 1);
+    assert_eq!(
+        handler.get_repository(&repo_id).unwrap().branches.len(),
+        initial_branch_count + 1
+    );
 }
 
 #[tokio::test]
@@ -271,3 +291,2811 @@ async fn test_empty_repository() {
         .count();
     assert_eq!(commit_count, 0);
 }
+
+fn clone_command(source: &TempDir, target: &Path) -> CloneRepository {
+    CloneRepository {
+        repository_id: None,
+        remote_url: RemoteUrl::new(format!("file://{}", source.path().display())).unwrap(),
+        local_path: target.to_str().unwrap().to_string(),
+        branch: None,
+        depth: None,
+    }
+}
+
+#[tokio::test]
+async fn test_clone_local_repository() {
+    let source = create_test_repo();
+    let target = TempDir::new().unwrap();
+    let clone_path = target.path().join("clone");
+
+    let handler = RepositoryCommandHandler::new();
+    let mut observed = 0;
+    let (repo_id, events) = handler
+        .clone_repository_with_progress(&clone_command(&source, &clone_path), |_| observed += 1)
+        .await
+        .unwrap();
+
+    assert!(clone_path.join("lib.rs").exists());
+    assert!(matches!(
+        events.last(),
+        Some(GitDomainEvent::RepositoryCloned(e)) if e.repository_id == repo_id
+    ));
+
+    let progress_events = events
+        .iter()
+        .filter(|e| matches!(e, GitDomainEvent::CloneProgressUpdated(_)))
+        .count();
+    assert!(progress_events > 0);
+    assert_eq!(progress_events, observed);
+
+    let stored = handler.get_repository(&repo_id).unwrap();
+    assert_eq!(stored.local_path.as_deref(), clone_path.to_str());
+}
+
+#[tokio::test]
+async fn test_clone_specific_branch() {
+    let source = create_test_repo();
+    let target = TempDir::new().unwrap();
+    let clone_path = target.path().join("clone");
+
+    let mut command = clone_command(&source, &clone_path);
+    command.branch = Some(BranchName::new("feature-branch").unwrap());
+
+    let handler = RepositoryCommandHandler::new();
+    handler.clone_repository(&command).await.unwrap();
+
+    let cloned = Repository::open(&clone_path).unwrap();
+    assert_eq!(cloned.head().unwrap().shorthand(), Some("feature-branch"));
+    // The feature branch predates the second commit
+    assert!(!clone_path.join("lib.rs").exists());
+}
+
+/// Serve the repositories below `root` over smart HTTP on a local port
+///
+/// Each request is handed to `git http-backend`; the port is returned once
+/// the server listens, or `None` if the backend is not installed.
+fn serve_git_http(root: &Path) -> Option<u16> {
+    let exec_path = std::process::Command::new("git")
+        .arg("--exec-path")
+        .output()
+        .ok()?;
+    let exec_path = String::from_utf8(exec_path.stdout).ok()?;
+    if !Path::new(exec_path.trim())
+        .join("git-http-backend")
+        .exists()
+    {
+        return None;
+    }
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").ok()?;
+    let port = listener.local_addr().ok()?.port();
+    let root = root.to_path_buf();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let _ = git_http_request(&root, stream);
+        }
+    });
+    Some(port)
+}
+
+/// Answer one HTTP request through `git http-backend`
+fn git_http_request(root: &Path, stream: std::net::TcpStream) -> std::io::Result<()> {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or("GET"), parts.next().unwrap_or("/"));
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let (mut content_type, mut content_length) = (String::new(), 0);
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let Some((name, value)) = line.trim_end().split_once(':') else {
+            break;
+        };
+        match name.to_ascii_lowercase().as_str() {
+            "content-type" => content_type = value.trim().to_string(),
+            "content-length" => content_length = value.trim().parse().unwrap_or(0),
+            _ => {}
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    let mut backend = std::process::Command::new("git")
+        .arg("http-backend")
+        .env("GIT_PROJECT_ROOT", root)
+        .env("GIT_HTTP_EXPORT_ALL", "1")
+        .env("REQUEST_METHOD", method)
+        .env("PATH_INFO", path)
+        .env("QUERY_STRING", query)
+        .env("CONTENT_TYPE", content_type)
+        .env("CONTENT_LENGTH", content_length.to_string())
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()?;
+    backend.stdin.take().unwrap().write_all(&body)?;
+    let output = backend.wait_with_output()?;
+
+    // The backend answers with CGI headers, which carry the status separately
+    let split = output
+        .stdout
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .unwrap_or(0);
+    let headers = String::from_utf8_lossy(&output.stdout[..split]);
+    let content = output.stdout.get(split + 4..).unwrap_or_default();
+    let mut status = "200 OK".to_string();
+    let mut response = Vec::new();
+    for header in headers.lines() {
+        match header.strip_prefix("Status: ") {
+            Some(code) => status = code.to_string(),
+            None => write!(response, "{header}\r\n")?,
+        }
+    }
+
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        content.len()
+    )?;
+    stream.write_all(&response)?;
+    stream.write_all(b"\r\n")?;
+    stream.write_all(content)
+}
+
+#[tokio::test]
+async fn test_shallow_clone_over_http() {
+    let source = create_linear_repo(5);
+    let root = source.path().parent().unwrap();
+    let name = source.path().file_name().unwrap().to_str().unwrap();
+    // Serving HTTP needs git http-backend, which not every environment has
+    let Some(port) = serve_git_http(root) else {
+        return;
+    };
+    let target = TempDir::new().unwrap();
+    let clone_path = target.path().join("shallow");
+
+    let mut command = clone_command(&source, &clone_path);
+    command.remote_url = RemoteUrl::new(format!("http://127.0.0.1:{port}/{name}")).unwrap();
+    command.depth = Some(2);
+    RepositoryCommandHandler::new()
+        .clone_repository(&command)
+        .await
+        .unwrap();
+
+    let cloned = Repository::open(&clone_path).unwrap();
+    assert!(cloned.is_shallow());
+    let mut revwalk = cloned.revwalk().unwrap();
+    revwalk.push_head().unwrap();
+    assert_eq!(revwalk.count(), 2);
+}
+
+#[tokio::test]
+async fn test_clone_failures_map_to_domain_errors() {
+    let source = create_test_repo();
+    let target = TempDir::new().unwrap();
+    let handler = RepositoryCommandHandler::new();
+
+    // Unknown branch
+    let mut command = clone_command(&source, &target.path().join("missing-branch"));
+    command.branch = Some(BranchName::new("does-not-exist").unwrap());
+    assert!(matches!(
+        handler.clone_repository(&command).await,
+        Err(GitDomainError::BranchNotFound(_))
+    ));
+
+    // Shallow clone over the local transport
+    let mut command = clone_command(&source, &target.path().join("shallow"));
+    command.depth = Some(1);
+    assert!(matches!(
+        handler.clone_repository(&command).await,
+        Err(GitDomainError::ShallowCloneUnsupported(_))
+    ));
+
+    // Non-empty destination
+    fs::write(target.path().join("occupied.txt"), "taken").unwrap();
+    let command = clone_command(&source, target.path());
+    assert!(matches!(
+        handler.clone_repository(&command).await,
+        Err(GitDomainError::CloneTargetExists(_))
+    ));
+
+    // Repository that already has a clone
+    let (repo_id, _) = handler
+        .clone_repository(&clone_command(&source, &target.path().join("first")))
+        .await
+        .unwrap();
+    let mut command = clone_command(&source, &target.path().join("second"));
+    command.repository_id = Some(repo_id);
+    assert!(matches!(
+        handler.clone_repository(&command).await,
+        Err(GitDomainError::RepositoryAlreadyCloned(id)) if id == repo_id
+    ));
+
+    // Missing remote
+    let mut command = clone_command(&source, &target.path().join("no-remote"));
+    command.remote_url = RemoteUrl::new("file:///nonexistent/cim-domain-git/repo").unwrap();
+    assert!(matches!(
+        handler.clone_repository(&command).await,
+        Err(GitDomainError::RemoteUnreachable(_))
+    ));
+}
+
+/// Seconds since the epoch of the first commit made by `commit_at`
+const BASE_TIME: i64 = 1_700_000_000;
+
+/// Write a flat tree holding the given files
+fn flat_tree(repo: &Repository, files: &[(&str, &str)]) -> Oid {
+    let mut builder = repo.treebuilder(None).unwrap();
+    for (name, content) in files {
+        let blob = repo.blob(content.as_bytes()).unwrap();
+        builder.insert(name, blob, 0o100_644).unwrap();
+    }
+    builder.write().unwrap()
+}
+
+/// Commit an empty tree on top of `parents`, `day` days after `BASE_TIME`
+fn commit_at(repo: &Repository, update_ref: Option<&str>, day: i64, parents: &[Oid]) -> Oid {
+    commit_files_at(
+        repo,
+        update_ref,
+        day,
+        parents,
+        &[],
+        &format!("Commit on day {day}"),
+    )
+}
+
+/// Commit `files` on top of `parents`, `day` days after `BASE_TIME`
+fn commit_files_at(
+    repo: &Repository,
+    update_ref: Option<&str>,
+    day: i64,
+    parents: &[Oid],
+    files: &[(&str, &str)],
+    message: &str,
+) -> Oid {
+    let sig = Signature::new(
+        "Test Author",
+        "test@example.com",
+        &Time::new(BASE_TIME + day * 86_400, 0),
+    )
+    .unwrap();
+    commit_as(repo, update_ref, &sig, parents, files, message)
+}
+
+/// Commit `files` on top of `parents` with `author` as author and committer
+fn commit_as(
+    repo: &Repository,
+    update_ref: Option<&str>,
+    author: &Signature,
+    parents: &[Oid],
+    files: &[(&str, &str)],
+    message: &str,
+) -> Oid {
+    let tree = repo.find_tree(flat_tree(repo, files)).unwrap();
+    let parents: Vec<_> = parents
+        .iter()
+        .map(|oid| repo.find_commit(*oid).unwrap())
+        .collect();
+    let parent_refs: Vec<_> = parents.iter().collect();
+
+    repo.commit(update_ref, author, author, message, &tree, &parent_refs)
+        .unwrap()
+}
+
+/// Linear history committed onto HEAD, each commit on top of the previous one
+struct LinearHistory<'a> {
+    repo: &'a Repository,
+    tip: Option<Oid>,
+    day: i64,
+}
+
+impl<'a> LinearHistory<'a> {
+    fn new(repo: &'a Repository) -> Self {
+        Self {
+            repo,
+            tip: None,
+            day: 0,
+        }
+    }
+
+    /// Commit `files` a day after the previous commit made by `commit`
+    fn commit(&mut self, message: &str, files: &[(&str, &str)]) -> Oid {
+        let parents: Vec<Oid> = self.tip.into_iter().collect();
+        let oid = commit_files_at(self.repo, Some("HEAD"), self.day, &parents, files, message);
+        self.day += 1;
+        self.tip = Some(oid);
+        oid
+    }
+
+    /// Commit `files` with `author` as author and committer
+    fn commit_as(&mut self, author: &Signature, message: &str, files: &[(&str, &str)]) -> Oid {
+        let parents: Vec<Oid> = self.tip.into_iter().collect();
+        let oid = commit_as(self.repo, Some("HEAD"), author, &parents, files, message);
+        self.tip = Some(oid);
+        oid
+    }
+}
+
+/// Create a repository with a linear history of `count` daily commits
+fn create_linear_repo(count: i64) -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+
+    let mut parent = None;
+    for day in 0..count {
+        let parents: Vec<Oid> = parent.into_iter().collect();
+        parent = Some(commit_at(&repo, Some("HEAD"), day, &parents));
+    }
+
+    temp_dir
+}
+
+fn commit_messages(events: &[GitDomainEvent]) -> Vec<String> {
+    events
+        .iter()
+        .filter_map(|e| match e {
+            GitDomainEvent::CommitAnalyzed(commit) => Some(commit.message.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_analysis_walks_full_history() {
+    let temp_dir = create_linear_repo(150);
+    let handler = RepositoryCommandHandler::new();
+
+    let (repo_id, events) = handler
+        .analyze_repository_at_path(temp_dir.path().to_str().unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(commit_messages(&events).len(), 150);
+    match events.last() {
+        Some(GitDomainEvent::RepositoryAnalyzed(analyzed)) => {
+            assert_eq!(analyzed.commit_count, 150);
+        }
+        other => panic!("unexpected last event {other:?}"),
+    }
+    let stored = handler.get_repository(&repo_id).unwrap();
+    assert_eq!(stored.metadata.commit_count, Some(150));
+}
+
+#[tokio::test]
+async fn test_analysis_options_limit_history() {
+    let temp_dir = create_linear_repo(20);
+    let path = temp_dir.path().to_str().unwrap();
+    let day = |n: i64| DateTime::<Utc>::from_timestamp(BASE_TIME + n * 86_400, 0).unwrap();
+
+    // Newest commits come first
+    let (_, events) = RepositoryCommandHandler::new()
+        .analyze_repository_with_options(path, AnalysisOptions::default().with_max_commits(3))
+        .await
+        .unwrap();
+    assert_eq!(
+        commit_messages(&events),
+        vec!["Commit on day 19", "Commit on day 18", "Commit on day 17"]
+    );
+
+    // Inclusive date window
+    let options = AnalysisOptions::default()
+        .with_since(day(5))
+        .with_until(day(9));
+    let (_, events) = RepositoryCommandHandler::new()
+        .analyze_repository_with_options(path, options)
+        .await
+        .unwrap();
+    assert_eq!(commit_messages(&events).len(), 5);
+
+    // Starting from an older revision
+    let options = AnalysisOptions::default().with_ref("HEAD~15");
+    let (_, events) = RepositoryCommandHandler::new()
+        .analyze_repository_with_options(path, options)
+        .await
+        .unwrap();
+    assert_eq!(commit_messages(&events).len(), 5);
+
+    // Unknown refs are reported
+    let options = AnalysisOptions::default().with_ref("no-such-branch");
+    assert!(RepositoryCommandHandler::new()
+        .analyze_repository_with_options(path, options)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_analysis_since_with_clock_skew() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+
+    // Day 5 was committed on top of day 10 by a machine with a slow clock
+    let root = commit_at(&repo, Some("HEAD"), 0, &[]);
+    let ahead = commit_at(&repo, Some("HEAD"), 10, &[root]);
+    let skewed = commit_at(&repo, Some("HEAD"), 5, &[ahead]);
+    commit_at(&repo, Some("HEAD"), 11, &[skewed]);
+
+    let since = DateTime::<Utc>::from_timestamp(BASE_TIME + 4 * 86_400, 0).unwrap();
+    let (_, events) = RepositoryCommandHandler::new()
+        .analyze_repository_with_options(
+            temp_dir.path().to_str().unwrap(),
+            AnalysisOptions::default().with_since(since),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        commit_messages(&events),
+        vec!["Commit on day 11", "Commit on day 10", "Commit on day 5"]
+    );
+}
+
+#[tokio::test]
+async fn test_analysis_first_parent_only() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+
+    let base = commit_at(&repo, Some("HEAD"), 0, &[]);
+    let side = commit_at(&repo, None, 1, &[base]);
+    let main = commit_at(&repo, Some("HEAD"), 2, &[base]);
+    commit_at(&repo, Some("HEAD"), 3, &[main, side]);
+
+    let path = temp_dir.path().to_str().unwrap();
+
+    let (_, events) = RepositoryCommandHandler::new()
+        .analyze_repository_at_path(path)
+        .await
+        .unwrap();
+    assert_eq!(commit_messages(&events).len(), 4);
+
+    let options = AnalysisOptions::default().first_parent_only();
+    let (_, events) = RepositoryCommandHandler::new()
+        .analyze_repository_with_options(path, options)
+        .await
+        .unwrap();
+    assert!(!commit_messages(&events).contains(&"Commit on day 1".to_string()));
+    assert_eq!(commit_messages(&events).len(), 3);
+}
+
+#[tokio::test]
+async fn test_analysis_stream() {
+    let temp_dir = create_linear_repo(300);
+    let path = temp_dir.path().to_str().unwrap();
+    let handler = RepositoryCommandHandler::new();
+
+    let mut stream = handler.analyze_repository_stream(path, AnalysisOptions::default());
+    let repo_id = stream.repository_id();
+
+    let mut commits = 0;
+    while let Some(event) = stream.next().await {
+        if let GitDomainEvent::CommitAnalyzed(e) = event.unwrap() {
+            assert_eq!(e.repository_id, repo_id);
+            commits += 1;
+        }
+    }
+    assert_eq!(commits, 300);
+    assert!(handler.get_repository(&repo_id).is_some());
+
+    // Abandoning a stream with more events pending than the channel buffers
+    // stops the walk without storing the aggregate
+    let handler = RepositoryCommandHandler::new();
+    let stream = handler.analyze_repository_stream(path, AnalysisOptions::default());
+    let abandoned_id = stream.repository_id();
+    let first: Vec<_> = stream.take(2).collect().await;
+    assert_eq!(first.len(), 2);
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(handler.get_repository(&abandoned_id).is_none());
+}
+
+#[tokio::test]
+async fn test_incremental_reanalysis() {
+    let temp_dir = create_linear_repo(3);
+    let path = temp_dir.path().to_str().unwrap();
+    let repo = Repository::open(temp_dir.path()).unwrap();
+    let handler = RepositoryCommandHandler::new();
+
+    let first_tip = repo.head().unwrap().target().unwrap();
+    let branch_tip = repo.find_commit(first_tip).unwrap().parent_id(0).unwrap();
+    repo.branch("stale", &repo.find_commit(branch_tip).unwrap(), false)
+        .unwrap();
+    repo.branch("rewound", &repo.find_commit(first_tip).unwrap(), false)
+        .unwrap();
+
+    let (repo_id, _) = handler.analyze_repository_at_path(path).await.unwrap();
+
+    // Nothing changed, nothing to report
+    let (same_id, events) = handler.analyze_repository_at_path(path).await.unwrap();
+    assert_eq!(same_id, repo_id);
+    assert!(commit_messages(&events).is_empty());
+    assert!(events
+        .iter()
+        .all(|e| matches!(e, GitDomainEvent::RepositoryAnalyzed(_))));
+
+    // Advance HEAD, rewind one branch and drop another
+    let new_tip = commit_at(&repo, Some("HEAD"), 3, &[first_tip]);
+    repo.branch("rewound", &repo.find_commit(branch_tip).unwrap(), true)
+        .unwrap();
+    repo.find_branch("stale", git2::BranchType::Local)
+        .unwrap()
+        .delete()
+        .unwrap();
+
+    let (_, events) = handler.analyze_repository_at_path(path).await.unwrap();
+    assert_eq!(commit_messages(&events), vec!["Commit on day 3"]);
+    assert!(matches!(
+        events.last(),
+        Some(GitDomainEvent::RepositoryAnalyzed(analyzed))
+            if analyzed.commit_count == 4 && analyzed.branch_count == 2
+    ));
+
+    let moved: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            GitDomainEvent::BranchMoved(moved) => Some(moved),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(moved.len(), 2);
+    let default_branch = moved
+        .iter()
+        .find(|m| m.new_commit.as_str() == new_tip.to_string())
+        .unwrap();
+    assert!(default_branch.fast_forward);
+    let rewound = moved
+        .iter()
+        .find(|m| m.branch_name.as_str() == "rewound")
+        .unwrap();
+    assert!(!rewound.fast_forward);
+
+    assert!(events.iter().any(|e| matches!(
+        e,
+        GitDomainEvent::BranchDeleted(deleted) if deleted.branch_name.as_str() == "stale"
+    )));
+
+    let stored = handler.get_repository(&repo_id).unwrap();
+    assert_eq!(stored.metadata.commit_count, Some(4));
+    assert_eq!(stored.branches.len(), 2);
+    assert_eq!(stored.analyzed_tips["HEAD"].as_str(), new_tip.to_string());
+}
+
+#[tokio::test]
+async fn test_limited_analysis_leaves_rest_for_later_runs() {
+    let temp_dir = create_linear_repo(10);
+    let path = temp_dir.path().to_str().unwrap();
+    let handler = RepositoryCommandHandler::new();
+
+    let (repo_id, events) = handler
+        .analyze_repository_with_options(path, AnalysisOptions::default().with_max_commits(4))
+        .await
+        .unwrap();
+    assert_eq!(commit_messages(&events).len(), 4);
+    assert!(handler
+        .get_repository(&repo_id)
+        .unwrap()
+        .analyzed_tips
+        .is_empty());
+
+    // The full run still reaches the commits the limited run skipped
+    let (same_id, events) = handler.analyze_repository_at_path(path).await.unwrap();
+    assert_eq!(same_id, repo_id);
+    let messages = commit_messages(&events);
+    assert_eq!(messages.len(), 10);
+    assert!(messages.contains(&"Commit on day 0".to_string()));
+
+    // Only now is the history covered
+    let (_, events) = handler.analyze_repository_at_path(path).await.unwrap();
+    assert!(commit_messages(&events).is_empty());
+}
+
+#[tokio::test]
+async fn test_analysis_recognises_cloned_repository() {
+    let source = create_test_repo();
+    let target = TempDir::new().unwrap();
+    let clone_path = target.path().join("clone");
+    let handler = RepositoryCommandHandler::new();
+
+    let (clone_id, _) = handler
+        .clone_repository(&clone_command(&source, &clone_path))
+        .await
+        .unwrap();
+
+    // A relative spelling of the same path resolves to the same repository
+    let spelled = clone_path.join("..").join("clone");
+    let (repo_id, events) = handler
+        .analyze_repository_at_path(spelled.to_str().unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(repo_id, clone_id);
+    assert_eq!(commit_messages(&events).len(), 2);
+    assert_eq!(handler.list_repositories().len(), 1);
+}
+
+#[tokio::test]
+async fn test_file_change_line_counts_renames_and_binaries() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let sig = Signature::now("Test Author", "test@example.com").unwrap();
+    let lines: Vec<String> = (1..=10).map(|n| format!("line {n}")).collect();
+    let notes = "Release notes\nwith enough content\nto be recognised\nas a rename\n";
+
+    let write_tree = |files: &[(&str, &[u8])]| {
+        let mut index = repo.index().unwrap();
+        index.clear().unwrap();
+        for (path, content) in files {
+            let full_path = temp_dir.path().join(path);
+            fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+            fs::write(&full_path, content).unwrap();
+            index.add_path(Path::new(path)).unwrap();
+        }
+        index.write().unwrap();
+        repo.find_tree(index.write_tree().unwrap()).unwrap()
+    };
+
+    let original = lines.join("\n") + "\n";
+    let tree = write_tree(&[
+        ("a.txt", original.as_bytes()),
+        ("notes.txt", notes.as_bytes()),
+        ("image.bin", &[0u8, 1, 2, 3, 0, 255]),
+    ]);
+    let first = repo
+        .commit(Some("HEAD"), &sig, &sig, "Initial", &tree, &[])
+        .unwrap();
+
+    // Rewrite two lines and append three in a.txt, copy its old content to
+    // b.txt, move notes.txt and touch the binary
+    let mut edited = lines.clone();
+    edited[0] = "first line".to_string();
+    edited[4] = "fifth line".to_string();
+    edited.extend(["x".to_string(), "y".to_string(), "z".to_string()]);
+    let edited = edited.join("\n") + "\n";
+    let tree = write_tree(&[
+        ("a.txt", edited.as_bytes()),
+        ("b.txt", original.as_bytes()),
+        ("docs/notes.md", notes.as_bytes()),
+        ("image.bin", &[0u8, 9, 9, 9, 0, 255]),
+    ]);
+    let parent = repo.find_commit(first).unwrap();
+    repo.commit(Some("HEAD"), &sig, &sig, "Reshuffle", &tree, &[&parent])
+        .unwrap();
+
+    let handler = RepositoryCommandHandler::new();
+    let (_, events) = handler
+        .analyze_repository_at_path(temp_dir.path().to_str().unwrap())
+        .await
+        .unwrap();
+
+    let commit = events
+        .iter()
+        .find_map(|e| match e {
+            GitDomainEvent::CommitAnalyzed(c) if c.message == "Reshuffle" => Some(c),
+            _ => None,
+        })
+        .unwrap();
+    let change = |path: &str| {
+        commit
+            .files_changed
+            .iter()
+            .find(|c| c.path.as_str() == path)
+            .unwrap_or_else(|| panic!("no change recorded for {path}"))
+    };
+
+    let modified = change("a.txt");
+    assert_eq!(modified.change_type, FileChangeType::Modified);
+    assert_eq!((modified.additions, modified.deletions), (5, 2));
+    assert!(!modified.binary);
+
+    let copied = change("b.txt");
+    assert_eq!(copied.change_type, FileChangeType::Copied);
+    assert_eq!(copied.old_path.as_ref().unwrap().as_str(), "a.txt");
+
+    let renamed = change("docs/notes.md");
+    assert_eq!(renamed.change_type, FileChangeType::Renamed);
+    assert_eq!(renamed.old_path.as_ref().unwrap().as_str(), "notes.txt");
+    assert_eq!((renamed.additions, renamed.deletions), (0, 0));
+
+    let binary = change("image.bin");
+    assert!(binary.binary);
+    assert_eq!((binary.additions, binary.deletions), (0, 0));
+
+    // The rename is visible under both paths in the file change projection
+    let projection = FileChangeProjection::new();
+    for event in &events {
+        projection.handle_event(event).await.unwrap();
+    }
+    let old_path = FilePath::new("notes.txt").unwrap();
+    let history = projection.get_rename_history(&old_path).unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].new_path.as_str(), "docs/notes.md");
+
+    let stats = projection
+        .get_file_statistics(&FilePath::new("a.txt").unwrap())
+        .unwrap();
+    // The root commit adds ten lines, the second adds five and removes two
+    assert_eq!(stats.change_count, 2);
+    assert_eq!(stats.total_additions, 15);
+    assert_eq!(stats.total_deletions, 2);
+}
+
+#[tokio::test]
+async fn test_root_and_merge_commit_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let base = commit_files_at(
+        &repo,
+        Some("HEAD"),
+        0,
+        &[],
+        &[("a.txt", "base\n"), ("b.txt", "base\n")],
+        "Root",
+    );
+    let side = commit_files_at(
+        &repo,
+        None,
+        1,
+        &[base],
+        &[("a.txt", "base\n"), ("b.txt", "side\n")],
+        "Side change",
+    );
+    let main = commit_files_at(
+        &repo,
+        Some("HEAD"),
+        2,
+        &[base],
+        &[("a.txt", "main\n"), ("b.txt", "base\n")],
+        "Main change",
+    );
+
+    // a.txt is resolved to content neither parent had
+    let merge = commit_files_at(
+        &repo,
+        Some("HEAD"),
+        3,
+        &[main, side],
+        &[("a.txt", "resolved\n"), ("b.txt", "side\n")],
+        "Merge branch 'side' into main",
+    );
+
+    let (_, events) = RepositoryCommandHandler::new()
+        .analyze_repository_at_path(temp_dir.path().to_str().unwrap())
+        .await
+        .unwrap();
+    let commit_named = |message: &str| {
+        events
+            .iter()
+            .find_map(|e| match e {
+                GitDomainEvent::CommitAnalyzed(c) if c.message == message => Some(c),
+                _ => None,
+            })
+            .unwrap()
+    };
+
+    // Root commits are diffed against the empty tree
+    let root = commit_named("Root");
+    assert_eq!(root.files_changed.len(), 2);
+    assert!(root
+        .files_changed
+        .iter()
+        .all(|c| c.change_type == FileChangeType::Added && c.additions == 1));
+    assert!(root.parent_diffs.is_empty());
+
+    // Merges are diffed against every parent
+    let merged = commit_named("Merge branch 'side' into main");
+    assert_eq!(merged.parent_diffs.len(), 2);
+    assert_eq!(merged.parent_diffs[0].parent.as_str(), main.to_string());
+    assert_eq!(merged.parent_diffs[1].parent.as_str(), side.to_string());
+    let paths = |i: usize| -> Vec<&str> {
+        let mut paths: Vec<_> = merged.parent_diffs[i]
+            .files_changed
+            .iter()
+            .map(|c| c.path.as_str())
+            .collect();
+        paths.sort_unstable();
+        paths
+    };
+    assert_eq!(paths(0), vec!["a.txt", "b.txt"]);
+    assert_eq!(paths(1), vec!["a.txt"]);
+    assert_eq!(merged.files_changed.len(), 2);
+    let combined: Vec<_> = merged.combined_files.iter().map(|p| p.as_str()).collect();
+    assert_eq!(combined, vec!["a.txt"]);
+
+    // Exactly one merge event, for the merge commit
+    let merges: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            GitDomainEvent::MergeDetected(m) => Some(m),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(merges.len(), 1);
+    let detected = merges[0];
+    assert_eq!(detected.merge_commit.as_str(), merge.to_string());
+    assert_eq!(detected.parents.len(), 2);
+    let branches: Vec<_> = detected.branches.iter().map(|b| b.as_str()).collect();
+    assert_eq!(branches, vec!["side", "main"]);
+    assert_eq!(detected.merge_strategy, None);
+    // History does not tell whether a.txt merged cleanly
+    assert!(detected.conflicts.is_empty());
+}
+
+#[tokio::test]
+async fn test_analyze_single_commit() {
+    let temp_dir = create_test_repo();
+    let handler = RepositoryCommandHandler::new();
+    let (repo_id, _) = handler
+        .analyze_repository_at_path(temp_dir.path().to_str().unwrap())
+        .await
+        .unwrap();
+
+    let repo = Repository::open(temp_dir.path()).unwrap();
+    let root = repo
+        .head()
+        .unwrap()
+        .peel_to_commit()
+        .unwrap()
+        .parent(0)
+        .unwrap()
+        .id()
+        .to_string();
+
+    // Abbreviated hashes are resolved against the repository
+    let command = AnalyzeCommit {
+        repository_id: repo_id,
+        commit_hash: CommitHash::new(&root[..12]).unwrap(),
+        analyze_files: true,
+        extract_dependencies: true,
+    };
+    let events = handler.analyze_commit(&command).await.unwrap();
+
+    let commit = match &events[0] {
+        GitDomainEvent::CommitAnalyzed(commit) => commit,
+        other => panic!("expected CommitAnalyzed first, got {other:?}"),
+    };
+    assert_eq!(commit.commit_hash.as_str(), root);
+    assert_eq!(commit.files_changed.len(), 3);
+
+    let files: std::collections::HashMap<_, _> = events[1..]
+        .iter()
+        .map(|event| match event {
+            GitDomainEvent::FileAnalyzed(file) => (file.file_path.as_str().to_string(), file),
+            other => panic!("unexpected event {other:?}"),
+        })
+        .collect();
+    assert_eq!(files.len(), 3);
+
+    let main = files["main.rs"];
+    assert_eq!(main.commit_hash.as_str(), root);
+    assert_eq!(main.metrics.language.as_deref(), Some("Rust"));
+    assert_eq!(main.metrics.lines_of_code, 5);
+    assert_eq!(main.metrics.function_count, Some(1));
+    let main_deps: Vec<_> = main.dependencies.iter().map(FilePath::as_str).collect();
+    assert_eq!(main_deps, ["serde", "std::collections::HashMap"]);
+
+    let manifest_deps: Vec<_> = files["Cargo.toml"]
+        .dependencies
+        .iter()
+        .map(FilePath::as_str)
+        .collect();
+    assert_eq!(manifest_deps, ["serde", "tokio"]);
+
+    let readme = files["readme.md"];
+    assert_eq!(readme.metrics.language, None);
+    assert_eq!(readme.metrics.function_count, None);
+    assert!(readme.dependencies.is_empty());
+    assert_eq!(readme.metrics.size_bytes, 34);
+}
+
+#[tokio::test]
+async fn test_analyze_commit_options_and_failures() {
+    let temp_dir = create_test_repo();
+    let handler = RepositoryCommandHandler::new();
+    let (repo_id, _) = handler
+        .analyze_repository_at_path(temp_dir.path().to_str().unwrap())
+        .await
+        .unwrap();
+    let head = Repository::open(temp_dir.path())
+        .unwrap()
+        .head()
+        .unwrap()
+        .target()
+        .unwrap()
+        .to_string();
+
+    let mut command = AnalyzeCommit {
+        repository_id: repo_id,
+        commit_hash: CommitHash::new(&head).unwrap(),
+        analyze_files: false,
+        extract_dependencies: true,
+    };
+    let events = handler.analyze_commit(&command).await.unwrap();
+    assert_eq!(events.len(), 1);
+    assert!(matches!(events[0], GitDomainEvent::CommitAnalyzed(_)));
+
+    command.analyze_files = true;
+    command.extract_dependencies = false;
+    let events = handler.analyze_commit(&command).await.unwrap();
+    assert_eq!(events.len(), 2);
+    let lib = match &events[1] {
+        GitDomainEvent::FileAnalyzed(lib) => lib,
+        other => panic!("expected FileAnalyzed, got {other:?}"),
+    };
+    assert_eq!(lib.file_path.as_str(), "lib.rs");
+    assert!(lib.dependencies.is_empty());
+
+    command.commit_hash = CommitHash::new("0123456789abcdef0123").unwrap();
+    assert!(matches!(
+        handler.analyze_commit(&command).await,
+        Err(GitDomainError::InvalidCommitHash(_))
+    ));
+
+    command.repository_id = RepositoryId::new();
+    assert!(matches!(
+        handler.analyze_commit(&command).await,
+        Err(GitDomainError::RepositoryNotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_branch_operations_update_repository() {
+    let temp_dir = create_test_repo();
+    let handler = RepositoryCommandHandler::new();
+    let (repo_id, _) = handler
+        .analyze_repository_at_path(temp_dir.path().to_str().unwrap())
+        .await
+        .unwrap();
+    let repo = Repository::open(temp_dir.path()).unwrap();
+    let head = repo.head().unwrap().target().unwrap();
+
+    let topic = CreateBranch {
+        repository_id: repo_id,
+        branch_name: BranchName::new("topic").unwrap(),
+        start_point: "feature-branch".to_string(),
+        checkout: false,
+    };
+    let events = handler.create_branch(&topic).await.unwrap();
+    match &events[..] {
+        [GitDomainEvent::BranchCreated(created)] => {
+            assert_eq!(
+                created.source_branch.as_ref().unwrap().as_str(),
+                "feature-branch"
+            );
+            assert!(!created.checked_out);
+        }
+        other => panic!("unexpected events {other:?}"),
+    }
+    assert!(repo.find_branch("topic", git2::BranchType::Local).is_ok());
+    assert!(matches!(
+        handler.create_branch(&topic).await,
+        Err(GitDomainError::BranchAlreadyExists(_))
+    ));
+
+    let work = CreateBranch {
+        repository_id: repo_id,
+        branch_name: BranchName::new("work").unwrap(),
+        start_point: "HEAD".to_string(),
+        checkout: true,
+    };
+    handler.create_branch(&work).await.unwrap();
+    assert_eq!(repo.head().unwrap().shorthand(), Some("work"));
+    let aggregate = handler.get_repository(&repo_id).unwrap();
+    assert_eq!(aggregate.current_branch.unwrap().as_str(), "work");
+
+    let mut delete = DeleteBranch {
+        repository_id: repo_id,
+        branch_name: BranchName::new("work").unwrap(),
+        force: true,
+    };
+    assert!(matches!(
+        handler.delete_branch(&delete).await,
+        Err(GitDomainError::CannotDeleteCurrentBranch(_))
+    ));
+
+    // A branch with a commit HEAD does not contain needs `force`
+    let wip_tip = commit_at(&repo, Some("refs/heads/wip"), 1, &[head]);
+    delete.branch_name = BranchName::new("wip").unwrap();
+    delete.force = false;
+    assert!(matches!(
+        handler.delete_branch(&delete).await,
+        Err(GitDomainError::BranchNotMerged(_))
+    ));
+    delete.force = true;
+    match &handler.delete_branch(&delete).await.unwrap()[..] {
+        [GitDomainEvent::BranchDeleted(deleted)] => {
+            assert_eq!(deleted.last_commit.as_str(), wip_tip.to_string());
+        }
+        other => panic!("unexpected events {other:?}"),
+    }
+    assert!(repo.find_branch("wip", git2::BranchType::Local).is_err());
+
+    // Merged branches go without `force`
+    delete.branch_name = BranchName::new("topic").unwrap();
+    delete.force = false;
+    handler.delete_branch(&delete).await.unwrap();
+    let aggregate = handler.get_repository(&repo_id).unwrap();
+    assert!(!aggregate
+        .branches
+        .contains_key(&BranchName::new("topic").unwrap()));
+
+    // Re-analysis finds nothing the operations have not already reported
+    let (_, events) = handler
+        .analyze_repository_at_path(temp_dir.path().to_str().unwrap())
+        .await
+        .unwrap();
+    assert!(!events.iter().any(|e| matches!(
+        e,
+        GitDomainEvent::BranchCreated(_) | GitDomainEvent::BranchDeleted(_)
+    )));
+}
+
+#[tokio::test]
+async fn test_create_tags() {
+    let temp_dir = create_test_repo();
+    let handler = RepositoryCommandHandler::new();
+    let (repo_id, _) = handler
+        .analyze_repository_at_path(temp_dir.path().to_str().unwrap())
+        .await
+        .unwrap();
+    let repo = Repository::open(temp_dir.path()).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Release Bot").unwrap();
+    config.set_str("user.email", "release@example.com").unwrap();
+    let root = repo.revparse_single("HEAD~1").unwrap().id();
+
+    let mut command = CreateTag {
+        repository_id: repo_id,
+        tag_name: TagName::new("v0.1.0").unwrap(),
+        commit_hash: Some(CommitHash::new(root.to_string()).unwrap()),
+        message: None,
+        annotated: false,
+    };
+    match &handler.create_tag(&command).await.unwrap()[..] {
+        [GitDomainEvent::TagCreated(tag)] => {
+            assert_eq!(tag.commit_hash.as_str(), root.to_string());
+            assert!(tag.message.is_none());
+            assert!(tag.tagger.is_none());
+        }
+        other => panic!("unexpected events {other:?}"),
+    }
+    let reference = repo.find_reference("refs/tags/v0.1.0").unwrap();
+    assert_eq!(reference.target(), Some(root));
+    assert!(matches!(
+        handler.create_tag(&command).await,
+        Err(GitDomainError::TagAlreadyExists(_))
+    ));
+
+    command.tag_name = TagName::new("v0.2.0").unwrap();
+    command.commit_hash = None;
+    command.annotated = true;
+    assert!(matches!(
+        handler.create_tag(&command).await,
+        Err(GitDomainError::ValidationError(_))
+    ));
+
+    // The tagger resolves through the mailmap like tags found by analysis
+    fs::write(
+        temp_dir.path().join(".mailmap"),
+        "Release Team <team@example.com> <release@example.com>\n",
+    )
+    .unwrap();
+    command.message = Some("Second release".to_string());
+    match &handler.create_tag(&command).await.unwrap()[..] {
+        [GitDomainEvent::TagCreated(tag)] => {
+            assert_eq!(tag.message.as_deref(), Some("Second release"));
+            let tagger = tag.tagger.as_ref().unwrap();
+            assert_eq!(tagger.name, "Release Team");
+            assert_eq!(tagger.email, "team@example.com");
+        }
+        other => panic!("unexpected events {other:?}"),
+    }
+    let tag = repo
+        .find_reference("refs/tags/v0.2.0")
+        .unwrap()
+        .peel_to_tag()
+        .unwrap();
+    assert_eq!(tag.target_id(), repo.head().unwrap().target().unwrap());
+    assert_eq!(tag.message(), Some("Second release"));
+
+    let aggregate = handler.get_repository(&repo_id).unwrap();
+    assert_eq!(aggregate.tags.len(), 2);
+}
+
+fn ref_updates(events: &[GitDomainEvent]) -> Vec<(String, RefUpdateType)> {
+    match events {
+        [GitDomainEvent::RemoteFetched(fetched)] => fetched
+            .ref_updates
+            .iter()
+            .map(|update| (update.ref_name.clone(), update.update_type))
+            .collect(),
+        other => panic!("unexpected events {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_fetch_remote_reports_ref_updates() {
+    let source = create_test_repo();
+    let target = TempDir::new().unwrap();
+    let clone_path = target.path().join("clone");
+    let handler = RepositoryCommandHandler::new();
+    let (repo_id, _) = handler
+        .clone_repository(&clone_command(&source, &clone_path))
+        .await
+        .unwrap();
+
+    let upstream = Repository::open(source.path()).unwrap();
+    let default_branch = upstream.head().unwrap().shorthand().unwrap().to_string();
+    let old_head = upstream.head().unwrap().target().unwrap();
+    let new_head = commit_at(&upstream, Some("HEAD"), 1, &[old_head]);
+    upstream
+        .branch(
+            "new-feature",
+            &upstream.find_commit(new_head).unwrap(),
+            false,
+        )
+        .unwrap();
+    upstream
+        .find_branch("feature-branch", git2::BranchType::Local)
+        .unwrap()
+        .delete()
+        .unwrap();
+
+    // Configured pruning does not override the command
+    Repository::open(&clone_path)
+        .unwrap()
+        .config()
+        .unwrap()
+        .set_bool("fetch.prune", true)
+        .unwrap();
+
+    let mut command = FetchRemote {
+        repository_id: repo_id,
+        remote: None,
+        all_remotes: false,
+        prune: false,
+    };
+    let events = handler.fetch_remote(&command).await.unwrap();
+    assert_eq!(
+        ref_updates(&events),
+        [
+            (
+                format!("refs/remotes/origin/{default_branch}"),
+                RefUpdateType::Updated
+            ),
+            (
+                "refs/remotes/origin/new-feature".to_string(),
+                RefUpdateType::Created
+            ),
+        ]
+    );
+    match &events[0] {
+        GitDomainEvent::RemoteFetched(fetched) => {
+            let updated = &fetched.ref_updates[0];
+            assert_eq!(
+                updated.old_target.as_ref().unwrap().as_str(),
+                old_head.to_string()
+            );
+            assert_eq!(
+                updated.new_target.as_ref().unwrap().as_str(),
+                new_head.to_string()
+            );
+        }
+        other => panic!("unexpected event {other:?}"),
+    }
+
+    command.prune = true;
+    let events = handler.fetch_remote(&command).await.unwrap();
+    assert_eq!(
+        ref_updates(&events),
+        [(
+            "refs/remotes/origin/feature-branch".to_string(),
+            RefUpdateType::Deleted
+        )]
+    );
+
+    // The aggregate follows the remote-tracking branches
+    let aggregate = handler.get_repository(&repo_id).unwrap();
+    let new_feature = aggregate
+        .branches
+        .get(&BranchName::new("origin/new-feature").unwrap())
+        .unwrap();
+    assert_eq!(new_feature.as_str(), new_head.to_string());
+    assert!(!aggregate
+        .branches
+        .contains_key(&BranchName::new("origin/feature-branch").unwrap()));
+}
+
+#[tokio::test]
+async fn test_fetch_all_remotes_and_unknown_remote() {
+    let source = create_test_repo();
+    let target = TempDir::new().unwrap();
+    let clone_path = target.path().join("clone");
+    let handler = RepositoryCommandHandler::new();
+    let (repo_id, _) = handler
+        .clone_repository(&clone_command(&source, &clone_path))
+        .await
+        .unwrap();
+    Repository::open(&clone_path)
+        .unwrap()
+        .remote("upstream", &format!("file://{}", source.path().display()))
+        .unwrap();
+
+    let mut command = FetchRemote {
+        repository_id: repo_id,
+        remote: Some("missing".to_string()),
+        all_remotes: false,
+        prune: false,
+    };
+    assert!(matches!(
+        handler.fetch_remote(&command).await,
+        Err(GitDomainError::RemoteNotFound(name)) if name == "missing"
+    ));
+
+    command.all_remotes = true;
+    let events = handler.fetch_remote(&command).await.unwrap();
+    match &events[..] {
+        [GitDomainEvent::RemoteFetched(fetched)] => {
+            assert_eq!(fetched.remotes, ["origin", "upstream"]);
+            assert!(fetched.ref_updates.iter().all(|update| update
+                .ref_name
+                .starts_with("refs/remotes/upstream/")
+                && update.update_type == RefUpdateType::Created));
+            assert_eq!(fetched.ref_updates.len(), 2);
+        }
+        other => panic!("unexpected events {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_compare_branches() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let main_ref = Some("refs/heads/main");
+    let feature_ref = Some("refs/heads/feature");
+    let root = commit_files_at(&repo, main_ref, 0, &[], &[("a.txt", "one\ntwo\n")], "Root");
+    repo.set_head("refs/heads/main").unwrap();
+    let feature_one = commit_files_at(
+        &repo,
+        feature_ref,
+        1,
+        &[root],
+        &[("a.txt", "one\n2\n"), ("b.txt", "new\n")],
+        "Feature",
+    );
+    let feature_two = commit_files_at(
+        &repo,
+        feature_ref,
+        2,
+        &[feature_one],
+        &[("a.txt", "one\n2\n"), ("b.txt", "newer\n")],
+        "More feature",
+    );
+    // Changes only on main do not show up in the comparison
+    let main = commit_files_at(
+        &repo,
+        main_ref,
+        3,
+        &[root],
+        &[("a.txt", "one\ntwo\n"), ("c.txt", "main\n")],
+        "Main",
+    );
+
+    let handler = RepositoryCommandHandler::new();
+    let (repo_id, _) = handler
+        .analyze_repository_at_path(temp_dir.path().to_str().unwrap())
+        .await
+        .unwrap();
+
+    let mut command = CompareBranches {
+        repository_id: repo_id,
+        base_branch: BranchName::new("main").unwrap(),
+        compare_branch: BranchName::new("feature").unwrap(),
+        include_diffs: false,
+    };
+    let (comparison, events) = handler.compare_branches(&command).await.unwrap();
+
+    let hashes = |oids: &[Oid]| oids.iter().map(ToString::to_string).collect::<Vec<_>>();
+    let strings = |hashes: &[CommitHash]| {
+        hashes
+            .iter()
+            .map(|h| h.as_str().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        comparison.merge_base.as_ref().unwrap().as_str(),
+        root.to_string()
+    );
+    assert_eq!(comparison.base_commit.as_str(), main.to_string());
+    assert_eq!(
+        strings(&comparison.ahead),
+        hashes(&[feature_two, feature_one])
+    );
+    assert_eq!(strings(&comparison.behind), hashes(&[main]));
+    assert!(!comparison.is_fast_forward());
+    assert!(comparison.hunks.is_empty());
+
+    let mut files: Vec<_> = comparison
+        .files_changed
+        .iter()
+        .map(|f| (f.path.as_str(), f.change_type, f.additions, f.deletions))
+        .collect();
+    files.sort_by_key(|f| f.0);
+    assert_eq!(
+        files,
+        [
+            ("a.txt", FileChangeType::Modified, 1, 1),
+            ("b.txt", FileChangeType::Added, 1, 0),
+        ]
+    );
+
+    match &events[..] {
+        [GitDomainEvent::BranchesCompared(compared)] => {
+            assert_eq!(compared.compare_commit.as_str(), feature_two.to_string());
+            assert_eq!(compared.ahead.len(), 2);
+            assert_eq!(compared.files_changed.len(), 2);
+        }
+        other => panic!("unexpected events {other:?}"),
+    }
+
+    command.include_diffs = true;
+    let (comparison, _) = handler.compare_branches(&command).await.unwrap();
+    let hunk = comparison
+        .hunks
+        .iter()
+        .find(|hunk| hunk.path.as_str() == "a.txt")
+        .unwrap();
+    assert_eq!(hunk.header, "@@ -1,2 +1,2 @@");
+    assert_eq!(hunk.lines, [" one", "-two", "+2"]);
+
+    command.compare_branch = BranchName::new("missing").unwrap();
+    assert!(matches!(
+        handler.compare_branches(&command).await,
+        Err(GitDomainError::BranchNotFound(name)) if name == "missing"
+    ));
+}
+
+fn search_command(repository_id: RepositoryId, pattern: &str) -> SearchRepository {
+    SearchRepository {
+        repository_id,
+        pattern: pattern.to_string(),
+        include_patterns: Vec::new(),
+        exclude_patterns: Vec::new(),
+        case_sensitive: true,
+        max_results: None,
+        revision: None,
+        pickaxe: false,
+    }
+}
+
+#[tokio::test]
+async fn test_search_repository_tree() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let sig = Signature::now("Test Author", "test@example.com").unwrap();
+
+    let files: [(&str, &[u8]); 8] = [
+        (".gitattributes", b"*.dat binary\n*.raw text\n"),
+        (
+            "src/main.rs",
+            b"fn main() {\n    // TODO: parse args\n    let todo = 1; // todo again\n}\n",
+        ),
+        ("src/lib.rs", b"// TODO one\n"),
+        ("docs/notes.md", b"TODO in docs\n"),
+        ("target/out.rs", b"// TODO build output\n"),
+        ("data.bin", b"TODO\0binary"),
+        ("fixtures/sample.dat", b"TODO marked binary\n"),
+        ("forced.raw", b"TODO\0forced text"),
+    ];
+    let mut index = repo.index().unwrap();
+    for (path, content) in files {
+        let full_path = temp_dir.path().join(path);
+        fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+        fs::write(&full_path, content).unwrap();
+        index.add_path(Path::new(path)).unwrap();
+    }
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let first = repo
+        .commit(Some("HEAD"), &sig, &sig, "Add files", &tree, &[])
+        .unwrap();
+
+    // The second commit drops the TODO from lib.rs
+    let mut builder = repo.treebuilder(Some(&tree)).unwrap();
+    let src = tree.get_name("src").unwrap().to_object(&repo).unwrap();
+    let mut src_builder = repo.treebuilder(Some(src.as_tree().unwrap())).unwrap();
+    src_builder
+        .insert("lib.rs", repo.blob(b"// done\n").unwrap(), 0o100_644)
+        .unwrap();
+    builder
+        .insert("src", src_builder.write().unwrap(), 0o040_000)
+        .unwrap();
+    let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+    let parent = repo.find_commit(first).unwrap();
+    repo.commit(Some("HEAD"), &sig, &sig, "Finish lib", &tree, &[&parent])
+        .unwrap();
+
+    let handler = RepositoryCommandHandler::new();
+    let (repo_id, _) = handler
+        .analyze_repository_at_path(temp_dir.path().to_str().unwrap())
+        .await
+        .unwrap();
+
+    let mut command = search_command(repo_id, "TODO");
+    command.include_patterns = vec!["*.rs".to_string()];
+    command.exclude_patterns = vec!["target/".to_string()];
+    let results = handler.search_repository(&command).await.unwrap();
+    let found: Vec<_> = results
+        .matches
+        .iter()
+        .map(|m| (m.path.as_str(), m.line, m.column, m.snippet.as_str()))
+        .collect();
+    assert_eq!(found, [("src/main.rs", 2, 8, "    // TODO: parse args")]);
+    assert!(!results.truncated);
+    assert!(results.commits.is_empty());
+
+    // Older revisions are searched without touching the working copy
+    command.revision = Some(first.to_string());
+    let results = handler.search_repository(&command).await.unwrap();
+    let paths: Vec<_> = results.matches.iter().map(|m| m.path.as_str()).collect();
+    assert_eq!(paths, ["src/lib.rs", "src/main.rs"]);
+
+    // Binary blobs are skipped unless .gitattributes marks them as text
+    let mut command = search_command(repo_id, "todo");
+    command.case_sensitive = false;
+    let results = handler.search_repository(&command).await.unwrap();
+    let mut paths: Vec<_> = results.matches.iter().map(|m| m.path.as_str()).collect();
+    paths.dedup();
+    assert_eq!(
+        paths,
+        [
+            "docs/notes.md",
+            "forced.raw",
+            "src/main.rs",
+            "target/out.rs"
+        ]
+    );
+    assert_eq!(results.matches.len(), 6);
+
+    command.max_results = Some(2);
+    let results = handler.search_repository(&command).await.unwrap();
+    assert_eq!(results.matches.len(), 2);
+    assert!(results.truncated);
+
+    command.pattern = "(".to_string();
+    assert!(matches!(
+        handler.search_repository(&command).await,
+        Err(GitDomainError::ValidationError(_))
+    ));
+
+    let mut command = search_command(repo_id, "TODO");
+    command.revision = Some("no-such-branch".to_string());
+    assert!(matches!(
+        handler.search_repository(&command).await,
+        Err(GitDomainError::GitOperationFailed(_))
+    ));
+
+    let command = search_command(RepositoryId::new(), "TODO");
+    assert!(matches!(
+        handler.search_repository(&command).await,
+        Err(GitDomainError::RepositoryNotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_search_repository_pickaxe() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let mut history = LinearHistory::new(&repo);
+    let mut commit = |message: &str, files: &[(&str, &str)]| history.commit(message, files);
+
+    let needles = "alpha\nneedle\nneedle\n";
+    commit("Root", &[("a.txt", "alpha\n"), ("b.txt", "beta\n")]);
+    let added = commit(
+        "Add needle",
+        &[("a.txt", "alpha\nneedle\n"), ("b.txt", "beta\n")],
+    );
+    commit(
+        "Unrelated",
+        &[("a.txt", "alpha\nneedle\n"), ("b.txt", "beta\nchanged\n")],
+    );
+    let doubled = commit(
+        "Double needle",
+        &[("a.txt", needles), ("b.txt", "beta\nchanged\n")],
+    );
+    // A pure rename keeps the count and is not reported
+    commit(
+        "Rename",
+        &[("moved.txt", needles), ("b.txt", "beta\nchanged\n")],
+    );
+    let removed = commit("Remove", &[("b.txt", "beta\nchanged\n")]);
+
+    let handler = RepositoryCommandHandler::new();
+    let (repo_id, _) = handler
+        .analyze_repository_at_path(temp_dir.path().to_str().unwrap())
+        .await
+        .unwrap();
+
+    let mut command = search_command(repo_id, "need+le");
+    command.pickaxe = true;
+    let results = handler.search_repository(&command).await.unwrap();
+    let hits: Vec<_> = results
+        .commits
+        .iter()
+        .map(|hit| {
+            (
+                hit.commit.as_str().to_string(),
+                hit.path.as_str(),
+                hit.matches_before,
+                hit.matches_after,
+            )
+        })
+        .collect();
+    assert_eq!(
+        hits,
+        [
+            (removed.to_string(), "moved.txt", 2, 0),
+            (doubled.to_string(), "a.txt", 1, 2),
+            (added.to_string(), "a.txt", 0, 1),
+        ]
+    );
+    assert!(results.matches.is_empty());
+
+    command.revision = Some(format!("{added}..HEAD"));
+    let results = handler.search_repository(&command).await.unwrap();
+    assert_eq!(results.commits.len(), 2);
+    assert!(!results.truncated);
+
+    command.max_results = Some(1);
+    let results = handler.search_repository(&command).await.unwrap();
+    assert_eq!(results.commits[0].commit.as_str(), removed.to_string());
+    assert!(results.truncated);
+
+    command.revision = Some(format!("{added}...HEAD"));
+    assert!(matches!(
+        handler.search_repository(&command).await,
+        Err(GitDomainError::ValidationError(message)) if message.contains("a..b")
+    ));
+}
+
+#[tokio::test]
+async fn test_search_repository_pickaxe_follows_attribute_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let mut history = LinearHistory::new(&repo);
+    let mut commit = |message: &str, files: &[(&str, &str)]| history.commit(message, files);
+
+    let added = commit("Add data", &[("data.txt", "needle\n")]);
+    let binary = (".gitattributes", "*.txt binary\n");
+    commit(
+        "Mark text as binary",
+        &[binary, ("data.txt", "needle\nneedle\n")],
+    );
+    commit(
+        "More data",
+        &[binary, ("data.txt", "needle\nneedle\nneedle\n")],
+    );
+    let unmarked = commit(
+        "Unmark text",
+        &[("data.txt", "needle\nneedle\nneedle\nneedle\n")],
+    );
+
+    let handler = RepositoryCommandHandler::new();
+    let (repo_id, _) = handler
+        .analyze_repository_at_path(temp_dir.path().to_str().unwrap())
+        .await
+        .unwrap();
+
+    let mut command = search_command(repo_id, "needle");
+    command.pickaxe = true;
+    let results = handler.search_repository(&command).await.unwrap();
+    let hits: Vec<_> = results
+        .commits
+        .iter()
+        .map(|hit| {
+            (
+                hit.commit.as_str().to_string(),
+                hit.matches_before,
+                hit.matches_after,
+            )
+        })
+        .collect();
+    assert_eq!(
+        hits,
+        [(unmarked.to_string(), 3, 4), (added.to_string(), 0, 1)]
+    );
+}
+
+#[tokio::test]
+async fn test_analyze_file_history_follows_renames() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let alice = Signature::now("Alice", "alice@example.com").unwrap();
+    let bob = Signature::now("Bob", "bob@example.com").unwrap();
+    let mut history = LinearHistory::new(&repo);
+    let mut commit = |author: &Signature, message: &str, files: &[(&str, &str)]| {
+        history.commit_as(author, message, files)
+    };
+
+    let added = commit(&alice, "Add file", &[("old.txt", "one\ntwo\nthree\n")]);
+    commit(
+        &bob,
+        "Unrelated",
+        &[("old.txt", "one\ntwo\nthree\n"), ("other.txt", "other\n")],
+    );
+    let modified = commit(
+        &bob,
+        "Change line",
+        &[("old.txt", "one\n2\nthree\n"), ("other.txt", "other\n")],
+    );
+    let renamed = commit(
+        &alice,
+        "Rename",
+        &[("new.txt", "one\n2\nthree\n"), ("other.txt", "other\n")],
+    );
+    let extended = commit(
+        &alice,
+        "Add line",
+        &[
+            ("new.txt", "one\n2\nthree\nfour\n"),
+            ("other.txt", "other\n"),
+        ],
+    );
+
+    let handler = RepositoryCommandHandler::new();
+    let (repo_id, _) = handler
+        .analyze_repository_at_path(temp_dir.path().to_str().unwrap())
+        .await
+        .unwrap();
+
+    let mut command = AnalyzeFileHistory {
+        repository_id: repo_id,
+        file_path: FilePath::new("new.txt").unwrap(),
+        start_commit: None,
+        end_commit: None,
+        follow_renames: true,
+    };
+    let events = handler.analyze_file_history(&command).await.unwrap();
+
+    let history = match &events[0] {
+        GitDomainEvent::FileHistoryAnalyzed(history) => history,
+        other => panic!("unexpected event {other:?}"),
+    };
+    let revisions: Vec<_> = history
+        .revisions
+        .iter()
+        .map(|r| {
+            (
+                r.commit_hash.as_str().to_string(),
+                r.change.path.as_str(),
+                r.change.change_type,
+                r.author.name.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        revisions,
+        [
+            (
+                extended.to_string(),
+                "new.txt",
+                FileChangeType::Modified,
+                "Alice"
+            ),
+            (
+                renamed.to_string(),
+                "new.txt",
+                FileChangeType::Renamed,
+                "Alice"
+            ),
+            (
+                modified.to_string(),
+                "old.txt",
+                FileChangeType::Modified,
+                "Bob"
+            ),
+            (added.to_string(), "old.txt", FileChangeType::Added, "Alice"),
+        ]
+    );
+    assert_eq!(history.end_commit.as_str(), extended.to_string());
+
+    let blame: Vec<_> = history
+        .blame
+        .iter()
+        .map(|hunk| {
+            (
+                hunk.commit_hash.as_str().to_string(),
+                hunk.author.name.as_str(),
+                hunk.start_line,
+                hunk.line_count,
+            )
+        })
+        .collect();
+    assert_eq!(
+        blame,
+        [
+            (added.to_string(), "Alice", 1, 1),
+            (modified.to_string(), "Bob", 2, 1),
+            (added.to_string(), "Alice", 3, 1),
+            (extended.to_string(), "Alice", 4, 1),
+        ]
+    );
+
+    let analyzed: Vec<_> = events[1..]
+        .iter()
+        .map(|e| match e {
+            GitDomainEvent::FileAnalyzed(file) => {
+                (file.file_path.as_str(), file.metrics.lines_of_code)
+            }
+            other => panic!("unexpected event {other:?}"),
+        })
+        .collect();
+    assert_eq!(
+        analyzed,
+        [
+            ("new.txt", 4),
+            ("new.txt", 3),
+            ("old.txt", 3),
+            ("old.txt", 3)
+        ]
+    );
+
+    // The events feed the file change projection, renames included
+    let projection = FileChangeProjection::new();
+    for event in &events {
+        projection.handle_event(event).await.unwrap();
+    }
+    let renames = projection
+        .get_rename_history(&FilePath::new("new.txt").unwrap())
+        .unwrap();
+    assert_eq!(renames.len(), 1);
+    assert_eq!(renames[0].old_path.as_str(), "old.txt");
+    assert_eq!(renames[0].commit_hash.as_str(), renamed.to_string());
+    let old_history = projection
+        .get_file_history(&FilePath::new("old.txt").unwrap())
+        .unwrap();
+    let old_commits: Vec<_> = old_history
+        .iter()
+        .map(|c| c.commit_hash.as_str().to_string())
+        .collect();
+    assert_eq!(old_commits, [modified.to_string(), added.to_string()]);
+
+    // Without following renames the history starts at the rename
+    command.follow_renames = false;
+    let events = handler.analyze_file_history(&command).await.unwrap();
+    match &events[0] {
+        GitDomainEvent::FileHistoryAnalyzed(history) => assert_eq!(history.revisions.len(), 2),
+        other => panic!("unexpected event {other:?}"),
+    }
+
+    // A bounded range only reports revisions from the start commit on
+    command.follow_renames = true;
+    command.start_commit = Some(CommitHash::new(modified.to_string()).unwrap());
+    let events = handler.analyze_file_history(&command).await.unwrap();
+    match &events[0] {
+        GitDomainEvent::FileHistoryAnalyzed(history) => {
+            assert_eq!(history.revisions.len(), 3);
+            assert_eq!(
+                history.revisions[2].commit_hash.as_str(),
+                modified.to_string()
+            );
+        }
+        other => panic!("unexpected event {other:?}"),
+    }
+
+    command.file_path = FilePath::new("old.txt").unwrap();
+    command.start_commit = None;
+    command.end_commit = Some(CommitHash::new(&modified.to_string()[..7]).unwrap());
+    let events = handler.analyze_file_history(&command).await.unwrap();
+    match &events[0] {
+        GitDomainEvent::FileHistoryAnalyzed(history) => {
+            assert_eq!(history.revisions.len(), 2);
+            assert_eq!(history.blame.len(), 3);
+        }
+        other => panic!("unexpected event {other:?}"),
+    }
+
+    command.start_commit = Some(CommitHash::new(extended.to_string()).unwrap());
+    assert!(matches!(
+        handler.analyze_file_history(&command).await,
+        Err(GitDomainError::ValidationError(_))
+    ));
+
+    command.start_commit = None;
+    command.end_commit = Some(CommitHash::new("deadbee").unwrap());
+    assert!(matches!(
+        handler.analyze_file_history(&command).await,
+        Err(GitDomainError::InvalidCommitHash(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_analyze_file_history_follows_renames_across_merges() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let (head, side) = (Some("HEAD"), Some("refs/heads/side"));
+    let added = commit_files_at(
+        &repo,
+        head,
+        0,
+        &[],
+        &[("old.txt", "one\ntwo\nthree\n")],
+        "Add file",
+    );
+    let renamed = commit_files_at(
+        &repo,
+        head,
+        1,
+        &[added],
+        &[("new.txt", "one\ntwo\nthree\n")],
+        "Rename",
+    );
+    // A branch forked before the rename keeps editing the old name
+    let edited = commit_files_at(
+        &repo,
+        side,
+        2,
+        &[added],
+        &[("old.txt", "one\n2\nthree\n")],
+        "Change line",
+    );
+    let merged = commit_files_at(
+        &repo,
+        head,
+        3,
+        &[renamed, edited],
+        &[("new.txt", "one\n2\nthree\n")],
+        "Merge branch 'side'",
+    );
+    let extended = commit_files_at(
+        &repo,
+        head,
+        4,
+        &[merged],
+        &[("new.txt", "one\n2\nthree\nfour\n")],
+        "Add line",
+    );
+
+    let handler = RepositoryCommandHandler::new();
+    let (repo_id, _) = handler
+        .analyze_repository_at_path(temp_dir.path().to_str().unwrap())
+        .await
+        .unwrap();
+    let command = AnalyzeFileHistory {
+        repository_id: repo_id,
+        file_path: FilePath::new("new.txt").unwrap(),
+        start_commit: None,
+        end_commit: None,
+        follow_renames: true,
+    };
+    let events = handler.analyze_file_history(&command).await.unwrap();
+
+    let history = match &events[0] {
+        GitDomainEvent::FileHistoryAnalyzed(history) => history,
+        other => panic!("unexpected event {other:?}"),
+    };
+    let revisions: Vec<_> = history
+        .revisions
+        .iter()
+        .map(|r| (r.commit_hash.as_str().to_string(), r.change.path.as_str()))
+        .collect();
+    assert_eq!(
+        revisions,
+        [
+            (extended.to_string(), "new.txt"),
+            (edited.to_string(), "old.txt"),
+            (renamed.to_string(), "new.txt"),
+            (added.to_string(), "old.txt"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_blame_weighted_code_ownership() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let people: Vec<_> = ["Alice", "Bob", "Carol", "Dave"]
+        .iter()
+        .map(|name| AuthorInfo::new(*name, format!("{}@example.com", name.to_lowercase())))
+        .collect();
+    let function = |name: &str| format!("fn {name}_function_with_a_long_name() {{}}\n");
+    let (alpha, beta, gamma, delta) = (
+        function("alpha"),
+        function("beta"),
+        function("gamma"),
+        function("delta"),
+    );
+    let indented_alpha = format!("    {alpha}");
+
+    let mut history = LinearHistory::new(&repo);
+    let mut commit = |author: &AuthorInfo, lines: &[&str]| {
+        let sig = Signature::now(&author.name, &author.email).unwrap();
+        history.commit_as(&sig, "Change", &[("lib.rs", lines.concat().as_str())])
+    };
+
+    commit(&people[0], &[&alpha, &beta, &gamma]);
+    // Bob re-indents alpha and adds delta
+    commit(&people[1], &[&indented_alpha, &beta, &gamma, &delta]);
+    // Carol moves gamma to the top
+    commit(&people[2], &[&gamma, &indented_alpha, &beta, &delta]);
+    // Dave swaps beta and delta in a reordering commit
+    let reorder = commit(&people[3], &[&gamma, &indented_alpha, &delta, &beta]);
+
+    let now = Utc::now();
+    let lib = FilePath::new("lib.rs").unwrap();
+    let gone = FilePath::new("gone.rs").unwrap();
+    let mut file_commits = HashMap::new();
+    file_commits.insert(
+        lib.clone(),
+        people.iter().map(|author| (author.clone(), now)).collect(),
+    );
+    file_commits.insert(gone.clone(), vec![(people[0].clone(), now)]);
+
+    let analyzer = CollaborationAnalyzer::new();
+    let repo_id = RepositoryId::new();
+    let ownership_of = |events: &[CodeOwnershipCalculated], path: &FilePath| {
+        events.iter().find(|e| &e.path == path).unwrap().clone()
+    };
+
+    // Plain blame credits the re-indent, the move and the reordering
+    let events = analyzer
+        .calculate_blame_ownership(
+            repo_id,
+            &repo,
+            &file_commits,
+            &BlameOwnershipOptions::default(),
+        )
+        .unwrap();
+    let plain = ownership_of(&events, &lib);
+    assert_eq!(plain.total_commits, 4);
+    assert!((plain.ownership_percentage - 0.25).abs() < f64::EPSILON);
+    assert_eq!(plain.total_lines, 4);
+    assert!(plain.line_owner.is_some());
+    assert!(plain.line_ownership_percentage.unwrap() <= 0.5);
+    let missing = ownership_of(&events, &gone);
+    assert_eq!(missing.line_owner, None);
+    assert_eq!(missing.total_lines, 0);
+
+    fs::write(
+        temp_dir.path().join(".git-blame-ignore-revs"),
+        format!("# Reordering only\n{reorder}\n"),
+    )
+    .unwrap();
+    let options = BlameOwnershipOptions::default()
+        .ignore_whitespace()
+        .detect_moves()
+        .with_ignore_revs_file(".git-blame-ignore-revs");
+    let events = analyzer
+        .calculate_blame_ownership(repo_id, &repo, &file_commits, &options)
+        .unwrap();
+    let weighted = ownership_of(&events, &lib);
+    assert_eq!(weighted.line_owner.as_ref(), Some(&people[0]));
+    assert_eq!(weighted.line_ownership_percentage, Some(0.75));
+    assert_eq!(weighted.line_contributors, [(people[1].clone(), 0.25)]);
+    assert_eq!(weighted.total_lines, 4);
+    assert_eq!(weighted.total_commits, 4);
+
+    fs::write(
+        temp_dir.path().join(".git-blame-ignore-revs"),
+        "not-a-commit\n",
+    )
+    .unwrap();
+    assert!(matches!(
+        analyzer.calculate_blame_ownership(repo_id, &repo, &file_commits, &options),
+        Err(GitDomainError::InvalidCommitHash(_))
+    ));
+
+    let options = options.with_ignore_revs_file("missing-file");
+    assert!(matches!(
+        analyzer.calculate_blame_ownership(repo_id, &repo, &file_commits, &options),
+        Err(GitDomainError::GitOperationFailed(_))
+    ));
+}
+
+const CODEOWNERS: &str = r"# Global owners
+*                @alice
+*.md             docs@example.com
+docs/*           @bob
+/src/generated/
+\#notes.txt      @carol
+
+[Backend] @org/backend
+src/
+src/legacy/      @dave  # until the rewrite lands
+";
+
+#[tokio::test]
+async fn test_codeowners_parsing() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let readme = ("README.md", "# Readme\n");
+    let first = commit_files_at(&repo, Some("HEAD"), 0, &[], &[readme], "Initial");
+    commit_files_at(
+        &repo,
+        Some("HEAD"),
+        1,
+        &[first],
+        &[readme, ("CODEOWNERS", CODEOWNERS)],
+        "Add owners",
+    );
+
+    assert!(CodeOwners::load(&repo, "HEAD~1").unwrap().is_none());
+    let codeowners = CodeOwners::load(&repo, "HEAD").unwrap().unwrap();
+
+    // Last match wins within a section, sections add up
+    assert_eq!(codeowners.owners_of("README.md"), ["docs@example.com"]);
+    assert_eq!(codeowners.owners_of("docs/guide.txt"), ["@bob"]);
+    assert_eq!(codeowners.owners_of("docs/api/index.txt"), ["@alice"]);
+    assert_eq!(
+        codeowners.owners_of("src/generated/api.rs"),
+        ["@org/backend"]
+    );
+    assert_eq!(
+        codeowners.owners_of("src/legacy/old.rs"),
+        ["@alice", "@dave"]
+    );
+    assert_eq!(codeowners.owners_of("#notes.txt"), ["@carol"]);
+
+    let alice = AuthorInfo::new("Alice", "alice@example.com");
+    assert!(codeowners.is_owner("@alice", &alice));
+    assert!(codeowners.is_owner("ALICE@example.com", &alice));
+    assert!(!codeowners.is_owner("@org/backend", &alice));
+    assert!(!codeowners.resolves("@org/backend"));
+    let codeowners = codeowners.with_alias("@org/backend", ["alice@example.com"]);
+    assert!(codeowners.is_owner("@org/backend", &alice));
+
+    // GitLab optional sections and approval counts
+    let gitlab =
+        CodeOwners::parse("^[Docs][2] @writers\n*.md\n[docs]\nguide.md @editor\n").unwrap();
+    assert_eq!(gitlab.owners_of("README.md"), ["@writers"]);
+    assert_eq!(gitlab.owners_of("guide.md"), ["@editor"]);
+
+    assert!(matches!(
+        CodeOwners::parse("[Docs\n*.md @writers\n"),
+        Err(GitDomainError::ValidationError(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_ownership_drift_detection() {
+    let author = |name: &str| AuthorInfo::new(name, format!("{}@example.com", name.to_lowercase()));
+    let (alice, carol, erin) = (author("Alice"), author("Carol"), author("Erin"));
+    let docs_bot = AuthorInfo::new("Docs Bot", "docs@example.com");
+
+    let now = Utc::now();
+    let mut file_commits = HashMap::new();
+    for (path, authors) in [
+        ("src/legacy/old.rs", vec![&alice, &alice, &carol]),
+        ("src/main.rs", vec![&erin]),
+        ("README.md", vec![&docs_bot]),
+        ("docs/guide.txt", vec![&carol]),
+    ] {
+        file_commits.insert(
+            FilePath::new(path).unwrap(),
+            authors
+                .into_iter()
+                .map(|a| (a.clone(), now))
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    let analyzer = CollaborationAnalyzer::new();
+    let repo_id = RepositoryId::new();
+    let ownership = analyzer.calculate_ownership(repo_id, &file_commits);
+    let drift = |codeowners: &CodeOwners| {
+        let mut drifts = analyzer.detect_ownership_drift(repo_id, codeowners, &ownership);
+        drifts.sort_by(|a, b| a.path.as_str().cmp(b.path.as_str()));
+        drifts
+            .into_iter()
+            .map(|d| {
+                (
+                    d.path.as_str().to_string(),
+                    d.inactive_owners,
+                    d.top_contributor.name,
+                    d.top_contributor_listed,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let strings = |owners: &[&str]| owners.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+    // The unresolved team may include Erin, so src/main.rs only drifts on Alice
+    let codeowners = CodeOwners::parse(CODEOWNERS).unwrap();
+    assert_eq!(
+        drift(&codeowners),
+        [
+            (
+                "docs/guide.txt".to_string(),
+                strings(&["@bob"]),
+                "Carol".to_string(),
+                false
+            ),
+            (
+                "src/legacy/old.rs".to_string(),
+                strings(&["@dave"]),
+                "Alice".to_string(),
+                true
+            ),
+            (
+                "src/main.rs".to_string(),
+                strings(&["@alice"]),
+                "Erin".to_string(),
+                true
+            ),
+        ]
+    );
+
+    // Once the team resolves, Erin is known not to be listed
+    let codeowners = codeowners.with_alias("@org/backend", ["bob@example.com"]);
+    let drifts = drift(&codeowners);
+    assert_eq!(
+        drifts[2],
+        (
+            "src/main.rs".to_string(),
+            strings(&["@alice", "@org/backend"]),
+            "Erin".to_string(),
+            false
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_mailmap_and_alias_identity_resolution() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let jane = AuthorInfo::new("Jane Doe", "jane@example.com");
+    let old_jane = AuthorInfo::new("jd", "jd@old.example.com");
+    let laptop_jane = AuthorInfo::new("Jane", "jane@laptop.local");
+    let john = AuthorInfo::new("John", "john@example.com");
+
+    let mut history = LinearHistory::new(&repo);
+    let mut content = String::new();
+    for (day, author) in [&jane, &old_jane, &laptop_jane, &john]
+        .into_iter()
+        .enumerate()
+    {
+        content.push_str(&format!("line by {}\n", author.email));
+        let time = Time::new(BASE_TIME + i64::try_from(day).unwrap() * 86_400, 0);
+        let sig = Signature::new(&author.name, &author.email, &time).unwrap();
+        history.commit_as(&sig, "Change", &[("lib.rs", content.as_str())]);
+    }
+    fs::write(
+        temp_dir.path().join(".mailmap"),
+        "# Old address\nJane Doe <jane@example.com> <jd@old.example.com>\n",
+    )
+    .unwrap();
+
+    let handler = RepositoryCommandHandler::new().with_identity_resolver(
+        AliasRules::new().with_email_alias("jane@laptop.local", jane.clone()),
+    );
+    let (repo_id, events) = handler
+        .analyze_repository_at_path(temp_dir.path().to_str().unwrap())
+        .await
+        .unwrap();
+
+    // Oldest first: the mailmap and the alias rule both resolve to Jane
+    let mut identities: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            GitDomainEvent::CommitAnalyzed(commit) => {
+                Some((commit.author.clone(), commit.raw_author.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+    identities.reverse();
+    assert_eq!(
+        identities,
+        [
+            (jane.clone(), None),
+            (jane.clone(), Some(old_jane)),
+            (jane.clone(), Some(laptop_jane.clone())),
+            (john.clone(), None),
+        ]
+    );
+
+    // Analyzers fed from the events count Jane once
+    let mut file_commits: HashMap<FilePath, Vec<(AuthorInfo, DateTime<Utc>)>> = HashMap::new();
+    for event in &events {
+        if let GitDomainEvent::CommitAnalyzed(commit) = event {
+            for change in &commit.files_changed {
+                file_commits
+                    .entry(change.path.clone())
+                    .or_default()
+                    .push((commit.author.clone(), commit.commit_timestamp));
+            }
+        }
+    }
+    let ownership = CollaborationAnalyzer::new().calculate_ownership(repo_id, &file_commits);
+    assert_eq!(ownership.len(), 1);
+    assert_eq!(ownership[0].primary_owner, jane);
+    assert!((ownership[0].ownership_percentage - 0.75).abs() < f64::EPSILON);
+
+    // Blamed lines resolve through the mailmap and the same alias rules
+    let ownership = CollaborationAnalyzer::new()
+        .with_identity_resolver(
+            AliasRules::new().with_email_alias("jane@laptop.local", jane.clone()),
+        )
+        .calculate_blame_ownership(
+            repo_id,
+            &repo,
+            &file_commits,
+            &BlameOwnershipOptions::default(),
+        )
+        .unwrap();
+    assert_eq!(ownership[0].line_owner, Some(jane.clone()));
+    assert_eq!(
+        ownership[0].line_owner,
+        Some(ownership[0].primary_owner.clone())
+    );
+    assert!((ownership[0].line_ownership_percentage.unwrap() - 0.75).abs() < f64::EPSILON);
+    assert_eq!(ownership[0].line_contributors, vec![(john.clone(), 0.25)]);
+
+    // File history and its blame snapshot resolve identities the same way
+    let command = AnalyzeFileHistory {
+        repository_id: repo_id,
+        file_path: FilePath::new("lib.rs").unwrap(),
+        start_commit: None,
+        end_commit: None,
+        follow_renames: false,
+    };
+    let events = handler.analyze_file_history(&command).await.unwrap();
+    let history = match &events[0] {
+        GitDomainEvent::FileHistoryAnalyzed(history) => history,
+        other => panic!("unexpected event {other:?}"),
+    };
+    assert_eq!(history.revisions.len(), 4);
+    assert_eq!(history.revisions[1].author, jane);
+    assert_eq!(history.revisions[1].raw_author, Some(laptop_jane));
+    let blamed: Vec<_> = history.blame.iter().map(|hunk| &hunk.author).collect();
+    assert!(!blamed.is_empty());
+    assert!(blamed
+        .iter()
+        .all(|author| **author == jane || **author == john));
+}
+
+#[tokio::test]
+async fn test_commit_relationships_detected_during_analysis() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let main = Some("refs/heads/main");
+    let feature = Some("refs/heads/feature");
+    let a = commit_files_at(&repo, main, 0, &[], &[("a.txt", "1\n")], "Add a");
+    let b = commit_files_at(&repo, main, 1, &[a], &[("a.txt", "1\n2\n")], "Add line 2");
+    let c = commit_files_at(
+        &repo,
+        main,
+        2,
+        &[b],
+        &[("a.txt", "1\n")],
+        &format!("Revert \"Add line 2\"\n\nThis reverts commit {b}.\n"),
+    );
+    let d = commit_files_at(
+        &repo,
+        main,
+        3,
+        &[c],
+        &[("a.txt", "1\n"), ("b.txt", "x\n")],
+        &format!(
+            "Add b\n\nBuilds on {} from issue 1234567.\n",
+            &a.to_string()[..7]
+        ),
+    );
+    repo.branch("feature", &repo.find_commit(a).unwrap(), false)
+        .unwrap();
+    let e = commit_files_at(
+        &repo,
+        feature,
+        4,
+        &[a],
+        &[("a.txt", "1\n2\n")],
+        &format!("Add line 2\n\n(cherry picked from commit {b})\n"),
+    );
+    // Picked without -x, so only the patch gives it away
+    let f = commit_files_at(
+        &repo,
+        feature,
+        5,
+        &[e],
+        &[("a.txt", "1\n2\n"), ("b.txt", "x\n")],
+        "Add b",
+    );
+    let g = commit_files_at(
+        &repo,
+        feature,
+        6,
+        &[f],
+        &[("a.txt", "1\n2\n"), ("b.txt", "x\n"), ("c.txt", "c\n")],
+        &format!("Add c\n\nFixes: {}\n", &c.to_string()[..12]),
+    );
+    repo.set_head("refs/heads/main").unwrap();
+
+    let handler = RepositoryCommandHandler::new();
+    let (_, events) = handler
+        .analyze_repository_with_options(
+            temp_dir.path().to_str().unwrap(),
+            AnalysisOptions::default().with_ref("refs/heads/*"),
+        )
+        .await
+        .unwrap();
+
+    let mut relationships: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            GitDomainEvent::CommitRelationshipDetected(relationship) => Some((
+                relationship.source_commit.as_str().to_string(),
+                relationship.target_commit.as_str().to_string(),
+                format!("{:?}", relationship.relationship_type),
+            )),
+            _ => None,
+        })
+        .collect();
+    relationships.sort();
+
+    let mut expected: Vec<_> = [
+        (b, c, CommitRelationshipType::Revert),
+        (a, d, CommitRelationshipType::References),
+        (b, e, CommitRelationshipType::CherryPick),
+        (d, f, CommitRelationshipType::CherryPick),
+        (c, g, CommitRelationshipType::Fixes),
+    ]
+    .into_iter()
+    .map(|(source, target, relationship_type)| {
+        (
+            source.to_string(),
+            target.to_string(),
+            format!("{relationship_type:?}"),
+        )
+    })
+    .collect();
+    expected.sort();
+
+    assert_eq!(relationships, expected);
+}
+
+#[tokio::test]
+async fn test_analysis_parses_commit_messages() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    commit_files_at(
+        &repo,
+        Some("HEAD"),
+        0,
+        &[],
+        &[("a.txt", "a\n")],
+        "feat(api)!: Replace search endpoint\n\nCloses #4 and PROJ-7.\n\n\
+         Reviewed-by: Jane Doe <jane@example.com>\n",
+    );
+
+    let handler = RepositoryCommandHandler::new();
+    let (_, events) = handler
+        .analyze_repository_with_options(
+            temp_dir.path().to_str().unwrap(),
+            AnalysisOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let parsed = events
+        .iter()
+        .find_map(|event| match event {
+            GitDomainEvent::CommitAnalyzed(commit) => Some(commit.parsed_message.clone()),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(parsed.change_type(), Some("feat"));
+    assert_eq!(
+        parsed.conventional.as_ref().unwrap().scope.as_deref(),
+        Some("api")
+    );
+    assert!(parsed.breaking);
+    assert_eq!(
+        parsed
+            .issue_references
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        vec!["#4", "PROJ-7"]
+    );
+    assert_eq!(
+        parsed.trailer("Reviewed-by"),
+        vec!["Jane Doe <jane@example.com>"]
+    );
+}
+
+#[tokio::test]
+async fn test_release_notes_between_tags() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let mut history = LinearHistory::new(&repo);
+    let mut commit = |message: &str| history.commit(message, &[("a.txt", message)]);
+    let tag = |name: &str, oid: Oid| {
+        repo.tag_lightweight(name, &repo.find_object(oid, None).unwrap(), false)
+            .unwrap();
+    };
+
+    tag("v0.9.0", commit("feat: Initial version"));
+    tag("v1.0.0", commit("chore: Release 1.0.0"));
+    let fix = commit("fix(parser): Handle empty input\n\nCloses #3");
+    commit("docs: Describe the parser");
+    tag("v1.1.0-rc.1", commit("feat(api): Add search"));
+    commit("Tidy up");
+    // Release tags need not point to commits
+    tag("v9.0.0", repo.blob(b"not a release").unwrap());
+
+    let handler = RepositoryCommandHandler::new();
+    let (repo_id, _) = handler
+        .analyze_repository_at_path(temp_dir.path().to_str().unwrap())
+        .await
+        .unwrap();
+
+    // Up to HEAD from the latest release, skipping the pre-release
+    let command = GenerateReleaseNotes {
+        repository_id: repo_id,
+        from_tag: None,
+        to_tag: None,
+    };
+    let (notes, events) = handler.generate_release_notes(&command).await.unwrap();
+    assert_eq!(notes.from_tag, Some(TagName::new("v1.0.0").unwrap()));
+    assert_eq!(
+        notes.previous_version.as_ref().unwrap().to_string(),
+        "1.0.0"
+    );
+    assert_eq!(notes.bump, VersionBump::Minor);
+    assert_eq!(notes.next_version.as_ref().unwrap().to_string(), "1.1.0");
+    let titles: Vec<&str> = notes.sections.iter().map(|s| s.title.as_str()).collect();
+    assert_eq!(
+        titles,
+        vec!["Features", "Bug Fixes", "Documentation", "Other Changes"]
+    );
+
+    let fix_hash = fix.to_string();
+    let markdown = match events.as_slice() {
+        [GitDomainEvent::ReleaseNotesGenerated(generated)] => generated.markdown.clone(),
+        other => panic!("unexpected events: {other:?}"),
+    };
+    assert!(markdown.starts_with("## 1.1.0\n\n### Features\n\n- **api:** Add search ("));
+    assert!(markdown.contains(&format!(
+        "- **parser:** Handle empty input (#3) ({})\n",
+        &fix_hash[..7]
+    )));
+    assert!(markdown.contains("### Other Changes\n\n- Tidy up ("));
+    let json: serde_json::Value = serde_json::from_str(&notes.to_json().unwrap()).unwrap();
+    assert_eq!(json["bump"], "Minor");
+
+    // A breaking change before 1.0.0 only bumps the minor version
+    let head = commit("refactor!: Drop the v0 parser\n\nBREAKING CHANGE: v0 input is rejected");
+    tag("v2.0.0", head);
+    let command = GenerateReleaseNotes {
+        repository_id: repo_id,
+        from_tag: Some(TagName::new("v0.9.0").unwrap()),
+        to_tag: Some(TagName::new("v2.0.0").unwrap()),
+    };
+    let (notes, _) = handler.generate_release_notes(&command).await.unwrap();
+    assert_eq!(notes.bump, VersionBump::Minor);
+    assert_eq!(notes.next_version.as_ref().unwrap().to_string(), "0.10.0");
+    assert_eq!(notes.to_commit.as_str(), head.to_string());
+    assert_eq!(notes.sections[0].title, "Breaking Changes");
+    assert!(notes
+        .to_markdown()
+        .starts_with("## v2.0.0\n\n### Breaking Changes\n\n- v0 input is rejected ("));
+
+    // From the latest release the same change is a major bump
+    let command = GenerateReleaseNotes {
+        repository_id: repo_id,
+        from_tag: None,
+        to_tag: Some(TagName::new("v2.0.0").unwrap()),
+    };
+    let (notes, _) = handler.generate_release_notes(&command).await.unwrap();
+    assert_eq!(notes.bump, VersionBump::Major);
+    assert_eq!(notes.next_version.as_ref().unwrap().to_string(), "2.0.0");
+
+    let command = GenerateReleaseNotes {
+        repository_id: repo_id,
+        from_tag: Some(TagName::new("v3.0.0").unwrap()),
+        to_tag: None,
+    };
+    assert!(matches!(
+        handler.generate_release_notes(&command).await,
+        Err(GitDomainError::TagNotFound(tag)) if tag == "v3.0.0"
+    ));
+}
+
+#[tokio::test]
+async fn test_analysis_discovers_tags() {
+    let temp_dir = create_test_repo();
+    let path = temp_dir.path().to_str().unwrap();
+    let repo = Repository::open(temp_dir.path()).unwrap();
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    let root = head.parent(0).unwrap();
+    let tagger = Signature::new(
+        "Release Bot",
+        "release@example.com",
+        &Time::new(BASE_TIME, 0),
+    )
+    .unwrap();
+    repo.tag_lightweight("v1.0.0", root.as_object(), false)
+        .unwrap();
+    repo.tag(
+        "v1.1.0",
+        head.as_object(),
+        &tagger,
+        "Minor release\n",
+        false,
+    )
+    .unwrap();
+    // Tags of anything but a commit are left out
+    repo.tag_lightweight("tree", &head.tree().unwrap().into_object(), false)
+        .unwrap();
+
+    let handler = RepositoryCommandHandler::new();
+    let (repo_id, events) = handler.analyze_repository_at_path(path).await.unwrap();
+    let projection = TagProjection::new();
+    for event in &events {
+        projection.handle_event(event).unwrap();
+    }
+    let tags: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            GitDomainEvent::TagCreated(tag) => Some(tag),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(tags.len(), 2);
+    assert_eq!(tags[0].tag_name.as_str(), "v1.0.0");
+    assert_eq!(tags[0].commit_hash.as_str(), root.id().to_string());
+    assert!(!tags[0].annotated);
+    assert!(tags[0].tagger.is_none());
+    assert_eq!(tags[1].tag_name.as_str(), "v1.1.0");
+    assert_eq!(tags[1].commit_hash.as_str(), head.id().to_string());
+    assert!(tags[1].annotated);
+    assert_eq!(tags[1].message.as_deref(), Some("Minor release"));
+    assert_eq!(
+        tags[1].tagger.as_ref().unwrap().email,
+        "release@example.com"
+    );
+
+    let listed: Vec<_> = projection
+        .get_tags(&repo_id)
+        .unwrap()
+        .into_iter()
+        .map(|tag| tag.name.to_string())
+        .collect();
+    assert_eq!(listed, ["v1.1.0", "v1.0.0"]);
+
+    // Re-analysis reports moved and removed tags only
+    repo.tag_delete("v1.0.0").unwrap();
+    repo.tag("v1.1.0", root.as_object(), &tagger, "Minor release\n", true)
+        .unwrap();
+    let (_, events) = handler.analyze_repository_at_path(path).await.unwrap();
+    let changes: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            GitDomainEvent::TagCreated(tag) => {
+                Some(format!("created {} {}", tag.tag_name, tag.commit_hash))
+            }
+            GitDomainEvent::TagDeleted(tag) => {
+                Some(format!("deleted {} {}", tag.tag_name, tag.last_commit))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            format!("deleted v1.1.0 {}", head.id()),
+            format!("created v1.1.0 {}", root.id()),
+            format!("deleted v1.0.0 {}", root.id()),
+        ]
+    );
+
+    let aggregate = handler.get_repository(&repo_id).unwrap();
+    assert_eq!(aggregate.tags.len(), 1);
+    assert_eq!(
+        aggregate.tags[&TagName::new("v1.1.0").unwrap()].as_str(),
+        root.id().to_string()
+    );
+}
+
+/// Sign `data` with an SSH key as `git commit -S` does for SSH keys
+fn ssh_sign(dir: &Path, key: &Path, data: &[u8]) -> String {
+    let payload = dir.join("payload");
+    let signature = dir.join("payload.sig");
+    fs::write(&payload, data).unwrap();
+    // ssh-keygen asks before overwriting an earlier signature
+    let _ = fs::remove_file(&signature);
+    let status = std::process::Command::new("ssh-keygen")
+        .args(["-q", "-Y", "sign", "-n", "git", "-f"])
+        .arg(key)
+        .arg(&payload)
+        .stdin(std::process::Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success());
+    fs::read_to_string(signature).unwrap()
+}
+
+#[tokio::test]
+async fn test_signed_commits_and_tags() {
+    let keys = TempDir::new().unwrap();
+    let key = keys.path().join("id_ed25519");
+    // Signing needs ssh-keygen, which not every environment has
+    let Ok(generated) = std::process::Command::new("ssh-keygen")
+        .args(["-q", "-t", "ed25519", "-N", "", "-C", "dev", "-f"])
+        .arg(&key)
+        .status()
+    else {
+        return;
+    };
+    assert!(generated.success());
+    let public_key = fs::read_to_string(keys.path().join("id_ed25519.pub")).unwrap();
+    let allowed_signers = keys.path().join("allowed_signers");
+    fs::write(
+        &allowed_signers,
+        format!("dev@example.com namespaces=\"git\" {public_key}"),
+    )
+    .unwrap();
+
+    let temp_dir = create_test_repo();
+    let path = temp_dir.path().to_str().unwrap();
+    let repo = Repository::open(temp_dir.path()).unwrap();
+    let head = repo.head().unwrap();
+    let branch = head.shorthand().unwrap().to_string();
+    let parent = head.peel_to_commit().unwrap();
+    let sig = Signature::new("Dev", "dev@example.com", &Time::new(BASE_TIME, 0)).unwrap();
+    let buffer = repo
+        .commit_create_buffer(
+            &sig,
+            &sig,
+            "Signed change",
+            &parent.tree().unwrap(),
+            &[&parent],
+        )
+        .unwrap();
+    let content = buffer.as_str().unwrap();
+    let signed = repo
+        .commit_signed(
+            content,
+            &ssh_sign(keys.path(), &key, content.as_bytes()),
+            None,
+        )
+        .unwrap();
+    repo.reference(
+        &format!("refs/heads/{branch}"),
+        signed,
+        true,
+        "signed commit",
+    )
+    .unwrap();
+
+    let tag = format!(
+        "object {signed}\ntype commit\ntag v1.0.0\ntagger Dev <dev@example.com> {BASE_TIME} +0000\n\nRelease\n"
+    );
+    let tag_signature = ssh_sign(keys.path(), &key, tag.as_bytes());
+    let tag_id = repo
+        .odb()
+        .unwrap()
+        .write(
+            git2::ObjectType::Tag,
+            format!("{tag}{tag_signature}").as_bytes(),
+        )
+        .unwrap();
+    repo.reference("refs/tags/v1.0.0", tag_id, false, "signed tag")
+        .unwrap();
+
+    let handler = RepositoryCommandHandler::new()
+        .with_signature_verifier(SignatureVerifier::new().with_allowed_signers(&allowed_signers));
+    let (repo_id, events) = handler.analyze_repository_at_path(path).await.unwrap();
+    let mut signed_commits = 0;
+    for event in &events {
+        match event {
+            GitDomainEvent::CommitAnalyzed(commit)
+                if commit.commit_hash.as_str() == signed.to_string() =>
+            {
+                let signature = commit.signature.as_ref().unwrap();
+                assert_eq!(signature.format, SignatureFormat::Ssh);
+                assert_eq!(signature.status, SignatureStatus::Good);
+                assert_eq!(signature.signer.as_deref(), Some("dev@example.com"));
+                assert!(signature.key.as_ref().unwrap().starts_with("SHA256:"));
+                signed_commits += 1;
+            }
+            GitDomainEvent::CommitAnalyzed(commit) => assert!(commit.signature.is_none()),
+            GitDomainEvent::TagCreated(tag) => {
+                assert_eq!(tag.message.as_deref(), Some("Release"));
+                assert!(tag.signature.as_ref().unwrap().is_good());
+            }
+            _ => {}
+        }
+    }
+    assert_eq!(signed_commits, 1);
+
+    // Only the signed commit satisfies the policy
+    let mut command = CheckSignaturePolicy {
+        repository_id: repo_id,
+        policy: SignaturePolicy::new([branch.as_str()]).require_verified(),
+        max_commits: None,
+    };
+    let (report, events) = handler.check_signature_policy(&command).await.unwrap();
+    assert_eq!(report.branches, [BranchName::new(&branch).unwrap()]);
+    assert_eq!(report.commits_checked, 3);
+    assert_eq!(report.violations.len(), 2);
+    assert!(report.violations.iter().all(|v| v.signature.is_none()));
+    assert!(matches!(
+        &events[..],
+        [GitDomainEvent::SignaturePolicyChecked(_)]
+    ));
+
+    command.max_commits = Some(1);
+    let (report, _) = handler.check_signature_policy(&command).await.unwrap();
+    assert!(report.is_compliant());
+
+    // A signer missing from the allowed signers is reported, not trusted
+    fs::write(&allowed_signers, "").unwrap();
+    let untrusted = RepositoryCommandHandler::new()
+        .with_signature_verifier(SignatureVerifier::new().with_allowed_signers(&allowed_signers));
+    let (repo_id, _) = untrusted.analyze_repository_at_path(path).await.unwrap();
+    command.repository_id = repo_id;
+    let (report, _) = untrusted.check_signature_policy(&command).await.unwrap();
+    assert!(!report.is_compliant());
+    let signature = report.violations[0].signature.as_ref().unwrap();
+    assert_eq!(signature.status, SignatureStatus::UnknownKey);
+
+    // Without a verifier a signature only has to be present
+    let unverified = RepositoryCommandHandler::new();
+    let (repo_id, events) = unverified.analyze_repository_at_path(path).await.unwrap();
+    assert!(events.iter().any(|event| matches!(
+        event,
+        GitDomainEvent::CommitAnalyzed(commit)
+            if commit.signature.as_ref().is_some_and(|s| s.status == SignatureStatus::Unverified)
+    )));
+    command.repository_id = repo_id;
+    command.policy.require_verified = false;
+    let (report, _) = unverified.check_signature_policy(&command).await.unwrap();
+    assert!(report.is_compliant());
+
+    // A signature over other content is a violation even then
+    fs::write(
+        &allowed_signers,
+        format!("dev@example.com namespaces=\"git\" {public_key}"),
+    )
+    .unwrap();
+    let signed_commit = repo.find_commit(signed).unwrap();
+    let buffer = |message: &str| {
+        repo.commit_create_buffer(
+            &sig,
+            &sig,
+            message,
+            &signed_commit.tree().unwrap(),
+            &[&signed_commit],
+        )
+        .unwrap()
+        .as_str()
+        .unwrap()
+        .to_string()
+    };
+    let tampered = repo
+        .commit_signed(
+            &buffer("Tampered change"),
+            &ssh_sign(keys.path(), &key, buffer("Reviewed change").as_bytes()),
+            None,
+        )
+        .unwrap();
+    repo.reference(
+        &format!("refs/heads/{branch}"),
+        tampered,
+        true,
+        "tampered commit",
+    )
+    .unwrap();
+
+    let (repo_id, _) = handler.analyze_repository_at_path(path).await.unwrap();
+    command.repository_id = repo_id;
+    command.max_commits = Some(1);
+    let (report, _) = handler.check_signature_policy(&command).await.unwrap();
+    assert!(!command.policy.require_verified);
+    assert_eq!(report.violations.len(), 1);
+    assert_eq!(
+        report.violations[0].signature.as_ref().unwrap().status,
+        SignatureStatus::Bad
+    );
+}
@@ -158,6 +158,7 @@ async fn test_event_store_append_and_replay() {
             branch_name: cim_domain_git::value_objects::BranchName::new("feature/test").unwrap(),
             commit_hash: cim_domain_git::value_objects::CommitHash::new("abc1234").unwrap(),
             source_branch: None,
+            checked_out: false,
             timestamp: Utc::now(),
         }),
     ];
@@ -308,8 +309,13 @@ async fn test_projection_updates() {
                 name: format!("Test Author {}", i),
                 email: "test@example.com".to_string(),
             },
+            raw_author: None,
             message: format!("Test commit {}", i),
+            parsed_message: Default::default(),
+            signature: None,
             files_changed: vec![],
+            parent_diffs: vec![],
+            combined_files: vec![],
             commit_timestamp: Utc::now(),
             timestamp: Utc::now(),
         });
@@ -421,6 +427,7 @@ async fn test_correlation_tracking() {
         branch_name: cim_domain_git::value_objects::BranchName::new("main").unwrap(),
         commit_hash: cim_domain_git::value_objects::CommitHash::new("def456").unwrap(),
         source_branch: None,
+        checked_out: false,
         timestamp: Utc::now(),
     });
 